@@ -0,0 +1,402 @@
+use crate::{logging, user_agent};
+use reqwest::header::HeaderMap;
+use reqwest::{RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub(crate) const REPLAY_DIR_ENV_VAR: &str = "GEN_ARTICLE_HTTP_REPLAY_DIR";
+
+const REDACTED_HEADERS: &[&str] = &["authorization", "api-key", "x-api-key", "cookie"];
+const REDACTED_QUERY_PARAMS: &[&str] = &["key", "api_key"];
+
+static RECORDING_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+static REPLAYED_FILES: Mutex<Option<HashSet<PathBuf>>> = Mutex::new(None);
+static RECORDING_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct RecordedExchange {
+    pub(crate) recorded_at: u64,
+    pub(crate) method: String,
+    pub(crate) url: String,
+    pub(crate) request_headers: Vec<(String, String)>,
+    pub(crate) request_body: Option<String>,
+    pub(crate) status: u16,
+    pub(crate) response_headers: Vec<(String, String)>,
+    pub(crate) response_body: String,
+}
+
+pub(crate) fn set_recording_dir(dir: Option<PathBuf>) {
+    if let Ok(mut current) = RECORDING_DIR.lock() {
+        *current = dir;
+    }
+}
+
+pub(crate) fn recording_dir() -> Option<PathBuf> {
+    RECORDING_DIR.lock().ok().and_then(|dir| dir.clone())
+}
+
+fn replay_dir() -> Option<PathBuf> {
+    std::env::var(REPLAY_DIR_ENV_VAR)
+        .ok()
+        .filter(|dir| !dir.trim().is_empty())
+        .map(PathBuf::from)
+}
+
+fn headers_for_recording(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let name = name.as_str().to_string();
+            let value = if REDACTED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+                "[REDACTED]".to_string()
+            } else {
+                value.to_str().unwrap_or("[non-utf8]").to_string()
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+// Some APIs (e.g. Gemini) take the key as a query parameter.
+fn url_for_recording(url: &reqwest::Url) -> String {
+    if !url
+        .query_pairs()
+        .any(|(name, _)| REDACTED_QUERY_PARAMS.contains(&name.as_ref()))
+    {
+        return url.to_string();
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| {
+            let value = if REDACTED_QUERY_PARAMS.contains(&name.as_ref()) {
+                "[REDACTED]".to_string()
+            } else {
+                value.to_string()
+            };
+            (name.to_string(), value)
+        })
+        .collect();
+    let mut redacted = url.clone();
+    redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    redacted.to_string()
+}
+
+// reqwest's own message for a timeout does not say which limit was hit, and
+// other errors embed the URL, which may carry an API key.
+pub(crate) fn describe_send_error(
+    error: &reqwest::Error,
+    timeout: Option<Duration>,
+    elapsed: Duration,
+) -> String {
+    if !error.is_timeout() {
+        let message = error.to_string();
+        return match error.url() {
+            Some(url) => message.replace(url.as_str(), &url_for_recording(url)),
+            None => message,
+        };
+    }
+    match timeout {
+        Some(limit) if elapsed >= limit => {
+            format!("request timed out after {}s", limit.as_secs())
+        }
+        _ if error.is_connect() => {
+            format!("connection timed out after {}s", elapsed.as_secs().max(1))
+        }
+        _ => format!("request timed out after {}s", elapsed.as_secs().max(1)),
+    }
+}
+
+fn body_for_recording(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => format!("[{} bytes of binary data]", bytes.len()),
+    }
+}
+
+fn write_recording(dir: &Path, exchange: &RecordedExchange) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create recording directory {:?}: {}", dir, e))?;
+    let host = reqwest::Url::parse(&exchange.url)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host.replace('.', "_")))
+        .unwrap_or_else(|| "unknown".to_string());
+    let file_path = dir.join(format!(
+        "{}_{:05}_{}_{}.json",
+        exchange.recorded_at,
+        RECORDING_SEQUENCE.fetch_add(1, Ordering::SeqCst),
+        exchange.method,
+        host
+    ));
+    let json = serde_json::to_string_pretty(exchange)
+        .map_err(|e| format!("Failed to serialize recording: {}", e))?;
+    std::fs::write(&file_path, json)
+        .map_err(|e| format!("Failed to write recording {:?}: {}", file_path, e))?;
+    Ok(file_path)
+}
+
+// Recordings are consumed in file-name order so repeated calls to the same URL
+// replay successive responses.
+fn take_replayed_exchange(dir: &Path, method: &str, url: &str) -> Result<RecordedExchange, String> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read replay directory {:?}: {}", dir, e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .collect();
+    entries.sort();
+
+    let mut replayed = REPLAYED_FILES
+        .lock()
+        .map_err(|_| "Replay state lock was poisoned".to_string())?;
+    let replayed = replayed.get_or_insert_with(HashSet::new);
+
+    for path in entries {
+        if replayed.contains(&path) {
+            continue;
+        }
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let Ok(exchange) = serde_json::from_str::<RecordedExchange>(&contents) else {
+            continue;
+        };
+        if exchange.method == method && exchange.url == url {
+            replayed.insert(path);
+            return Ok(exchange);
+        }
+    }
+
+    Err(format!(
+        "No recorded response found for {} {} in {:?}",
+        method, url, dir
+    ))
+}
+
+fn response_from_exchange(exchange: &RecordedExchange) -> Result<Response, String> {
+    let mut builder = http::Response::builder().status(exchange.status);
+    for (name, value) in &exchange.response_headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    builder
+        .body(exchange.response_body.clone().into_bytes())
+        .map(Response::from)
+        .map_err(|e| format!("Failed to rebuild recorded response: {}", e))
+}
+
+pub(crate) trait RecordedSend {
+    async fn send_recorded(self) -> Result<Response, String>;
+}
+
+impl RecordedSend for RequestBuilder {
+    async fn send_recorded(self) -> Result<Response, String> {
+        let recording_dir = recording_dir();
+        let replay_dir = replay_dir();
+
+        let (client, request) = self.build_split();
+        let mut request = request.map_err(|e| e.to_string())?;
+        user_agent::apply_to_request(&mut request);
+        let method = request.method().to_string();
+        let timeout = request.timeout().copied();
+        let url = url_for_recording(request.url());
+        if logging::is_debug_enabled() {
+            logging::debug(&format!(
+                "HTTP request {} {} headers={:?} body_bytes={}",
+                method,
+                url,
+                headers_for_recording(request.headers()),
+                request
+                    .body()
+                    .and_then(|body| body.as_bytes())
+                    .map_or(0, |bytes| bytes.len())
+            ));
+        }
+        if recording_dir.is_none() && replay_dir.is_none() {
+            let started = std::time::Instant::now();
+            let result = client
+                .execute(request)
+                .await
+                .map_err(|e| describe_send_error(&e, timeout, started.elapsed()));
+            if logging::is_debug_enabled() {
+                match &result {
+                    Ok(response) => logging::debug(&format!(
+                        "HTTP response {} {} status={} elapsed_ms={}",
+                        method,
+                        url,
+                        response.status(),
+                        started.elapsed().as_millis()
+                    )),
+                    Err(e) => logging::debug(&format!(
+                        "HTTP error {} {} elapsed_ms={}: {}",
+                        method,
+                        url,
+                        started.elapsed().as_millis(),
+                        e
+                    )),
+                }
+            }
+            return result;
+        }
+
+        if let Some(dir) = replay_dir {
+            logging::info(&format!(
+                "Replaying recorded response for {} {}",
+                method, url
+            ));
+            let exchange = take_replayed_exchange(&dir, &method, &url)?;
+            return response_from_exchange(&exchange);
+        }
+
+        let request_headers = headers_for_recording(request.headers());
+        let request_body = request.body().map(|body| match body.as_bytes() {
+            Some(bytes) => body_for_recording(bytes),
+            None => "[streaming body]".to_string(),
+        });
+
+        let started = std::time::Instant::now();
+        let response = client
+            .execute(request)
+            .await
+            .map_err(|e| describe_send_error(&e, timeout, started.elapsed()))?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| describe_send_error(&e, timeout, started.elapsed()))?;
+
+        let exchange = RecordedExchange {
+            recorded_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            method,
+            url,
+            request_headers,
+            request_body,
+            status: status.as_u16(),
+            response_headers: headers_for_recording(&headers),
+            response_body: body_for_recording(&bytes),
+        };
+        if let Some(dir) = recording_dir {
+            match write_recording(&dir, &exchange) {
+                Ok(path) => logging::info(&format!("Recorded HTTP exchange to {:?}", path)),
+                Err(e) => logging::warn(&format!("Failed to record HTTP exchange: {}", e)),
+            }
+        }
+
+        let mut rebuilt = http::Response::builder().status(status);
+        for (name, value) in headers.iter() {
+            rebuilt = rebuilt.header(name, value);
+        }
+        rebuilt
+            .body(bytes.to_vec())
+            .map(Response::from)
+            .map_err(|e| format!("Failed to rebuild response: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(method: &str, url: &str, status: u16, body: &str) -> RecordedExchange {
+        RecordedExchange {
+            recorded_at: 1_700_000_000,
+            method: method.to_string(),
+            url: url.to_string(),
+            request_headers: Vec::new(),
+            request_body: None,
+            status,
+            response_headers: vec![("content-type".to_string(), "application/json".to_string())],
+            response_body: body.to_string(),
+        }
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "gen_article_{}_{}_{}",
+            name,
+            std::process::id(),
+            RECORDING_SEQUENCE.fetch_add(1, Ordering::SeqCst)
+        ));
+        std::fs::create_dir_all(&dir).expect("create scratch dir");
+        dir
+    }
+
+    #[test]
+    fn url_for_recording_redacts_key_query_params() {
+        let url = reqwest::Url::parse(
+            "https://generativelanguage.googleapis.com/v1beta/models/m:generateContent?key=secret&alt=sse&api_key=other",
+        )
+        .unwrap();
+        let recorded = url_for_recording(&url);
+        assert!(!recorded.contains("secret"));
+        assert!(!recorded.contains("other"));
+        assert!(recorded.contains("alt=sse"));
+        assert!(recorded.contains("key=%5BREDACTED%5D"));
+    }
+
+    #[test]
+    fn url_for_recording_keeps_urls_without_keys() {
+        let url = reqwest::Url::parse("https://api.openai.com/v1/chat/completions?x=1").unwrap();
+        assert_eq!(url_for_recording(&url), url.to_string());
+    }
+
+    #[test]
+    fn replayed_exchanges_are_consumed_in_file_name_order() {
+        let dir = scratch_dir("replay_order");
+        let url = "https://example.com/wp-json/wp/v2/posts";
+        for (file, body) in [("002.json", "second"), ("001.json", "first")] {
+            let json = serde_json::to_string(&exchange("POST", url, 200, body)).unwrap();
+            std::fs::write(dir.join(file), json).unwrap();
+        }
+        let other = serde_json::to_string(&exchange("GET", url, 200, "other")).unwrap();
+        std::fs::write(dir.join("000.json"), other).unwrap();
+
+        let first = take_replayed_exchange(&dir, "POST", url).unwrap();
+        let second = take_replayed_exchange(&dir, "POST", url).unwrap();
+        assert_eq!(first.response_body, "first");
+        assert_eq!(second.response_body, "second");
+        assert!(take_replayed_exchange(&dir, "POST", url).is_err());
+        assert_eq!(
+            take_replayed_exchange(&dir, "GET", url)
+                .unwrap()
+                .response_body,
+            "other"
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn replay_skips_files_that_are_not_recordings() {
+        let dir = scratch_dir("replay_skip");
+        let url = "https://example.com/a";
+        std::fs::write(dir.join("001.json"), "not json").unwrap();
+        std::fs::write(dir.join("002.txt"), "ignored").unwrap();
+        let json = serde_json::to_string(&exchange("GET", url, 404, "missing")).unwrap();
+        std::fs::write(dir.join("003.json"), json).unwrap();
+
+        assert_eq!(
+            take_replayed_exchange(&dir, "GET", url).unwrap().status,
+            404
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn response_is_rebuilt_from_exchange() {
+        let recorded = exchange("GET", "https://example.com/a", 201, "{\"id\":7}");
+        let response = response_from_exchange(&recorded).unwrap();
+        assert_eq!(response.status().as_u16(), 201);
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        assert_eq!(response.text().await.unwrap(), "{\"id\":7}");
+    }
+}
@@ -1,21 +1,127 @@
+mod http_recording;
+mod logging;
+mod markdown;
+mod pricing;
+mod user_agent;
+
+use chrono::TimeZone;
+use http_recording::RecordedSend;
 use mime_guess;
 use regex::Regex;
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_DISPOSITION, CONTENT_TYPE, RETRY_AFTER};
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 use tauri_plugin_store::{JsonValue, StoreExt};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use tokio::time::sleep;
+use user_agent::{UserAgentSettings, UserAgentTarget};
 
 const STORE_FILE: &str = ".settings.dat";
 
 const STORE_KEY_TEXT_API: &str = "textApiKey";
 const STORE_KEY_IMAGE_API: &str = "imageApiKey";
+const STORE_KEY_GEMINI_API: &str = "geminiApiKey";
 const STORE_KEY_PROJECTS: &str = "projects";
+const STORE_KEY_PRICING: &str = "pricingTable";
+const STORE_KEY_IMAGE_PRICING: &str = "imagePricingTable";
+const STORE_KEY_SAFE_MODE: &str = "safeMode";
+const STORE_KEY_ARTICLE_HISTORY: &str = "articleHistory";
+const STORE_KEY_RECORD_HTTP: &str = "recordHttp";
+const STORE_KEY_BACKUP_INTERVAL_MINS: &str = "backupIntervalMinutes";
+const STORE_KEY_BACKUP_RETENTION: &str = "backupRetention";
+const STORE_KEY_BATCH_CONCURRENCY: &str = "batchConcurrency";
+const STORE_KEY_DEFAULT_PROJECT: &str = "defaultProject";
+const STORE_KEY_USER_AGENTS: &str = "userAgents";
+const STORE_KEY_USAGE_LOG: &str = "usageLog";
+const STORE_KEY_BATCHES: &str = "batches";
+const STORE_KEY_PER_PROJECT_FILES: &str = "perProjectFiles";
+const STORE_KEY_DAILY_UPLOADS: &str = "dailyUploads";
+const STORE_KEY_PROJECT_INDEX: &str = "projectIndex";
+
+const PROJECT_STORE_DIR: &str = "projects";
+const PROJECT_STORE_KEY_SETTINGS: &str = "settings";
+const PROJECT_STORE_KEY_HISTORY: &str = "articleHistory";
+
+const DEFAULT_BACKUP_INTERVAL_MINS: u64 = 60;
+const DEFAULT_BACKUP_RETENTION: usize = 10;
+const BACKUP_FILE_PREFIX: &str = "settings_";
+const BACKUP_FILE_SUFFIX: &str = ".zip";
+// Backups taken before per-project files were included are a bare copy of the main store.
+const LEGACY_BACKUP_FILE_SUFFIX: &str = ".dat";
+
+const MAX_ARTICLE_HISTORY_PER_PROJECT: usize = 50;
+// SEO <title> length asked of the model and enforced on its output.
+const TITLE_MIN_CHARS: usize = 60;
+const TITLE_MAX_CHARS: usize = 70;
+const MIN_TARGET_WORD_COUNT: u32 = 100;
+const MAX_TARGET_WORD_COUNT: u32 = 10000;
+// Articles shorter than this share of the target are regenerated with the escalation model.
+const ESCALATION_WORD_RATIO: f64 = 0.7;
+// Articles shorter than this share of the target get follow-up expansion requests.
+const EXPANSION_WORD_RATIO: f64 = 0.9;
+const MAX_EXPANSION_PASSES: usize = 3;
+const EXPANSION_THIN_SECTIONS: usize = 3;
+const SIMILARITY_SHINGLE_SIZE: usize = 5;
+const SIMILARITY_WARNING_THRESHOLD: f64 = 0.3;
+const MAX_SIMILARITY_HITS: usize = 5;
+// Share of words two section instructions must have in common to count as near-duplicates.
+const SECTION_DUPLICATE_THRESHOLD: f64 = 0.8;
+const MAX_USAGE_LOG_ENTRIES: usize = 10000;
+// Roughly 10k tokens in and out, well within gpt-4o's output limit.
+const PLACEHOLDER_CHUNK_MAX_CHARS: usize = 30000;
+const BULK_IMAGE_CONCURRENCY: usize = 3;
+const BULK_IMAGE_MAX_ATTEMPTS: u32 = 3;
+// Conservative limit; Ideogram rejects or truncates longer prompts.
+const IDEOGRAM_MAX_PROMPT_CHARS: usize = 2000;
+const IMAGE_PROMPT_POLICY_TERMS: &[&str] = &[
+    "nude",
+    "naked",
+    "nsfw",
+    "porn",
+    "sexual",
+    "gore",
+    "blood",
+    "corpse",
+    "beheading",
+    "suicide",
+    "self-harm",
+    "terrorist",
+    "nazi",
+    "swastika",
+    "child abuse",
+];
+const DEFAULT_BATCH_CONCURRENCY: usize = 1;
+const MAX_BATCH_CONCURRENCY: usize = 8;
+const DEFAULT_UPLOAD_CONCURRENCY: usize = 3;
+const MAX_UPLOAD_CONCURRENCY: usize = 8;
+
+const SAFE_MODE_ENV_VAR: &str = "GEN_ARTICLE_SAFE_MODE";
+
+const DEFAULT_HTTP_TIMEOUT_SECS: u64 = 120;
+const DEFAULT_LANGUAGE: &str = "fr";
+// Calls that send or return a whole article (merge, trim, expansion, long sections).
+const LONG_LLM_REQUEST_TIMEOUT_SECS: u64 = 600;
+// The article stream has no total cap, only a limit on the gap between chunks.
+const STREAM_IDLE_TIMEOUT_SECS: u64 = 60;
+const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_IMAGE_GEN_TIMEOUT_SECS: u64 = 180;
+const DEFAULT_UPLOAD_TIMEOUT_SECS: u64 = 60;
+const HTTP_CONNECT_TIMEOUT_SECS: u64 = 15;
+const HTTP_POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+
+const GEMINI_API_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+
+const DEFAULT_ARTICLE_MAX_RETRIES: u32 = 3;
+const ARTICLE_INITIAL_BACKOFF_SECS: u64 = 5;
+const ARTICLE_MAX_BACKOFF_SECS: u64 = 60;
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 struct SectionDefinitionData {
@@ -42,20 +148,111 @@ struct ProjectSettings {
     text_generation_model: String,
     #[serde(default = "default_word_count")]
     target_word_count: u32,
+    #[serde(default)]
+    footer_html: Option<String>,
+    #[serde(default)]
+    preferred_seed: Option<u64>,
+    #[serde(default = "default_image_download_timeout_secs")]
+    image_download_timeout_secs: u64,
+    #[serde(default = "default_image_download_concurrency")]
+    image_download_concurrency: usize,
+    #[serde(default = "default_language")]
+    language: String,
+    #[serde(default)]
+    french_typography: bool,
+    #[serde(default)]
+    outbound_link_rel: Option<String>,
+    #[serde(default)]
+    escalate_on_short: Option<String>,
+    #[serde(default)]
+    disambiguation_notes: Option<String>,
+    #[serde(default)]
+    max_word_count: Option<u32>,
+    #[serde(default)]
+    required_elements: Option<RequirementSet>,
+    #[serde(default)]
+    utm_params: Option<HashMap<String, String>>,
+    #[serde(default)]
+    max_payload_bytes: Option<u64>,
+    #[serde(default)]
+    upload_retry: Option<UploadConfig>,
+    #[serde(default)]
+    official_url: Option<String>,
+    #[serde(default)]
+    daily_upload_limit: Option<u32>,
+    #[serde(default)]
+    heading_keywords: Option<Vec<String>>,
+    #[serde(default)]
+    openai_base_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct UploadConfig {
+    #[serde(default = "default_initial_backoff_secs")]
+    initial_backoff_secs: u64,
+    #[serde(default = "default_backoff_multiplier")]
+    backoff_multiplier: f64,
+    #[serde(default = "default_max_backoff_secs")]
+    max_backoff_secs: u64,
+    #[serde(default = "default_upload_max_retries")]
+    max_retries: u32,
+}
+
+fn default_initial_backoff_secs() -> u64 {
+    10
+}
+
+fn default_backoff_multiplier() -> f64 {
+    2.0
+}
+
+fn default_max_backoff_secs() -> u64 {
+    60
+}
+
+fn default_upload_max_retries() -> u32 {
+    3
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        UploadConfig {
+            initial_backoff_secs: default_initial_backoff_secs(),
+            backoff_multiplier: default_backoff_multiplier(),
+            max_backoff_secs: default_max_backoff_secs(),
+            max_retries: default_upload_max_retries(),
+        }
+    }
 }
 
 type ProjectsMap = HashMap<String, ProjectSettings>;
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct StoredArticle {
+    created_at: u64,
+    title: Option<String>,
+    article_html: String,
+}
+
+type ArticleHistoryMap = HashMap<String, Vec<StoredArticle>>;
+
 #[derive(Deserialize, Debug)]
 struct ArticleRequest {
     topic: String,
     description: String,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct ArticleResponse {
     article_text: String,
     title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    escalated_to_model: Option<String>,
+    #[serde(default)]
+    word_count: usize,
+    seo_title: Option<String>,
+    meta_description: Option<String>,
+    h1: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -63,6 +260,12 @@ struct ImageGenRequest {
     prompt: String,
     rendering_speed: Option<String>,
     aspect_ratio: Option<String>,
+    project_name: Option<String>,
+    seed: Option<u64>,
+    rewrite_unsafe_prompt: Option<bool>,
+    dry_run: Option<bool>,
+    style_reference_urls: Option<Vec<String>>,
+    timeout_secs: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -84,7 +287,13 @@ struct IdeogramImageData {
 #[derive(Serialize, Debug)]
 struct ImageGenResponse {
     image_url: Option<String>,
+    seed: Option<u64>,
+    original_prompt: String,
+    rewritten_prompt: Option<String>,
+    successful_prompt: Option<String>,
     error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    form_preview: Option<serde_json::Map<String, JsonValue>>,
 }
 
 #[derive(Serialize, Debug)]
@@ -120,7 +329,7 @@ struct OpenAiV1Content {
     text: String,
 }
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(Serialize, serde::Deserialize, Debug, Clone)]
 struct FullArticleRequest {
     tool_name: String,
     article_goal_prompt: String,
@@ -128,6 +337,27 @@ struct FullArticleRequest {
     sections: Vec<SectionDefinitionData>,
     model: String,
     target_word_count: u32,
+    #[serde(default)]
+    footer_html: Option<String>,
+    #[serde(default)]
+    project_name: Option<String>,
+    // Unset fields fall back to the project's settings in generate_full_article.
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    french_typography: Option<bool>,
+    #[serde(default)]
+    timeout_secs: Option<u64>,
+    #[serde(default)]
+    escalate_on_short: Option<String>,
+    #[serde(default)]
+    preserve_title: Option<String>,
+    #[serde(default)]
+    disambiguation_notes: Option<String>,
+    #[serde(default)]
+    max_retries: Option<u32>,
+    #[serde(default)]
+    max_word_count: Option<u32>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -146,6 +376,22 @@ struct OpenAiApiResponse {
     choices: Vec<OpenAiApiResponseChoice>,
 }
 
+#[derive(Deserialize, Debug, Clone, Copy)]
+struct OpenAiUsage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct UsageLogEntry {
+    project: String,
+    timestamp: u64,
+    model: String,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    cost_usd: Option<f64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 struct ApiKeys {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -157,6 +403,8 @@ struct ApiKeys {
 #[derive(serde::Deserialize, Debug)]
 struct SuggestImagePromptsRequest {
     article_text: String,
+    timeout_secs: Option<u64>,
+    project_name: Option<String>,
 }
 
 #[derive(Serialize, Debug)]
@@ -174,6 +422,17 @@ struct PublishRequest {
     featured_media_id: Option<u32>,
     slug: Option<String>,
     schedule_date: Option<String>,
+    author_id: Option<u32>,
+    responsive_tables: Option<bool>,
+    table_data_labels: Option<bool>,
+    timeout_secs: Option<u64>,
+    custom_taxonomies: Option<HashMap<String, Vec<u32>>>,
+    include_jsonld: Option<bool>,
+    jsonld_author: Option<String>,
+    featured_image_url: Option<String>,
+    tidy_html: Option<bool>,
+    demote_headings: Option<bool>,
+    tags: Option<Vec<u32>>,
 }
 
 #[derive(Serialize, Debug)]
@@ -184,11 +443,35 @@ struct WordPressPostPayload<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     categories: Option<Vec<u32>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<&'a [u32]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     featured_media: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     slug: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     date: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<u32>,
+    // WordPress accepts each registered taxonomy's REST base as a top-level key.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    custom_taxonomies: Option<&'a HashMap<String, Vec<u32>>>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct WordPressError {
+    code: String,
+    message: String,
+    status: Option<u16>,
+}
+
+impl From<String> for WordPressError {
+    fn from(message: String) -> Self {
+        WordPressError {
+            code: "client_error".to_string(),
+            message,
+            status: None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -198,10 +481,111 @@ struct WordPressCategory {
     slug: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct WordPressTag {
+    id: u32,
+    name: String,
+    slug: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct WordPressTerm {
+    id: u32,
+    name: String,
+    slug: String,
+    #[serde(default)]
+    taxonomy: String,
+    #[serde(default)]
+    count: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct WordPressAuthor {
+    id: u32,
+    name: String,
+    slug: String,
+}
+
+#[derive(Serialize, Debug)]
+struct WordPressProbe {
+    site_name: Option<String>,
+    site_url: Option<String>,
+    namespaces: Vec<String>,
+    has_wp_v2: bool,
+    authentication_required: bool,
+    authentication_methods: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct WordPressRenderedField {
+    rendered: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct WordPressPostListItem {
+    id: u32,
+    link: String,
+    title: WordPressRenderedField,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct WordPressEditableField {
+    raw: Option<String>,
+    rendered: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct WordPressPostEditResponse {
+    id: u32,
+    title: WordPressEditableField,
+    content: WordPressEditableField,
+    status: String,
+    #[serde(default)]
+    categories: Vec<u32>,
+    #[serde(default)]
+    featured_media: u32,
+    slug: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct WordPressPostFull {
+    id: u32,
+    title: String,
+    content_raw: String,
+    status: String,
+    categories: Vec<u32>,
+    featured_media: Option<u32>,
+    slug: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct WordPressPostSummary {
+    id: u32,
+    title: String,
+    link: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct InternalLinkSuggestion {
+    anchor_text: String,
+    target_post_url: String,
+    target_title: String,
+}
+
 #[derive(Deserialize, Debug)]
 struct UploadImageRequest {
     project_name: String,
     image_urls: Vec<String>,
+    timeout_secs: Option<u64>,
+    alt_texts: Option<Vec<String>>,
+    dedup: Option<bool>,
+    upload_concurrency: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct WordPressMediaItem {
+    id: u32,
+    source_url: String,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -211,6 +595,65 @@ struct ImageUploadResult {
     error: Option<String>,
     wordpress_media_id: Option<u32>,
     wordpress_media_url: Option<String>,
+    reused: bool,
+    dry_run: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    regenerate_with: Option<ImageGenerationParams>,
+}
+
+impl ImageUploadResult {
+    fn uploaded(original_url: &str, media_id: u32, media_url: String, reused: bool) -> Self {
+        ImageUploadResult {
+            original_url: original_url.to_string(),
+            success: true,
+            error: None,
+            wordpress_media_id: Some(media_id),
+            wordpress_media_url: Some(media_url),
+            reused,
+            dry_run: false,
+            error_code: None,
+            regenerate_with: None,
+        }
+    }
+
+    // Safe mode never touches WordPress, so there is no media ID to hand back.
+    fn dry_run(original_url: &str) -> Self {
+        ImageUploadResult {
+            original_url: original_url.to_string(),
+            success: true,
+            error: None,
+            wordpress_media_id: None,
+            wordpress_media_url: None,
+            reused: false,
+            dry_run: true,
+            error_code: None,
+            regenerate_with: None,
+        }
+    }
+
+    fn failed(original_url: &str, error: String) -> Self {
+        ImageUploadResult {
+            original_url: original_url.to_string(),
+            success: false,
+            error: Some(error),
+            wordpress_media_id: None,
+            wordpress_media_url: None,
+            reused: false,
+            dry_run: false,
+            error_code: None,
+            regenerate_with: None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct ImageGenerationParams {
+    prompt: String,
+    rendering_speed: Option<String>,
+    aspect_ratio: Option<String>,
+    seed: Option<u64>,
 }
 
 #[derive(Serialize, Debug)]
@@ -222,6 +665,29 @@ struct UploadImagesResponse {
 struct WordPressMediaResponse {
     id: u32,
     source_url: String,
+    #[serde(default)]
+    media_details: Option<WordPressMediaDetails>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct WordPressMediaDetails {
+    #[serde(default)]
+    sizes: HashMap<String, WordPressMediaSizeDetails>,
+}
+
+#[derive(Deserialize, Debug)]
+struct WordPressMediaSizeDetails {
+    width: u32,
+    height: u32,
+    source_url: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct MediaSize {
+    name: String,
+    width: u32,
+    height: u32,
+    source_url: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -232,10 +698,20 @@ struct ImageDetailsForLLM {
     placeholder_index: usize,
 }
 
+#[derive(Serialize, Debug)]
+struct ReplacePlaceholdersResponse {
+    article_html: String,
+    missing_placeholders: Vec<String>,
+    error: Option<String>,
+}
+
 #[derive(Deserialize, Debug)]
 struct InsertPlaceholdersLLMRequest {
     article_html: String,
     images: Vec<ImageDetailsForLLM>,
+    timeout_secs: Option<u64>,
+    incremental: Option<bool>,
+    project_name: Option<String>,
 }
 
 #[derive(Serialize, Debug)]
@@ -243,160 +719,429 @@ struct InsertPlaceholdersLLMResponse {
     article_with_placeholders: String,
 }
 
-#[tauri::command]
-async fn save_api_key(
-    app: tauri::AppHandle,
-    key_name: String,
-    key_value: String,
-) -> Result<(), String> {
-    let store_result = app.store(PathBuf::from(STORE_FILE));
+#[derive(Serialize, Debug, Clone)]
+struct HtmlValidationIssue {
+    kind: String,
+    message: String,
+    tag: Option<String>,
+    position: Option<usize>,
+}
 
-    match store_result {
-        Ok(s) => {
-            s.set(key_name, JsonValue::String(key_value));
-            s.save()
-                .map_err(|e| format!("Failed to save store: {}", e))?;
-            Ok(())
-        }
-        Err(e) => Err(format!("Failed to access store: {}", e)),
-    }
+#[derive(Serialize, Debug)]
+struct HtmlValidation {
+    is_valid: bool,
+    has_single_root: bool,
+    root_element_count: usize,
+    issues: Vec<HtmlValidationIssue>,
 }
 
-#[tauri::command]
-async fn get_api_key(app: tauri::AppHandle, key_name: String) -> Result<Option<String>, String> {
-    let store_result = app.store(PathBuf::from(STORE_FILE));
+#[derive(Serialize, Debug, Clone)]
+struct SeoMetadata {
+    title: String,
+    description: String,
+}
 
-    match store_result {
-        Ok(s) => {
-            s.reload()
-                .map_err(|e| format!("Failed to reload store before get: {}", e))?;
+#[derive(Serialize, Debug)]
+struct SocialMeta {
+    og_title: String,
+    og_description: String,
+    og_image: Option<String>,
+    twitter_card: String,
+    meta_tags_html: String,
+}
 
-            let value = s.get(&key_name).clone();
+#[derive(Deserialize, Debug)]
+struct CostEstimateRequest {
+    model: String,
+    input_tokens: u64,
+    output_tokens: u64,
+}
 
-            match value {
-                Some(JsonValue::String(s_val)) => Ok(Some(s_val)),
-                Some(_) => Err("Stored value is not a string".to_string()),
-                None => Ok(None),
-            }
-        }
-        Err(e) => Err(format!("Failed to access store: {}", e)),
-    }
+#[derive(Serialize, Debug)]
+struct CostEstimate {
+    model: String,
+    input_tokens: u64,
+    output_tokens: u64,
+    pricing: Option<pricing::ModelPricing>,
+    estimated_cost_usd: Option<f64>,
 }
 
-fn get_projects_from_store(
-    store: &tauri_plugin_store::Store<tauri::Wry>,
-) -> Result<ProjectsMap, String> {
-    match store.get(STORE_KEY_PROJECTS) {
-        Some(value) => serde_json::from_value(value.clone()).map_err(|e| {
-            format!(
-                "Failed to deserialize projects: {}. Value was: {}",
-                e, value
-            )
-        }),
-        None => Ok(ProjectsMap::new()),
-    }
+#[derive(Serialize, Debug)]
+struct ExportSummary {
+    output_path: String,
+    projects_exported: usize,
+    articles_exported: usize,
+    projects_without_articles: Vec<String>,
 }
 
-#[tauri::command]
-async fn create_project(app: tauri::AppHandle, name: String) -> Result<(), String> {
-    if name.trim().is_empty() {
-        return Err("Project name cannot be empty".to_string());
-    }
-    let store_result = app.store(PathBuf::from(STORE_FILE));
-    match store_result {
-        Ok(s) => {
-            s.reload()
-                .map_err(|e| format!("Failed to load store: {}", e))?;
-            let mut projects = get_projects_from_store(&s)?;
+#[derive(Deserialize, Debug)]
+struct BatchGenerationRequest {
+    items: Vec<FullArticleRequest>,
+    batch_budget_usd: Option<f64>,
+}
 
-            if projects.contains_key(&name) {
-                return Err(format!("Project '{}' already exists.", name));
-            }
+#[derive(Serialize, Debug)]
+struct BatchItemResult {
+    index: usize,
+    tool_name: String,
+    success: bool,
+    article: Option<ArticleResponse>,
+    error: Option<String>,
+    estimated_cost_usd: Option<f64>,
+    skipped_by_budget: bool,
+}
 
-            let default_settings = ProjectSettings {
-                wordpress_url: default_string(),
-                wordpress_user: default_string(),
-                wordpress_pass: default_string(),
-                tool_name: name.clone(),
-                article_goal_prompt: default_string(),
-                example_url: default_string(),
-                sections: default_sections(),
-                text_generation_model: default_text_model(),
-                target_word_count: default_word_count(),
-            };
-            projects.insert(name.clone(), default_settings);
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum BatchItemStatus {
+    Pending,
+    #[serde(rename = "in_progress")]
+    InProgress,
+    Done,
+    Failed,
+}
 
-            s.set(
-                STORE_KEY_PROJECTS.to_string(),
-                serde_json::to_value(projects)
-                    .map_err(|e| format!("Failed to serialize projects: {}", e))?,
-            );
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct StoredBatchItem {
+    request: FullArticleRequest,
+    status: BatchItemStatus,
+    error: Option<String>,
+    article: Option<ArticleResponse>,
+    estimated_cost_usd: Option<f64>,
+}
 
-            s.save()
-                .map_err(|e| format!("Failed to save store: {}", e))?;
-            Ok(())
-        }
-        Err(e) => Err(format!("Failed to access store: {}", e)),
-    }
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct StoredBatch {
+    id: String,
+    created_at: u64,
+    updated_at: u64,
+    batch_budget_usd: Option<f64>,
+    items: Vec<StoredBatchItem>,
 }
 
-#[tauri::command]
-async fn get_projects(app: tauri::AppHandle) -> Result<Vec<String>, String> {
-    let store_result = app.store(PathBuf::from(STORE_FILE));
-    match store_result {
-        Ok(s) => {
-            s.reload()
-                .map_err(|e| format!("Failed to load store: {}", e))?;
-            let projects = get_projects_from_store(&s)?;
-            let mut names: Vec<String> = projects.keys().cloned().collect();
-            names.sort_unstable();
-            Ok(names)
-        }
-        Err(e) => Err(format!("Failed to access store: {}", e)),
-    }
+type BatchesMap = HashMap<String, StoredBatch>;
+
+#[derive(Serialize, Debug)]
+struct BatchSummary {
+    id: String,
+    created_at: u64,
+    updated_at: u64,
+    total: usize,
+    done: usize,
+    failed: usize,
+    pending: usize,
+    in_progress: usize,
+    tool_names: Vec<String>,
 }
 
-#[tauri::command]
-async fn get_project_settings(
-    app: tauri::AppHandle,
-    name: String,
-) -> Result<Option<ProjectSettings>, String> {
-    let store_result = app.store(PathBuf::from(STORE_FILE));
-    match store_result {
-        Ok(s) => {
-            s.reload()
-                .map_err(|e| format!("Failed to load store: {}", e))?;
-            let projects = get_projects_from_store(&s)?;
-            Ok(projects.get(&name).cloned())
+#[derive(Serialize, Debug)]
+struct BatchGenerationResponse {
+    batch_id: String,
+    results: Vec<BatchItemResult>,
+    total_estimated_cost_usd: f64,
+    note: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct ProjectAudit {
+    project_name: String,
+    is_complete: bool,
+    issues: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct SectionValidation {
+    is_valid: bool,
+    blank_indices: Vec<usize>,
+    duplicate_groups: Vec<Vec<usize>>,
+}
+
+#[derive(Serialize, Debug)]
+struct StoreBackup {
+    name: String,
+    created_at: u64,
+    size_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct StoreBackupSettings {
+    interval_minutes: u64,
+    retention: usize,
+}
+
+#[derive(Serialize, Debug)]
+struct NetworkCheck {
+    name: String,
+    target: String,
+    success: bool,
+    duration_ms: u128,
+    error_class: Option<String>,
+    detail: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct NetworkDiagnostics {
+    proxy: Option<String>,
+    checks: Vec<NetworkCheck>,
+}
+
+#[derive(Serialize, Debug)]
+struct SectionNormalizationResult {
+    before_count: usize,
+    after_count: usize,
+    empty_removed: usize,
+    duplicates_removed: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+struct RateLimitStatus {
+    captured_at: u64,
+    limit_requests: Option<u64>,
+    remaining_requests: Option<u64>,
+    reset_requests: Option<String>,
+    limit_tokens: Option<u64>,
+    remaining_tokens: Option<u64>,
+    reset_tokens: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Serialize, Debug)]
+struct SimilarityHit {
+    project_name: String,
+    created_at: u64,
+    title: Option<String>,
+    similarity: f64,
+    exceeds_threshold: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct ImageGenerationProgress {
+    index: usize,
+    total: usize,
+    prompt: String,
+    attempt: u32,
+    status: String,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct FeaturedImageInfo {
+    media_id: u32,
+    source_url: String,
+}
+
+#[derive(Serialize, Debug)]
+struct ImagePromptValidation {
+    is_valid: bool,
+    char_count: usize,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct MultilingualProgress {
+    language: String,
+    status: String,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct QualityCriterion {
+    score: u8,
+    justification: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct QualityScore {
+    readability: QualityCriterion,
+    structure: QualityCriterion,
+    depth: QualityCriterion,
+    seo: QualityCriterion,
+    #[serde(default)]
+    overall: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Requirement {
+    Title,
+    MetaDescription,
+    H1,
+    H2,
+    StyledTable,
+    OfficialSiteLink,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct RequirementSet {
+    #[serde(default = "default_true")]
+    title: bool,
+    #[serde(default = "default_true")]
+    meta_description: bool,
+    #[serde(default = "default_true")]
+    h1: bool,
+    #[serde(default = "default_min_h2_count")]
+    min_h2_count: u32,
+    #[serde(default)]
+    styled_table: bool,
+    #[serde(default = "default_true")]
+    official_site_link: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_min_h2_count() -> u32 {
+    2
+}
+
+impl Default for RequirementSet {
+    fn default() -> Self {
+        RequirementSet {
+            title: true,
+            meta_description: true,
+            h1: true,
+            min_h2_count: default_min_h2_count(),
+            styled_table: false,
+            official_site_link: true,
         }
-        Err(e) => Err(format!("Failed to access store: {}", e)),
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Callout {
+    text: String,
+    near_heading: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct DailyUploadCounter {
+    date: String,
+    count: u32,
+}
+
+type DailyUploadsMap = HashMap<String, DailyUploadCounter>;
+
+#[derive(Serialize, Debug)]
+struct DailyUploadUsage {
+    date: String,
+    count: u32,
+    limit: Option<u32>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct KeywordTitleCheck {
+    title: String,
+    title_length: usize,
+    contains_keyword: bool,
+    keyword_position: Option<usize>,
+    front_loaded: bool,
+    suggestions: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct HeadingKeywordResult {
+    keyword: String,
+    found: bool,
+    headings: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct LongArticleProgress {
+    section_index: usize,
+    total_sections: usize,
+    status: String,
+    word_count: usize,
+}
+
+#[derive(Deserialize, Debug)]
+struct LongArticleHead {
+    title: String,
+    meta_description: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct OpenAiStreamDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OpenAiStreamChoice {
+    delta: OpenAiStreamDelta,
+}
+
+#[derive(Deserialize, Debug)]
+struct OpenAiStreamChunk {
+    #[serde(default)]
+    choices: Vec<OpenAiStreamChoice>,
+    #[serde(default)]
+    usage: Option<OpenAiUsage>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct ArticleChunk {
+    project_name: Option<String>,
+    delta: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct GeminiPart {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GeminiContent {
+    #[serde(default)]
+    parts: Vec<GeminiPart>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GeminiCandidate {
+    #[serde(default)]
+    content: Option<GeminiContent>,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GeminiUsageMetadata {
+    #[serde(default)]
+    prompt_token_count: u64,
+    #[serde(default)]
+    candidates_token_count: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GeminiResponse {
+    #[serde(default)]
+    candidates: Vec<GeminiCandidate>,
+    #[serde(default)]
+    usage_metadata: Option<GeminiUsageMetadata>,
+}
+
 #[tauri::command]
-async fn save_project_settings(
+async fn save_api_key(
     app: tauri::AppHandle,
-    name: String,
-    settings: ProjectSettings,
+    key_name: String,
+    key_value: String,
 ) -> Result<(), String> {
+    let _guard = lock_store_writes()?;
     let store_result = app.store(PathBuf::from(STORE_FILE));
+
     match store_result {
         Ok(s) => {
-            s.reload()
-                .map_err(|e| format!("Failed to load store: {}", e))?;
-            let mut projects = get_projects_from_store(&s)?;
-
-            if !projects.contains_key(&name) {
-                return Err(format!("Project '{}' not found.", name));
-            }
-
-            projects.insert(name.clone(), settings);
-
-            s.set(
-                STORE_KEY_PROJECTS.to_string(),
-                serde_json::to_value(projects).unwrap(),
-            );
-
+            s.set(key_name, JsonValue::String(key_value));
             s.save()
                 .map_err(|e| format!("Failed to save store: {}", e))?;
             Ok(())
@@ -405,722 +1150,10208 @@ async fn save_project_settings(
     }
 }
 
+// Secrets are never logged, not even a prefix.
+fn mask_secret(secret: &str) -> &'static str {
+    if secret.trim().is_empty() {
+        "(empty)"
+    } else {
+        "***"
+    }
+}
+
+// Proxy URLs commonly carry user:pass@ credentials.
+fn redact_url_credentials(url: &str) -> String {
+    if let Ok(mut parsed) = reqwest::Url::parse(url) {
+        if !parsed.username().is_empty() || parsed.password().is_some() {
+            let user = mask_secret(parsed.username());
+            let password = parsed.password().map(mask_secret);
+            if parsed.set_username(user).is_ok() && parsed.set_password(password).is_ok() {
+                return parsed.to_string();
+            }
+        } else if !url.contains('@') {
+            return url.to_string();
+        }
+    }
+    // Scheme-less values such as "user:pass@host:port" don't parse as URLs.
+    match url.rsplit_once('@') {
+        Some((credentials, host)) => format!("{}@{}", mask_secret(credentials), host),
+        None => url.to_string(),
+    }
+}
+
 #[tauri::command]
-async fn delete_project(app: tauri::AppHandle, name: String) -> Result<(), String> {
-    println!("Rust: Attempting to delete project '{}'", name);
+async fn get_api_key(app: tauri::AppHandle, key_name: String) -> Result<Option<String>, String> {
+    let _guard = lock_store_writes()?;
     let store_result = app.store(PathBuf::from(STORE_FILE));
+
     match store_result {
         Ok(s) => {
-            println!("Rust: Store accessed for deletion.");
-            s.reload().map_err(|e| {
-                let err_msg = format!("Failed to load store: {}", e);
-                println!("Rust: Error - {}", &err_msg);
-                err_msg
-            })?;
+            s.reload()
+                .map_err(|e| format!("Failed to reload store before get: {}", e))?;
 
-            let mut projects = get_projects_from_store(&s).map_err(|e| {
-                println!("Rust: Error getting projects from store: {}", e);
-                e
-            })?;
-            println!("Rust: Projects map loaded. Size: {}", projects.len());
+            let value = s.get(&key_name).clone();
 
-            if projects.remove(&name).is_none() {
-                println!("Rust: Project '{}' not found in map.", name);
-                return Err(format!("Project '{}' not found.", name));
+            match value {
+                Some(JsonValue::String(s_val)) => Ok(Some(s_val)),
+                Some(_) => Err("Stored value is not a string".to_string()),
+                None => Ok(None),
             }
-            println!("Rust: Project '{}' removed from map.", name);
+        }
+        Err(e) => Err(format!("Failed to access store: {}", e)),
+    }
+}
 
-            let updated_projects_value = serde_json::to_value(&projects).map_err(|e| {
-                let err_msg = format!("Failed to serialize updated projects map: {}", e);
-                println!("Rust: Error - {}", &err_msg);
-                err_msg
-            })?;
+fn build_http_client() -> Client {
+    Client::builder()
+        .connect_timeout(Duration::from_secs(HTTP_CONNECT_TIMEOUT_SECS))
+        .pool_idle_timeout(Duration::from_secs(HTTP_POOL_IDLE_TIMEOUT_SECS))
+        .build()
+        .unwrap_or_else(|e| {
+            logging::warn(&format!(
+                "Failed to build the shared HTTP client, using defaults: {}",
+                e
+            ));
+            Client::new()
+        })
+}
 
-            s.set(STORE_KEY_PROJECTS.to_string(), updated_projects_value);
-            println!("Rust: Updated projects map set in store (in memory).");
+// Clones share the connection pool, so this is cheap.
+fn shared_client(app: &tauri::AppHandle) -> Client {
+    app.try_state::<Client>()
+        .map(|client| client.inner().clone())
+        .unwrap_or_else(build_http_client)
+}
 
-            s.save().map_err(|e| {
-                let err_msg = format!("Failed to save store after deletion: {}", e);
-                println!("Rust: Error - {}", &err_msg);
-                err_msg
-            })?;
+fn openai_chat_completions_url(base_url: Option<&str>) -> String {
+    let base_url = base_url
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .unwrap_or(DEFAULT_OPENAI_BASE_URL);
+    format!("{}/chat/completions", base_url.trim_end_matches('/'))
+}
 
-            println!("Rust: Store saved successfully after deleting '{}'.", name);
-            Ok(())
-        }
-        Err(e) => {
-            let err_msg = format!("Failed to access store: {}", e);
-            println!("Rust: Error - {}", &err_msg);
-            Err(err_msg)
-        }
+async fn project_chat_completions_url(
+    app: &tauri::AppHandle,
+    project_name: Option<&str>,
+) -> Result<String, String> {
+    let base_url = match project_name {
+        Some(project_name) => get_project_settings(app.clone(), project_name.to_string())
+            .await?
+            .and_then(|settings| settings.openai_base_url),
+        None => None,
+    };
+    Ok(openai_chat_completions_url(base_url.as_deref()))
+}
+
+// Helper calls go to the project's chat endpoint, so they use its text model
+// too; a custom endpoint may not serve gpt-4o. Gemini models only work through
+// the Gemini API, so those projects keep gpt-4o for helpers.
+async fn project_text_model(
+    app: &tauri::AppHandle,
+    project_name: Option<&str>,
+) -> Result<String, String> {
+    let model = match project_name {
+        Some(project_name) => get_project_settings(app.clone(), project_name.to_string())
+            .await?
+            .map(|settings| settings.text_generation_model)
+            .filter(|model| !model.trim().is_empty() && !is_gemini_model(model)),
+        None => None,
+    };
+    Ok(model.unwrap_or_else(|| "gpt-4o".to_string()))
+}
+
+fn request_timeout(timeout_secs: Option<u64>) -> Duration {
+    request_timeout_or(timeout_secs, DEFAULT_HTTP_TIMEOUT_SECS)
+}
+
+fn request_timeout_or(timeout_secs: Option<u64>, default_secs: u64) -> Duration {
+    Duration::from_secs(
+        timeout_secs
+            .filter(|secs| *secs > 0)
+            .unwrap_or(default_secs),
+    )
+}
+
+fn unix_timestamp_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn get_article_history_from_store(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+) -> Result<ArticleHistoryMap, String> {
+    match store.get(STORE_KEY_ARTICLE_HISTORY) {
+        Some(JsonValue::Null) | None => Ok(ArticleHistoryMap::new()),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| format!("Failed to deserialize article history: {}", e)),
+    }
+}
+
+fn record_article_version(
+    app: &tauri::AppHandle,
+    project_name: &str,
+    title: Option<String>,
+    article_html: String,
+) -> Result<(), String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let mut versions = load_project_history(app, &store, project_name)?;
+    versions.push(StoredArticle {
+        created_at: unix_timestamp_secs(),
+        title,
+        article_html,
+    });
+    if versions.len() > MAX_ARTICLE_HISTORY_PER_PROJECT {
+        let excess = versions.len() - MAX_ARTICLE_HISTORY_PER_PROJECT;
+        versions.drain(..excess);
     }
+    logging::info(&format!(
+        "Recorded article version for project '{}' ({} stored).",
+        project_name,
+        versions.len()
+    ));
+
+    save_project_history(app, &store, project_name, versions)?;
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))
 }
 
 #[tauri::command]
-async fn generate_ideogram_image(
+async fn get_article_history(
     app: tauri::AppHandle,
-    request: ImageGenRequest,
-) -> Result<ImageGenResponse, String> {
-    println!(
-        "Rust: Received image generation request for prompt: {}",
-        request.prompt
-    );
-    if let Some(ratio) = &request.aspect_ratio {
-        println!("Rust: Using aspect ratio: {}", ratio);
-    }
+    project_name: String,
+) -> Result<Vec<StoredArticle>, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    load_project_history(&app, &store, &project_name)
+}
 
-    let api_key = get_api_key(app.clone(), STORE_KEY_IMAGE_API.to_string())
-        .await?
-        .ok_or_else(|| "Ideogram API Key (imageApiKey) not found in store.".to_string())?;
+fn http_recording_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("http_recordings"))
+        .map_err(|e| format!("Failed to get app data dir: {}", e))
+}
 
-    let api_endpoint = "https://api.ideogram.ai/v1/ideogram-v3/generate";
-    let client = Client::new();
-    let mut headers = HeaderMap::new();
-    headers.insert(
-        "Api-Key",
-        HeaderValue::from_str(&api_key).map_err(|e| format!("Invalid API Key format: {}", e))?,
-    );
+fn get_usage_log_from_store(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+) -> Result<Vec<UsageLogEntry>, String> {
+    match store.get(STORE_KEY_USAGE_LOG) {
+        Some(JsonValue::Null) | None => Ok(Vec::new()),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| format!("Failed to deserialize usage log: {}", e)),
+    }
+}
 
-    let mut form = reqwest::multipart::Form::new().text("prompt", request.prompt);
+async fn record_usage(
+    app: &tauri::AppHandle,
+    project: &str,
+    model: &str,
+    usage: OpenAiUsage,
+) -> Result<(), String> {
+    let pricing_table = load_pricing_table(app).await?;
+    let cost_usd = pricing::estimate_cost(
+        &pricing_table,
+        model,
+        usage.prompt_tokens,
+        usage.completion_tokens,
+    );
+    logging::info(&format!(
+        "Usage for '{}' with {}: {} prompt + {} completion tokens (~${:?}).",
+        project, model, usage.prompt_tokens, usage.completion_tokens, cost_usd
+    ));
+
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let mut log = get_usage_log_from_store(&store)?;
+    log.push(UsageLogEntry {
+        project: project.to_string(),
+        timestamp: unix_timestamp_secs(),
+        model: model.to_string(),
+        prompt_tokens: usage.prompt_tokens,
+        completion_tokens: usage.completion_tokens,
+        cost_usd,
+    });
+    if log.len() > MAX_USAGE_LOG_ENTRIES {
+        let excess = log.len() - MAX_USAGE_LOG_ENTRIES;
+        log.drain(..excess);
+    }
+    store.set(
+        STORE_KEY_USAGE_LOG.to_string(),
+        serde_json::to_value(&log).map_err(|e| format!("Failed to serialize usage log: {}", e))?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))
+}
 
-    if let Some(speed) = request.rendering_speed {
-        form = form.text("rendering_speed", speed);
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
     } else {
-        form = form.text("rendering_speed", "TURBO");
+        value.to_string()
     }
+}
 
-    if let Some(ratio) = request.aspect_ratio {
-        form = form.text("aspect_ratio", ratio);
+#[tauri::command]
+async fn export_usage_log_csv(
+    app: tauri::AppHandle,
+    from: Option<u64>,
+    to: Option<u64>,
+) -> Result<String, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let log = get_usage_log_from_store(&store)?;
+
+    let mut csv = String::from(
+        "project,timestamp,model,prompt_tokens,completion_tokens,total_tokens,cost_usd\n",
+    );
+    let mut exported = 0;
+    for entry in log.iter().filter(|entry| {
+        !from.is_some_and(|from| entry.timestamp < from)
+            && !to.is_some_and(|to| entry.timestamp > to)
+    }) {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            csv_field(&entry.project),
+            entry.timestamp,
+            csv_field(&entry.model),
+            entry.prompt_tokens,
+            entry.completion_tokens,
+            entry.prompt_tokens + entry.completion_tokens,
+            entry
+                .cost_usd
+                .map(|cost| format!("{:.6}", cost))
+                .unwrap_or_default()
+        ));
+        exported += 1;
     }
 
-    println!(
-        "Rust: Sending multipart request to Ideogram API: {}",
-        api_endpoint
-    );
-    let response = client
-        .post(api_endpoint)
-        .headers(headers)
-        .multipart(form)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to send request to Ideogram API: {}", e))?;
+    let export_dir = app
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join("exports"))
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    std::fs::create_dir_all(&export_dir)
+        .map_err(|e| format!("Failed to create export directory {:?}: {}", export_dir, e))?;
+    let file_path = export_dir.join(format!("usage_{}.csv", unix_timestamp_secs()));
+    std::fs::write(&file_path, csv)
+        .map_err(|e| format!("Failed to write usage log {:?}: {}", file_path, e))?;
+
+    logging::info(&format!(
+        "Exported {} usage entries (of {}) to {:?}",
+        exported,
+        log.len(),
+        file_path
+    ));
+    Ok(file_path.to_string_lossy().to_string())
+}
 
-    let status = response.status();
-    println!(
-        "Rust: Received response from Ideogram API (Status: {})",
-        status
-    );
-
-    if status.is_success() {
-        let api_response = response
-            .json::<IdeogramApiResponse>()
-            .await
-            .map_err(|e| format!("Failed to parse Ideogram JSON response: {}", e))?;
+#[tauri::command]
+async fn get_http_recording() -> Result<Option<String>, String> {
+    Ok(http_recording::recording_dir().map(|dir| dir.to_string_lossy().to_string()))
+}
 
-        println!("Rust: Parsed Ideogram success response: {:?}", api_response);
+#[tauri::command]
+async fn set_http_recording(
+    app: tauri::AppHandle,
+    enabled: bool,
+) -> Result<Option<String>, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store.set(STORE_KEY_RECORD_HTTP.to_string(), JsonValue::Bool(enabled));
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+
+    let dir = if enabled {
+        Some(http_recording_dir(&app)?)
+    } else {
+        None
+    };
+    logging::info(&format!("HTTP recording set to {:?}", dir));
+    http_recording::set_recording_dir(dir.clone());
+    Ok(dir.map(|dir| dir.to_string_lossy().to_string()))
+}
 
-        if let Some(data_vec) = api_response.data {
-            if let Some(first_result) = data_vec.first() {
-                println!("Rust: Found image URL: {}", first_result.url);
-                return Ok(ImageGenResponse {
-                    image_url: Some(first_result.url.clone()),
-                    error: None,
-                });
-            } else {
-                println!("Rust: Ideogram response successful but 'data' array is empty.");
-                return Err("Ideogram response 'data' array was empty.".to_string());
-            }
-        } else {
-            println!("Rust: Ideogram response successful but 'data' field missing or null.");
-            return Err("Ideogram response missing 'data' field.".to_string());
+fn is_safe_mode(app: &tauri::AppHandle) -> bool {
+    if let Ok(value) = std::env::var(SAFE_MODE_ENV_VAR) {
+        if matches!(
+            value.trim().to_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        ) {
+            return true;
+        }
+    }
+    match app.store(PathBuf::from(STORE_FILE)) {
+        Ok(s) => matches!(s.get(STORE_KEY_SAFE_MODE), Some(JsonValue::Bool(true))),
+        Err(e) => {
+            logging::warn(&format!(
+                "Failed to access store while checking safe mode: {}",
+                e
+            ));
+            false
         }
-    } else {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Could not read error body".to_string());
-        println!(
-            "Rust: Ideogram API request failed - Status: {}, Body: {}",
-            status, error_text
-        );
-        Err(format!(
-            "Ideogram API request failed with status {}: {}",
-            status, error_text
-        ))
     }
 }
 
 #[tauri::command]
-async fn generate_full_article(
-    request: FullArticleRequest,
-    app: tauri::AppHandle,
-) -> Result<ArticleResponse, String> {
-    println!("Generating full article for tool: {}", request.tool_name);
-    println!("Using model: {}", request.model);
-    println!("Targeting word count: {}", request.target_word_count);
-    println!("Using article goal: {}", request.article_goal_prompt);
-    println!("Using example URL: {}", request.example_url);
-    println!(
-        "Received sections (instructions only): {:?}",
-        request.sections
-    );
+async fn get_safe_mode(app: tauri::AppHandle) -> Result<bool, String> {
+    Ok(is_safe_mode(&app))
+}
 
-    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
-        .await?
-        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
-    println!(
-        "[generate_full_article] Using API Key from store: {}...",
-        &api_key[..10]
-    );
+#[tauri::command]
+async fn set_safe_mode(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store.set(STORE_KEY_SAFE_MODE.to_string(), JsonValue::Bool(enabled));
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+    logging::info(&format!("Safe mode set to {}", enabled));
+    Ok(())
+}
 
-    let mut dynamic_sections_prompt_part = String::new();
-    for (index, section) in request.sections.iter().enumerate() {
-        let section_str = format!("Section {}:\n{}\n\n", index + 1, section.instructions);
-        dynamic_sections_prompt_part.push_str(&section_str);
+fn get_projects_from_store(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+) -> Result<ProjectsMap, String> {
+    match store.get(STORE_KEY_PROJECTS) {
+        Some(value) => serde_json::from_value(value.clone()).map_err(|e| {
+            format!(
+                "Failed to deserialize projects: {}. Value was: {}",
+                e, value
+            )
+        }),
+        None => Ok(ProjectsMap::new()),
     }
+}
 
-    let final_prompt = format!(
-        r#"{user_goal_prompt} Use {example_url} as a reference for style and structure where applicable. The article must focus on the AI tool: {tool_name}.
+fn uses_per_project_files(store: &tauri_plugin_store::Store<tauri::Wry>) -> bool {
+    matches!(
+        store.get(STORE_KEY_PER_PROJECT_FILES),
+        Some(JsonValue::Bool(true))
+    )
+}
 
-Recherche approfondie :
-Analyser le site officiel de l'outil, les discussions pertinentes sur X.com, et des sources web fiables pour collecter des informations à jour sur les fonctionnalités, tarifs, avis utilisateurs, et alternatives.
-Vérifier les données pour 2025 afin d'assurer leur actualité et leur précision.
-Éviter toute confusion avec des outils similaires (ex. Groq vs Grok).
+fn get_project_index(store: &tauri_plugin_store::Store<tauri::Wry>) -> Result<Vec<String>, String> {
+    match store.get(STORE_KEY_PROJECT_INDEX) {
+        Some(JsonValue::Null) | None => Ok(Vec::new()),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| format!("Failed to deserialize project index: {}", e)),
+    }
+}
 
-Structure de l'article :
-Based on the instructions below, create distinct sections with appropriate H2 titles. Develop each section thoroughly based on its instructions.
-{dynamic_sections}
+// Anything outside [A-Za-z0-9_-] is percent-encoded so distinct names never share a file.
+fn project_file_path(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+    let mut file_name = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+            file_name.push(c);
+        } else {
+            let mut buf = [0u8; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                file_name.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    Ok(project_store_dir(app)?.join(format!("{}.dat", file_name)))
+}
 
-Rédaction :
-Produire un article de minimum {target_word_count} mots en HTML, incluant :
-Balise <title> : Optimisée pour le SEO, 60-70 caractères, avec des mots-clés comme "avis", "fonctionnalités", "tarifs", "{tool_name}", "2025" (ex. "Avis {tool_name} 2025 : fonctionnalités, tarifs, alternatives").
-Balise <meta description> : 150-160 caractères, incluant un call-to-action engageant (ex. "Découvrez {tool_name} : fonctionnalités, tarifs, avis. Boostez vos projets IA !").
-Balise <h1> : Optimisée pour le lecteur, engageante, différente du <title>, axée sur un bénéfice clé (ex. "Pourquoi {tool_name} révolutionne vos projets IA en 2025").
-Balises H2: Générez des titres H2 descriptifs et pertinents pour chaque section définie ci-dessus en vous basant sur les instructions fournies pour cette section.
-Liens hypertextes : Inclure un lien vers le site officiel de l'outil dans l'introduction, les tarifs, et la conclusion, et des liens vers les sites des alternatives dans la section correspondante. Ne pas inclure de liens vers des sources de recherche.
-Style HTML : NE PAS INCLURE de balise <style> ni de styles CSS en ligne. Générer du HTML sémantique et brut uniquement. Si des tableaux sont nécessaires, utilisez des balises HTML standard (<table>, <tr>, <th>, <td>) sans aucun style CSS.
-Respecter les conventions typographiques françaises : minuscules sauf pour débuts de phrases, titres, et noms propres.
-Utiliser un ton engageant, professionnel, et accessible, avec des exemples concrets pour illustrer les cas d'usage.
-Assurez-vous que la sortie est uniquement le code HTML complet de l'article, en commençant par <!DOCTYPE html> ou <html> et se terminant par </html>. N'incluez AUCUN texte ou explication avant ou après le code HTML.
-IMPORTANT: The final article content within the HTML MUST contain at least {target_word_count} words. Expand significantly on each section's instructions to achieve this length.
-"#,
-        user_goal_prompt = request.article_goal_prompt,
-        example_url = request.example_url,
-        tool_name = request.tool_name,
-        dynamic_sections = dynamic_sections_prompt_part,
-        target_word_count = request.target_word_count
-    );
+fn project_store_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(PROJECT_STORE_DIR))
+        .map_err(|e| format!("Failed to resolve app data dir: {}", e))
+}
 
-    println!(
-        "--- Final Prompt Being Sent ---\n{}\n--- End Final Prompt ---",
-        final_prompt
+fn load_project_file(
+    app: &tauri::AppHandle,
+    name: &str,
+) -> Result<Option<ProjectSettings>, String> {
+    let path = project_file_path(app, name)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let project_store = app
+        .store(path.clone())
+        .map_err(|e| format!("Failed to access project store {:?}: {}", path, e))?;
+    project_store
+        .reload()
+        .map_err(|e| format!("Failed to load project store {:?}: {}", path, e))?;
+    match project_store.get(PROJECT_STORE_KEY_SETTINGS) {
+        Some(JsonValue::Null) | None => Ok(None),
+        Some(value) => serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|e| format!("Failed to deserialize project '{}': {}", name, e)),
+    }
+}
+
+fn write_project_file(
+    app: &tauri::AppHandle,
+    name: &str,
+    settings: &ProjectSettings,
+) -> Result<(), String> {
+    let path = project_file_path(app, name)?;
+    let project_store = app
+        .store(path.clone())
+        .map_err(|e| format!("Failed to access project store {:?}: {}", path, e))?;
+    project_store.set(
+        PROJECT_STORE_KEY_SETTINGS.to_string(),
+        serde_json::to_value(settings)
+            .map_err(|e| format!("Failed to serialize project '{}': {}", name, e))?,
     );
+    project_store
+        .save()
+        .map_err(|e| format!("Failed to save project store {:?}: {}", path, e))
+}
 
-    if api_key.is_empty() {
-        return Err("Fetched OpenAI API key is empty".to_string());
+fn remove_project_file(app: &tauri::AppHandle, name: &str) -> Result<(), String> {
+    let path = project_file_path(app, name)?;
+    if let Ok(project_store) = app.store(path.clone()) {
+        project_store.clear();
     }
+    if path.exists() {
+        std::fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove project file {:?}: {}", path, e))?;
+    }
+    Ok(())
+}
 
-    let client = reqwest::Client::new();
-    let api_url = "https://api.openai.com/v1/chat/completions";
-
-    let request_body = serde_json::json!({
-        "model": request.model,
-        "messages": [
-            {
-                "role": "system",
-                "content": format!("You are a helpful assistant tasked with writing detailed AI tool review articles in French HTML format based on user instructions and web searches. Generate appropriate H2 titles for each section based on the provided instructions. Prioritize reaching the target word count of {}.", request.target_word_count)
-            },
-            {
-                "role": "user",
-                "content": final_prompt
-            }
-        ],
-        "temperature": 0.7
-    });
+fn load_project_file_history(
+    app: &tauri::AppHandle,
+    name: &str,
+) -> Result<Vec<StoredArticle>, String> {
+    let path = project_file_path(app, name)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let project_store = app
+        .store(path.clone())
+        .map_err(|e| format!("Failed to access project store {:?}: {}", path, e))?;
+    project_store
+        .reload()
+        .map_err(|e| format!("Failed to load project store {:?}: {}", path, e))?;
+    match project_store.get(PROJECT_STORE_KEY_HISTORY) {
+        Some(JsonValue::Null) | None => Ok(Vec::new()),
+        Some(value) => serde_json::from_value(value.clone()).map_err(|e| {
+            format!(
+                "Failed to deserialize article history of project '{}': {}",
+                name, e
+            )
+        }),
+    }
+}
 
-    println!("Sending prompt to OpenAI API...");
-    let response = client
-        .post(api_url)
-        .bearer_auth(&api_key)
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to send request to OpenAI: {}", e))?;
+fn write_project_file_history(
+    app: &tauri::AppHandle,
+    name: &str,
+    versions: &[StoredArticle],
+) -> Result<(), String> {
+    let path = project_file_path(app, name)?;
+    let project_store = app
+        .store(path.clone())
+        .map_err(|e| format!("Failed to access project store {:?}: {}", path, e))?;
+    if versions.is_empty() {
+        project_store.delete(PROJECT_STORE_KEY_HISTORY);
+    } else {
+        project_store.set(
+            PROJECT_STORE_KEY_HISTORY.to_string(),
+            serde_json::to_value(versions)
+                .map_err(|e| format!("Failed to serialize article history: {}", e))?,
+        );
+    }
+    project_store
+        .save()
+        .map_err(|e| format!("Failed to save project store {:?}: {}", path, e))
+}
 
-    let status = response.status();
-    let response_body_text = response
-        .text()
-        .await
-        .map_err(|e| format!("Failed to read OpenAI response body: {}", e))?;
-    println!("Received response from OpenAI API (Status: {})", status);
+// Merges history still held in the main store into the project's own file.
+fn move_history_into_project_file(
+    app: &tauri::AppHandle,
+    history: &mut ArticleHistoryMap,
+    name: &str,
+) -> Result<bool, String> {
+    let Some(moved) = history.remove(name) else {
+        return Ok(false);
+    };
+    let mut versions = load_project_file_history(app, name)?;
+    versions.extend(moved);
+    versions.sort_by_key(|version| version.created_at);
+    if versions.len() > MAX_ARTICLE_HISTORY_PER_PROJECT {
+        let excess = versions.len() - MAX_ARTICLE_HISTORY_PER_PROJECT;
+        versions.drain(..excess);
+    }
+    write_project_file_history(app, name, &versions)?;
+    Ok(true)
+}
 
-    if status.is_success() {
-        match serde_json::from_str::<OpenAiApiResponse>(&response_body_text) {
-            Ok(parsed_response) => {
-                if let Some(choice) = parsed_response.choices.get(0) {
-                    let full_html_from_llm = choice.message.content.clone();
-                    println!(
-                        "Rust: Full HTML from LLM received. Length: {}",
-                        full_html_from_llm.len()
-                    );
+fn is_indexed_project(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+    name: &str,
+) -> Result<bool, String> {
+    Ok(uses_per_project_files(store)
+        && get_project_index(store)?
+            .iter()
+            .any(|indexed| indexed == name))
+}
 
-                    // Extract title
-                    let title_regex =
-                        Regex::new(r"(?i)<title>(.*?)</title>").expect("Invalid title regex");
-                    let extracted_title = title_regex
-                        .captures(&full_html_from_llm)
-                        .and_then(|caps| caps.get(1))
-                        .map(|m| m.as_str().trim().to_string());
+fn set_article_history_in_store(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+    history: &ArticleHistoryMap,
+) -> Result<(), String> {
+    store.set(
+        STORE_KEY_ARTICLE_HISTORY.to_string(),
+        serde_json::to_value(history)
+            .map_err(|e| format!("Failed to serialize article history: {}", e))?,
+    );
+    Ok(())
+}
 
-                    if let Some(ref title) = extracted_title {
-                        println!("Rust: Extracted title: {}", title);
-                    } else {
-                        println!("Rust: No <title> tag found in LLM response.");
-                    }
+// In per-project mode an indexed project's history lives in its own file; the
+// main store only keeps history of projects that no longer exist.
+fn load_article_history(
+    app: &tauri::AppHandle,
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+) -> Result<ArticleHistoryMap, String> {
+    let mut history = get_article_history_from_store(store)?;
+    if uses_per_project_files(store) {
+        for name in get_project_index(store)? {
+            let versions = load_project_file_history(app, &name)?;
+            if !versions.is_empty() {
+                history.insert(name, versions);
+            }
+        }
+    }
+    Ok(history)
+}
 
-                    // Extract body content
-                    let mut body_only_html = extract_body_content(&full_html_from_llm);
-                    println!(
-                        "Rust: Body-only HTML extracted. Length: {}",
-                        body_only_html.len()
-                    );
+fn load_project_history(
+    app: &tauri::AppHandle,
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+    name: &str,
+) -> Result<Vec<StoredArticle>, String> {
+    if is_indexed_project(store, name)? {
+        return load_project_file_history(app, name);
+    }
+    Ok(get_article_history_from_store(store)?
+        .remove(name)
+        .unwrap_or_default())
+}
 
-                    // Remove H1 tag and its content from the extracted body
-                    let h1_regex =
-                        Regex::new(r"(?is)<h1(?:[^>]*)>.*?</h1>").expect("Invalid H1 regex");
-                    if h1_regex.is_match(&body_only_html) {
-                        println!("Rust: Found H1 tag in body, removing it.");
-                        body_only_html = h1_regex.replace(&body_only_html, "").trim().to_string();
-                        println!(
-                            "Rust: Body HTML after H1 removal. New Length: {}",
-                            body_only_html.len()
-                        );
-                    } else {
-                        println!("Rust: No H1 tag found in extracted body content.");
-                    }
+// Callers still save the main store afterwards.
+fn save_project_history(
+    app: &tauri::AppHandle,
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+    name: &str,
+    versions: Vec<StoredArticle>,
+) -> Result<(), String> {
+    if is_indexed_project(store, name)? {
+        return write_project_file_history(app, name, &versions);
+    }
+    let mut history = get_article_history_from_store(store)?;
+    if versions.is_empty() {
+        history.remove(name);
+    } else {
+        history.insert(name.to_string(), versions);
+    }
+    set_article_history_in_store(store, &history)
+}
 
-                    Ok(ArticleResponse {
-                        article_text: body_only_html,
-                        title: extracted_title,
-                    })
-                } else {
-                    println!("OpenAI response successful but 'choices' array is empty.");
-                    Err("OpenAI response has no choices".to_string())
-                }
+// In per-project mode the main store only keeps the list of names; each
+// project's settings live in projects/<name>.dat.
+fn load_projects(
+    app: &tauri::AppHandle,
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+) -> Result<ProjectsMap, String> {
+    if !uses_per_project_files(store) {
+        return get_projects_from_store(store);
+    }
+    let mut projects = ProjectsMap::new();
+    for name in get_project_index(store)? {
+        match load_project_file(app, &name)? {
+            Some(settings) => {
+                projects.insert(name, settings);
             }
-            Err(e) => {
-                eprintln!("Detailed parsing error: {:?}", e);
-                eprintln!("Raw response body was:\n{}", response_body_text);
-                Err(format!(
-                    "Failed to parse OpenAI response into expected structure: {}",
-                    e
-                ))
+            None => logging::warn(&format!(
+                "Project '{}' is indexed but its file is missing.",
+                name
+            )),
+        }
+    }
+    Ok(projects)
+}
+
+fn load_project(
+    app: &tauri::AppHandle,
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+    name: &str,
+) -> Result<Option<ProjectSettings>, String> {
+    if !uses_per_project_files(store) {
+        return Ok(get_projects_from_store(store)?.get(name).cloned());
+    }
+    if !get_project_index(store)?
+        .iter()
+        .any(|indexed| indexed == name)
+    {
+        return Ok(None);
+    }
+    load_project_file(app, name)
+}
+
+// Callers still save the main store afterwards.
+fn save_projects(
+    app: &tauri::AppHandle,
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+    projects: &ProjectsMap,
+) -> Result<(), String> {
+    if !uses_per_project_files(store) {
+        store.set(
+            STORE_KEY_PROJECTS.to_string(),
+            serde_json::to_value(projects)
+                .map_err(|e| format!("Failed to serialize projects: {}", e))?,
+        );
+        return Ok(());
+    }
+    let previous_index = get_project_index(store)?;
+    let mut history = get_article_history_from_store(store)?;
+    let mut history_changed = false;
+    for (name, settings) in projects {
+        write_project_file(app, name, settings)?;
+        if !previous_index.contains(name) {
+            history_changed |= move_history_into_project_file(app, &mut history, name)?;
+        }
+    }
+    for name in previous_index {
+        if !projects.contains_key(&name) {
+            // History outlives its project, as it does in monolithic mode.
+            let versions = load_project_file_history(app, &name)?;
+            if !versions.is_empty() {
+                history.insert(name.clone(), versions);
+                history_changed = true;
             }
+            remove_project_file(app, &name)?;
+        }
+    }
+    if history_changed {
+        set_article_history_in_store(store, &history)?;
+    }
+    let mut names: Vec<String> = projects.keys().cloned().collect();
+    names.sort_unstable();
+    store.set(
+        STORE_KEY_PROJECT_INDEX.to_string(),
+        serde_json::to_value(names).unwrap_or(JsonValue::Null),
+    );
+    Ok(())
+}
+
+fn migrate_project_storage(
+    app: &tauri::AppHandle,
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+    per_project: bool,
+) -> Result<usize, String> {
+    let mut projects = load_projects(app, store)?;
+    if per_project {
+        // Also picks up projects left in the monolithic map by an interrupted migration.
+        for (name, settings) in get_projects_from_store(store)? {
+            projects.entry(name).or_insert(settings);
+        }
+    }
+    let count = projects.len();
+    let previous_index = get_project_index(store)?;
+    // Read before the flag flips, while per-project history files are still consulted.
+    let merged_history = load_article_history(app, store)?;
+
+    store.set(
+        STORE_KEY_PER_PROJECT_FILES.to_string(),
+        JsonValue::Bool(per_project),
+    );
+    save_projects(app, store, &projects)?;
+    if per_project {
+        store.delete(STORE_KEY_PROJECTS);
+        let mut history = get_article_history_from_store(store)?;
+        for name in projects.keys() {
+            move_history_into_project_file(app, &mut history, name)?;
         }
+        set_article_history_in_store(store, &history)?;
     } else {
-        eprintln!(
-            "OpenAI API request failed - Status: {}, Body:\n{}",
-            status, response_body_text
-        );
-        Err(format!(
-            "OpenAI API request failed with status {}: {}",
-            status, response_body_text
-        ))
+        store.delete(STORE_KEY_PROJECT_INDEX);
+        set_article_history_in_store(store, &merged_history)?;
+    }
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+
+    // Old files go only once the monolithic map is safely on disk.
+    if !per_project {
+        for name in previous_index {
+            remove_project_file(app, &name)?;
+        }
     }
+    logging::info(&format!(
+        "Migrated {} project(s) to {} storage.",
+        count,
+        if per_project {
+            "per-project"
+        } else {
+            "monolithic"
+        }
+    ));
+    Ok(count)
 }
 
 #[tauri::command]
-async fn suggest_image_prompts(
-    request: SuggestImagePromptsRequest,
-    app: tauri::AppHandle,
-) -> Result<SuggestImagePromptsResponse, String> {
-    println!("Rust: Received request to suggest image prompts.");
+async fn get_project_storage_mode(app: tauri::AppHandle) -> Result<bool, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    Ok(uses_per_project_files(&store))
+}
 
-    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
-        .await?
-        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
-    println!("Rust: Using API Key for prompt suggestion.");
+#[tauri::command]
+async fn set_project_storage_mode(
+    app: tauri::AppHandle,
+    per_project: bool,
+) -> Result<usize, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    if uses_per_project_files(&store) == per_project {
+        logging::info(&format!(
+            "Project storage already in {} mode.",
+            if per_project {
+                "per-project"
+            } else {
+                "monolithic"
+            }
+        ));
+        return Ok(0);
+    }
+    migrate_project_storage(&app, &store, per_project)
+}
 
-    let suggestion_prompt = format!(
-        r#"Based on the following article text, suggest 3-5 diverse image prompts suitable for illustrating it. Focus on key themes, concepts, or visual metaphors described in the text.
+#[tauri::command]
+async fn create_project(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Project name cannot be empty".to_string());
+    }
+    let _guard = lock_store_writes()?;
+    let store_result = app.store(PathBuf::from(STORE_FILE));
+    match store_result {
+        Ok(s) => {
+            s.reload()
+                .map_err(|e| format!("Failed to load store: {}", e))?;
+            let mut projects = load_projects(&app, &s)?;
 
-        Output ONLY a valid JSON array of strings, where each string is a suggested image prompt. Example output format: ["prompt idea 1", "prompt idea 2", "a third idea"]
+            if projects.contains_key(&name) {
+                return Err(format!("Project '{}' already exists.", name));
+            }
 
-        Article Text:
-        ---
-        {article}
-        ---
+            let default_settings = ProjectSettings {
+                wordpress_url: default_string(),
+                wordpress_user: default_string(),
+                wordpress_pass: default_string(),
+                tool_name: name.clone(),
+                article_goal_prompt: default_string(),
+                example_url: default_string(),
+                sections: default_sections(),
+                text_generation_model: default_text_model(),
+                target_word_count: default_word_count(),
+                footer_html: None,
+                preferred_seed: None,
+                image_download_timeout_secs: default_image_download_timeout_secs(),
+                image_download_concurrency: default_image_download_concurrency(),
+                language: default_language(),
+                french_typography: false,
+                outbound_link_rel: None,
+                escalate_on_short: None,
+                disambiguation_notes: None,
+                max_word_count: None,
+                required_elements: None,
+                utm_params: None,
+                max_payload_bytes: None,
+                upload_retry: None,
+                official_url: None,
+                daily_upload_limit: None,
+                heading_keywords: None,
+                openai_base_url: None,
+            };
+            projects.insert(name.clone(), default_settings);
+            save_projects(&app, &s, &projects)?;
 
-        Suggested Prompts (JSON Array Only):"#,
-        article = request.article_text
-    );
+            s.save()
+                .map_err(|e| format!("Failed to save store: {}", e))?;
+            Ok(())
+        }
+        Err(e) => Err(format!("Failed to access store: {}", e)),
+    }
+}
 
-    println!(
-        "--- Prompt for Image Suggestion ---\n{}\n--- End Prompt ---",
-        suggestion_prompt
-    );
+#[tauri::command]
+async fn get_projects(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    let _guard = lock_store_writes()?;
+    let store_result = app.store(PathBuf::from(STORE_FILE));
+    match store_result {
+        Ok(s) => {
+            s.reload()
+                .map_err(|e| format!("Failed to load store: {}", e))?;
+            let mut names: Vec<String> = if uses_per_project_files(&s) {
+                get_project_index(&s)?
+            } else {
+                get_projects_from_store(&s)?.keys().cloned().collect()
+            };
+            names.sort_unstable();
+            Ok(names)
+        }
+        Err(e) => Err(format!("Failed to access store: {}", e)),
+    }
+}
 
-    let client = reqwest::Client::new();
-    let api_url = "https://api.openai.com/v1/chat/completions";
+#[tauri::command]
+async fn get_project_settings(
+    app: tauri::AppHandle,
+    name: String,
+) -> Result<Option<ProjectSettings>, String> {
+    let _guard = lock_store_writes()?;
+    let store_result = app.store(PathBuf::from(STORE_FILE));
+    match store_result {
+        Ok(s) => {
+            s.reload()
+                .map_err(|e| format!("Failed to load store: {}", e))?;
+            load_project(&app, &s, &name)
+        }
+        Err(e) => Err(format!("Failed to access store: {}", e)),
+    }
+}
 
-    let request_body = serde_json::json!({
-        "model": "gpt-4-turbo",
-        "messages": [
-            {
-                "role": "system",
-                "content": "You are an assistant that suggests image prompts based on provided text and outputs ONLY a valid JSON array of strings."
-            },
+fn normalize_instructions(instructions: &str) -> String {
+    instructions
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn check_sections(sections: &[SectionDefinitionData]) -> SectionValidation {
+    let blank_indices: Vec<usize> = sections
+        .iter()
+        .enumerate()
+        .filter(|(_, section)| section.instructions.trim().is_empty())
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut groups: Vec<(HashSet<String>, Vec<usize>)> = Vec::new();
+    for (index, section) in sections.iter().enumerate() {
+        let words: HashSet<String> = normalize_instructions(&section.instructions)
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+        if words.is_empty() {
+            continue;
+        }
+        match groups
+            .iter_mut()
+            .find(|(key, _)| jaccard_similarity(key, &words) >= SECTION_DUPLICATE_THRESHOLD)
+        {
+            Some((_, indices)) => indices.push(index),
+            None => groups.push((words, vec![index])),
+        }
+    }
+    let duplicate_groups: Vec<Vec<usize>> = groups
+        .into_iter()
+        .map(|(_, indices)| indices)
+        .filter(|indices| indices.len() > 1)
+        .collect();
+
+    SectionValidation {
+        is_valid: blank_indices.is_empty(),
+        blank_indices,
+        duplicate_groups,
+    }
+}
+
+#[tauri::command]
+async fn validate_sections(
+    sections: Vec<SectionDefinitionData>,
+) -> Result<SectionValidation, String> {
+    let validation = check_sections(&sections);
+    logging::info(&format!(
+        "Validated {} sections: {} blank, {} duplicate group(s).",
+        sections.len(),
+        validation.blank_indices.len(),
+        validation.duplicate_groups.len()
+    ));
+    Ok(validation)
+}
+
+#[tauri::command]
+async fn save_project_settings(
+    app: tauri::AppHandle,
+    name: String,
+    settings: JsonValue,
+) -> Result<Vec<String>, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let mut projects = load_projects(&app, &store)?;
+    let existing = projects
+        .get(&name)
+        .ok_or_else(|| format!("Project '{}' not found.", name))?;
+    let settings = merge_project_settings(existing, settings)?;
+
+    let section_validation = check_sections(&settings.sections);
+    if !section_validation.blank_indices.is_empty() {
+        let positions: Vec<String> = section_validation
+            .blank_indices
+            .iter()
+            .map(|index| (index + 1).to_string())
+            .collect();
+        return Err(format!(
+            "Section(s) {} have empty instructions (indices: {:?}).",
+            positions.join(", "),
+            section_validation.blank_indices
+        ));
+    }
+    let warnings = section_duplicate_warnings(&section_validation);
+    for warning in &warnings {
+        logging::warn(warning);
+    }
+    check_target_word_count(settings.target_word_count, &settings.text_generation_model)?;
+    if let Some(upload_retry) = &settings.upload_retry {
+        check_upload_config(upload_retry)?;
+    }
+    if let Some(base_url) = settings
+        .openai_base_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+    {
+        let parsed = reqwest::Url::parse(base_url)
+            .map_err(|e| format!("Invalid OpenAI base URL '{}': {}", base_url, e))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(format!(
+                "OpenAI base URL '{}' must use http or https.",
+                base_url
+            ));
+        }
+    }
+    if let Some(max_words) = settings.max_word_count {
+        if max_words < settings.target_word_count {
+            return Err(format!(
+                "Maximum word count {} is below the target word count {}.",
+                max_words, settings.target_word_count
+            ));
+        }
+    }
+
+    projects.insert(name, settings);
+    save_projects(&app, &store, &projects)?;
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+    Ok(warnings)
+}
+
+fn section_duplicate_warnings(validation: &SectionValidation) -> Vec<String> {
+    validation
+        .duplicate_groups
+        .iter()
+        .map(|group| {
+            let positions: Vec<String> =
+                group.iter().map(|index| (index + 1).to_string()).collect();
+            format!(
+                "Sections {} have near-duplicate instructions.",
+                positions.join(", ")
+            )
+        })
+        .collect()
+}
+
+// The UI only sends the fields it edits; anything it leaves out keeps its stored value.
+fn merge_project_settings(
+    existing: &ProjectSettings,
+    patch: JsonValue,
+) -> Result<ProjectSettings, String> {
+    let JsonValue::Object(patch) = patch else {
+        return Err("Project settings must be a JSON object.".to_string());
+    };
+    let mut merged = serde_json::to_value(existing)
+        .map_err(|e| format!("Failed to serialize project settings: {}", e))?;
+    if let JsonValue::Object(fields) = &mut merged {
+        fields.extend(patch);
+    }
+    serde_json::from_value(merged).map_err(|e| format!("Invalid project settings: {}", e))
+}
+
+#[tauri::command]
+async fn normalize_sections(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<SectionNormalizationResult, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let mut projects = load_projects(&app, &store)?;
+    let settings = projects
+        .get_mut(&project_name)
+        .ok_or_else(|| format!("Project '{}' not found.", project_name))?;
+
+    let before_count = settings.sections.len();
+    let mut empty_removed = 0;
+    let mut duplicates_removed = 0;
+    let mut cleaned: Vec<SectionDefinitionData> = Vec::new();
+    for section in settings.sections.drain(..) {
+        let instructions = section.instructions.trim().to_string();
+        if instructions.is_empty() {
+            empty_removed += 1;
+        } else if cleaned
+            .iter()
+            .any(|existing| existing.instructions == instructions)
+        {
+            duplicates_removed += 1;
+        } else {
+            cleaned.push(SectionDefinitionData { instructions });
+        }
+    }
+    settings.sections = cleaned;
+    let after_count = settings.sections.len();
+
+    save_projects(&app, &store, &projects)?;
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+
+    logging::info(&format!(
+        "Normalized sections for '{}': {} -> {} ({} empty, {} duplicate removed).",
+        project_name, before_count, after_count, empty_removed, duplicates_removed
+    ));
+    Ok(SectionNormalizationResult {
+        before_count,
+        after_count,
+        empty_removed,
+        duplicates_removed,
+    })
+}
+
+// Maximum completion tokens per model family; longer prefixes win.
+const MODEL_MAX_OUTPUT_TOKENS: &[(&str, u32)] = &[
+    ("gpt-4o", 16384),
+    ("gpt-4o-mini", 16384),
+    ("gpt-4.1", 32768),
+    ("gpt-4-turbo", 4096),
+    ("gpt-4", 8192),
+    ("gpt-3.5-turbo", 4096),
+];
+
+fn max_output_tokens_for_model(model: &str) -> Option<u32> {
+    MODEL_MAX_OUTPUT_TOKENS
+        .iter()
+        .filter(|(prefix, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, tokens)| *tokens)
+}
+
+fn check_target_word_count(word_count: u32, model: &str) -> Result<(), String> {
+    if !(MIN_TARGET_WORD_COUNT..=MAX_TARGET_WORD_COUNT).contains(&word_count) {
+        return Err(format!(
+            "Target word count {} is out of range: it must be between {} and {} words.",
+            word_count, MIN_TARGET_WORD_COUNT, MAX_TARGET_WORD_COUNT
+        ));
+    }
+    // French HTML output runs about 2 tokens per word (see estimate_article_tokens).
+    if let Some(max_tokens) = max_output_tokens_for_model(model) {
+        let max_words = max_tokens / 2;
+        if word_count > max_words {
+            return Err(format!(
+                "Target word count {} is too long for model '{}', which can produce at most about {} words per response.",
+                word_count, model, max_words
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn audit_project_settings(settings: &ProjectSettings) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    if settings.tool_name.trim().is_empty() {
+        issues.push("Tool name is empty.".to_string());
+    }
+    if settings.article_goal_prompt.trim().is_empty() {
+        issues.push("Article goal prompt is empty.".to_string());
+    }
+    if settings.example_url.trim().is_empty() {
+        issues.push("Example URL is empty.".to_string());
+    }
+    if settings.sections.is_empty() {
+        issues.push("No sections are defined.".to_string());
+    }
+    let blank_sections: Vec<String> = settings
+        .sections
+        .iter()
+        .enumerate()
+        .filter(|(_, section)| section.instructions.trim().is_empty())
+        .map(|(index, _)| (index + 1).to_string())
+        .collect();
+    if !blank_sections.is_empty() {
+        issues.push(format!(
+            "Section(s) {} have no instructions.",
+            blank_sections.join(", ")
+        ));
+    }
+    if settings.text_generation_model.trim().is_empty() {
+        issues.push("Text generation model is empty.".to_string());
+    }
+    if let Err(e) =
+        check_target_word_count(settings.target_word_count, &settings.text_generation_model)
+    {
+        issues.push(e);
+    }
+    if settings.wordpress_url.trim().is_empty() {
+        issues.push("WordPress URL is empty.".to_string());
+    }
+    if settings.wordpress_user.trim().is_empty() {
+        issues.push("WordPress user is empty.".to_string());
+    }
+    if settings.wordpress_pass.trim().is_empty() {
+        issues.push("WordPress Application Password is empty.".to_string());
+    }
+
+    issues
+}
+
+#[tauri::command]
+async fn audit_projects(app: tauri::AppHandle) -> Result<Vec<ProjectAudit>, String> {
+    let _guard = lock_store_writes()?;
+    let store_result = app.store(PathBuf::from(STORE_FILE));
+    match store_result {
+        Ok(s) => {
+            s.reload()
+                .map_err(|e| format!("Failed to load store: {}", e))?;
+            let projects = load_projects(&app, &s)?;
+
+            let mut audits: Vec<ProjectAudit> = projects
+                .iter()
+                .map(|(name, settings)| {
+                    let issues = audit_project_settings(settings);
+                    ProjectAudit {
+                        project_name: name.clone(),
+                        is_complete: issues.is_empty(),
+                        issues,
+                    }
+                })
+                .collect();
+            audits.sort_unstable_by(|a, b| a.project_name.cmp(&b.project_name));
+
+            logging::info(&format!(
+                "Audited {} projects, {} incomplete.",
+                audits.len(),
+                audits.iter().filter(|audit| !audit.is_complete).count()
+            ));
+            Ok(audits)
+        }
+        Err(e) => Err(format!("Failed to access store: {}", e)),
+    }
+}
+
+#[tauri::command]
+async fn get_default_project(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let projects = load_projects(&app, &store)?;
+    // A default pointing at a project that no longer exists is treated as unset.
+    Ok(store
+        .get(STORE_KEY_DEFAULT_PROJECT)
+        .and_then(|value| value.as_str().map(String::from))
+        .filter(|name| projects.contains_key(name)))
+}
+
+#[tauri::command]
+async fn set_default_project(app: tauri::AppHandle, name: Option<String>) -> Result<(), String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+
+    let value = match name {
+        Some(name) => {
+            if load_project(&app, &store, &name)?.is_none() {
+                return Err(format!("Project '{}' not found.", name));
+            }
+            JsonValue::String(name)
+        }
+        None => JsonValue::Null,
+    };
+    logging::info(&format!("Setting default project to {}", value));
+    store.set(STORE_KEY_DEFAULT_PROJECT.to_string(), value);
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))
+}
+
+#[tauri::command]
+async fn delete_project(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    logging::info(&format!("Attempting to delete project '{}'", name));
+    let _guard = lock_store_writes()?;
+    let store_result = app.store(PathBuf::from(STORE_FILE));
+    match store_result {
+        Ok(s) => {
+            logging::info("Store accessed for deletion.");
+            s.reload().map_err(|e| {
+                let err_msg = format!("Failed to load store: {}", e);
+                logging::error(&err_msg);
+                err_msg
+            })?;
+
+            let mut projects = load_projects(&app, &s).map_err(|e| {
+                logging::error(&format!("Error getting projects from store: {}", e));
+                e
+            })?;
+            logging::info(&format!("Projects map loaded. Size: {}", projects.len()));
+
+            if projects.remove(&name).is_none() {
+                logging::info(&format!("Project '{}' not found in map.", name));
+                return Err(format!("Project '{}' not found.", name));
+            }
+            logging::info(&format!("Project '{}' removed from map.", name));
+
+            save_projects(&app, &s, &projects).map_err(|e| {
+                let err_msg = format!("Failed to save updated projects: {}", e);
+                logging::error(&err_msg);
+                err_msg
+            })?;
+            logging::info("Updated projects map set in store (in memory).");
+
+            if s.get(STORE_KEY_DEFAULT_PROJECT)
+                .and_then(|v| v.as_str().map(String::from))
+                == Some(name.clone())
             {
-                "role": "user",
-                "content": suggestion_prompt
+                s.set(STORE_KEY_DEFAULT_PROJECT.to_string(), JsonValue::Null);
+                logging::info(&format!("Cleared default project '{}'.", name));
             }
+
+            s.save().map_err(|e| {
+                let err_msg = format!("Failed to save store after deletion: {}", e);
+                logging::error(&err_msg);
+                err_msg
+            })?;
+
+            logging::info(&format!(
+                "Store saved successfully after deleting '{}'.",
+                name
+            ));
+            Ok(())
+        }
+        Err(e) => {
+            let err_msg = format!("Failed to access store: {}", e);
+            logging::error(&err_msg);
+            Err(err_msg)
+        }
+    }
+}
+
+#[tauri::command]
+async fn rename_project(
+    app: tauri::AppHandle,
+    old_name: String,
+    new_name: String,
+) -> Result<(), String> {
+    logging::info(&format!(
+        "Attempting to rename project '{}' to '{}'",
+        old_name, new_name
+    ));
+    if new_name.trim().is_empty() {
+        return Err("Project name cannot be empty".to_string());
+    }
+    // Held for the whole rename so concurrent history, usage and batch writes
+    // can't land under the old name halfway through.
+    let _guard = lock_store_writes()?;
+    let s = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    s.reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let mut projects = load_projects(&app, &s)?;
+
+    if !projects.contains_key(&old_name) {
+        return Err(format!("Project '{}' not found.", old_name));
+    }
+    if new_name == old_name {
+        return Ok(());
+    }
+    if projects.contains_key(&new_name) {
+        return Err(format!("Project '{}' already exists.", new_name));
+    }
+
+    let mut settings = projects
+        .remove(&old_name)
+        .ok_or_else(|| format!("Project '{}' not found.", old_name))?;
+    // A custom tool name was chosen deliberately and should survive the rename.
+    if settings.tool_name == old_name {
+        settings.tool_name = new_name.clone();
+    }
+    projects.insert(new_name.clone(), settings);
+    save_projects(&app, &s, &projects)?;
+
+    if s.get(STORE_KEY_DEFAULT_PROJECT)
+        .and_then(|v| v.as_str().map(String::from))
+        == Some(old_name.clone())
+    {
+        s.set(
+            STORE_KEY_DEFAULT_PROJECT.to_string(),
+            JsonValue::String(new_name.clone()),
+        );
+    }
+
+    // Everything else keyed by project name follows it, so cost reports and resumed
+    // batches keep pointing at the renamed project.
+    let versions = load_project_history(&app, &s, &old_name)?;
+    if !versions.is_empty() {
+        save_project_history(&app, &s, &new_name, versions)?;
+        save_project_history(&app, &s, &old_name, Vec::new())?;
+    }
+
+    let mut counters = get_daily_uploads_from_store(&s)?;
+    if let Some(counter) = counters.remove(&old_name) {
+        counters.insert(new_name.clone(), counter);
+        s.set(
+            STORE_KEY_DAILY_UPLOADS.to_string(),
+            serde_json::to_value(&counters)
+                .map_err(|e| format!("Failed to serialize daily upload counters: {}", e))?,
+        );
+    }
+
+    let mut log = get_usage_log_from_store(&s)?;
+    let mut renamed_entries = 0;
+    for entry in log.iter_mut().filter(|entry| entry.project == old_name) {
+        entry.project = new_name.clone();
+        renamed_entries += 1;
+    }
+    if renamed_entries > 0 {
+        s.set(
+            STORE_KEY_USAGE_LOG.to_string(),
+            serde_json::to_value(&log)
+                .map_err(|e| format!("Failed to serialize usage log: {}", e))?,
+        );
+    }
+
+    let mut batches = get_batches_from_store(&s)?;
+    let mut renamed_items = 0;
+    for item in batches
+        .values_mut()
+        .flat_map(|batch| batch.items.iter_mut())
+    {
+        if item.request.project_name.as_deref() == Some(old_name.as_str()) {
+            item.request.project_name = Some(new_name.clone());
+            renamed_items += 1;
+        }
+    }
+    if renamed_items > 0 {
+        s.set(
+            STORE_KEY_BATCHES.to_string(),
+            serde_json::to_value(&batches)
+                .map_err(|e| format!("Failed to serialize batches: {}", e))?,
+        );
+    }
+    logging::info(&format!(
+        "Moved {} usage log entries and {} batch items to '{}'.",
+        renamed_entries, renamed_items, new_name
+    ));
+
+    s.save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+    logging::info(&format!(
+        "Project '{}' renamed to '{}'.",
+        old_name, new_name
+    ));
+    Ok(())
+}
+
+#[tauri::command]
+async fn duplicate_project(
+    app: tauri::AppHandle,
+    source_name: String,
+    new_name: String,
+    copy_credentials: bool,
+) -> Result<(), String> {
+    if new_name.trim().is_empty() {
+        return Err("Project name cannot be empty".to_string());
+    }
+    let _guard = lock_store_writes()?;
+    let s = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    s.reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let mut projects = load_projects(&app, &s)?;
+
+    if projects.contains_key(&new_name) {
+        return Err(format!("Project '{}' already exists.", new_name));
+    }
+    let mut settings = projects
+        .get(&source_name)
+        .cloned()
+        .ok_or_else(|| format!("Project '{}' not found.", source_name))?;
+    if settings.tool_name == source_name {
+        settings.tool_name = new_name.clone();
+    }
+    if !copy_credentials {
+        settings.wordpress_url = default_string();
+        settings.wordpress_user = default_string();
+        settings.wordpress_pass = default_string();
+    }
+    projects.insert(new_name.clone(), settings);
+    save_projects(&app, &s, &projects)?;
+
+    s.save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+    logging::info(&format!(
+        "Project '{}' duplicated as '{}' (credentials copied: {}).",
+        source_name, new_name, copy_credentials
+    ));
+    Ok(())
+}
+
+async fn call_openai_chat(
+    client: &Client,
+    api_url: &str,
+    api_key: &str,
+    model: &str,
+    system_prompt: &str,
+    user_prompt: &str,
+    temperature: f64,
+    timeout: Duration,
+) -> Result<String, String> {
+    let request_body = serde_json::json!({
+        "model": model,
+        "messages": [
+            { "role": "system", "content": system_prompt },
+            { "role": "user", "content": user_prompt }
         ],
-        "temperature": 0.5
+        "temperature": temperature
     });
 
-    println!("Rust: Sending request to OpenAI for image prompt suggestions...");
     let response = client
         .post(api_url)
-        .bearer_auth(&api_key)
+        .bearer_auth(api_key)
+        .timeout(timeout)
         .json(&request_body)
-        .send()
+        .send_recorded()
         .await
         .map_err(|e| format!("Failed to send request to OpenAI: {}", e))?;
 
     let status = response.status();
+    record_rate_limit_headers(response.headers());
     let response_body_text = response
         .text()
         .await
         .map_err(|e| format!("Failed to read OpenAI response body: {}", e))?;
-    println!(
-        "Rust: Received suggestion response from OpenAI (Status: {})",
+    logging::info(&format!(
+        "Received response from OpenAI (Status: {})",
         status
-    );
+    ));
 
-    if status.is_success() {
-        match serde_json::from_str::<OpenAiApiResponse>(&response_body_text) {
-            Ok(parsed_response) => {
-                if let Some(choice) = parsed_response.choices.get(0) {
-                    let content = &choice.message.content;
-                    println!(
-                        "Rust: Extracted content potentially containing JSON: {}",
-                        content
-                    );
-                    match serde_json::from_str::<Vec<String>>(content) {
-                        Ok(prompts) => {
-                            println!("Rust: Successfully parsed suggested prompts: {:?}", prompts);
-                            Ok(SuggestImagePromptsResponse { prompts })
-                        }
-                        Err(e) => {
-                            eprintln!(
-                                "Rust: Failed to parse content as JSON array: {}. Content was: {}",
-                                e, content
-                            );
-                            Err(format!(
-                                "LLM response content was not a valid JSON array of strings: {}",
-                                e
-                            ))
-                        }
-                    }
-                } else {
-                    eprintln!("Rust: OpenAI response successful but 'choices' array is empty.");
-                    Err("OpenAI response structure unexpected (no choices)".to_string())
-                }
+    if !status.is_success() {
+        logging::warn(&format!(
+            "OpenAI API request failed - Status: {}, Body:\n{}",
+            status, response_body_text
+        ));
+        return Err(format!(
+            "OpenAI API request failed with status {}: {}",
+            status, response_body_text
+        ));
+    }
+
+    let parsed_response =
+        serde_json::from_str::<OpenAiApiResponse>(&response_body_text).map_err(|e| {
+            logging::debug(&format!("Raw response body was:\n{}", response_body_text));
+            format!(
+                "Failed to parse OpenAI response into expected structure: {}",
+                e
+            )
+        })?;
+    parsed_response
+        .choices
+        .into_iter()
+        .next()
+        .map(|choice| choice.message.content)
+        .ok_or_else(|| "OpenAI response has no choices".to_string())
+}
+
+const MAX_STYLE_REFERENCE_IMAGES: usize = 3;
+const MAX_STYLE_REFERENCE_BYTES: usize = 10 * 1024 * 1024;
+
+struct StyleReferenceImage {
+    file_name: String,
+    mime_type: String,
+    bytes: Vec<u8>,
+}
+
+async fn download_style_references(
+    client: &Client,
+    urls: &[String],
+) -> Result<Vec<StyleReferenceImage>, String> {
+    if urls.len() > MAX_STYLE_REFERENCE_IMAGES {
+        return Err(format!(
+            "Too many style reference images ({}); at most {} are allowed.",
+            urls.len(),
+            MAX_STYLE_REFERENCE_IMAGES
+        ));
+    }
+
+    let mut references = Vec::new();
+    for (index, url) in urls.iter().enumerate() {
+        logging::info(&format!(
+            "Downloading style reference {}: {}",
+            index + 1,
+            url
+        ));
+        let response =
+            user_agent::apply_to_builder(client.get(url), UserAgentTarget::ImageDownload)
+                .timeout(request_timeout(None))
+                .send()
+                .await
+                .map_err(|e| format!("Failed to download style reference {}: {}", url, e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Failed to download style reference {}: Status {}",
+                url,
+                response.status()
+            ));
+        }
+
+        let url_path = url.split(['?', '#']).next().unwrap_or(url);
+        let header_mime = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.split(';').next().unwrap_or(value).trim().to_string());
+        let mime_type = header_mime
+            .filter(|mime| mime.starts_with("image/"))
+            .or_else(|| {
+                mime_guess::from_path(url_path)
+                    .first()
+                    .map(|mime| mime.to_string())
+                    .filter(|mime| mime.starts_with("image/"))
+            })
+            .ok_or_else(|| format!("Style reference {} is not an image.", url))?;
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read style reference {}: {}", url, e))?;
+        if bytes.len() > MAX_STYLE_REFERENCE_BYTES {
+            return Err(format!(
+                "Style reference {} is too large ({} bytes, max {}).",
+                url,
+                bytes.len(),
+                MAX_STYLE_REFERENCE_BYTES
+            ));
+        }
+
+        let extension = mime_type
+            .trim_start_matches("image/")
+            .replace("jpeg", "jpg");
+        references.push(StyleReferenceImage {
+            file_name: format!("style_reference_{}.{}", index + 1, extension),
+            mime_type,
+            bytes: bytes.to_vec(),
+        });
+    }
+    Ok(references)
+}
+
+fn ideogram_form_fields(
+    prompt: &str,
+    rendering_speed: Option<&str>,
+    aspect_ratio: Option<&str>,
+    seed: Option<u64>,
+) -> Vec<(&'static str, String)> {
+    let mut fields = vec![
+        ("prompt", prompt.to_string()),
+        (
+            "rendering_speed",
+            rendering_speed.unwrap_or("TURBO").to_string(),
+        ),
+    ];
+    if let Some(ratio) = aspect_ratio {
+        fields.push(("aspect_ratio", ratio.to_string()));
+    }
+    if let Some(seed) = seed {
+        fields.push(("seed", seed.to_string()));
+    }
+    fields
+}
+
+async fn send_ideogram_generate_request(
+    client: &Client,
+    api_key: &str,
+    prompt: &str,
+    rendering_speed: Option<&str>,
+    aspect_ratio: Option<&str>,
+    seed: Option<u64>,
+    style_references: &[StyleReferenceImage],
+    timeout: Duration,
+) -> Result<IdeogramImageData, String> {
+    let api_endpoint = "https://api.ideogram.ai/v1/ideogram-v3/generate";
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "Api-Key",
+        HeaderValue::from_str(api_key).map_err(|e| format!("Invalid API Key format: {}", e))?,
+    );
+
+    let mut form = reqwest::multipart::Form::new();
+    for (name, value) in ideogram_form_fields(prompt, rendering_speed, aspect_ratio, seed) {
+        form = form.text(name, value);
+    }
+    if let Some(seed) = seed {
+        logging::info(&format!("Using seed: {}", seed));
+    }
+    for reference in style_references {
+        let part = reqwest::multipart::Part::bytes(reference.bytes.clone())
+            .file_name(reference.file_name.clone())
+            .mime_str(&reference.mime_type)
+            .map_err(|e| format!("Invalid style reference MIME type: {}", e))?;
+        form = form.part("style_reference_images", part);
+    }
+    if !style_references.is_empty() {
+        logging::info(&format!(
+            "Attached {} style reference image(s).",
+            style_references.len()
+        ));
+    }
+
+    logging::info(&format!(
+        "Sending multipart request to Ideogram API: {}",
+        api_endpoint
+    ));
+    let response = client
+        .post(api_endpoint)
+        .headers(headers)
+        .timeout(timeout)
+        .multipart(form)
+        .send_recorded()
+        .await
+        .map_err(|e| {
+            format!(
+                "{}: Failed to send request to Ideogram API: {}",
+                TRANSIENT_IMAGE_API_ERROR, e
+            )
+        })?;
+
+    let status = response.status();
+    logging::info(&format!(
+        "Received response from Ideogram API (Status: {})",
+        status
+    ));
+
+    if status.is_success() {
+        let api_response = response
+            .json::<IdeogramApiResponse>()
+            .await
+            .map_err(|e| format!("Failed to parse Ideogram JSON response: {}", e))?;
+
+        logging::info(&format!(
+            "Parsed Ideogram success response: {:?}",
+            api_response
+        ));
+
+        match api_response.data {
+            Some(data_vec) => data_vec.into_iter().next().ok_or_else(|| {
+                logging::info("Ideogram response successful but 'data' array is empty.");
+                "Ideogram response 'data' array was empty.".to_string()
+            }),
+            None => {
+                logging::info("Ideogram response successful but 'data' field missing or null.");
+                Err("Ideogram response missing 'data' field.".to_string())
             }
-            Err(e) => {
-                eprintln!(
-                    "Rust: Failed to parse primary OpenAI response structure: {:?}",
-                    e
-                );
-                eprintln!("Rust: Raw response body was:\n{}", response_body_text);
-                println!("Rust: Attempting fallback parse directly as JSON array...");
-                match serde_json::from_str::<Vec<String>>(&response_body_text) {
-                    Ok(prompts) => {
-                        println!("Rust: Fallback parse successful: {:?}", prompts);
-                        Ok(SuggestImagePromptsResponse { prompts })
+        }
+    } else {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Could not read error body".to_string());
+        logging::info(&format!(
+            "Ideogram API request failed - Status: {}, Body: {}",
+            status, error_text
+        ));
+        let message = format!(
+            "Ideogram API request failed with status {}: {}",
+            status, error_text
+        );
+        if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            Err(format!("{}: {}", TRANSIENT_IMAGE_API_ERROR, message))
+        } else {
+            Err(message)
+        }
+    }
+}
+
+async fn rewrite_prompt_for_safety(
+    app: &tauri::AppHandle,
+    project_name: Option<&str>,
+    prompt: &str,
+) -> Result<String, String> {
+    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+        .await?
+        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+    let api_url = project_chat_completions_url(app, project_name).await?;
+    let model = project_text_model(app, project_name).await?;
+
+    logging::info("Asking the LLM to rewrite an unsafe image prompt.");
+    let rewritten = call_openai_chat(
+        &shared_client(app),
+        &api_url,
+        &api_key,
+        &model,
+        "You rewrite image generation prompts so they comply with content policies. Keep the subject, composition and style, but remove or soften anything violent, sexual, hateful, or otherwise unsafe, and avoid real people's names and trademarked characters. Output ONLY the rewritten prompt, without quotes or explanations.",
+        prompt,
+        0.3,
+        request_timeout(None),
+    )
+    .await?;
+
+    let rewritten = rewritten.trim().trim_matches('"').trim().to_string();
+    if rewritten.is_empty() {
+        return Err("LLM returned an empty rewritten prompt.".to_string());
+    }
+    logging::info(&format!("Rewritten prompt: {}", rewritten));
+    Ok(rewritten)
+}
+
+#[tauri::command]
+async fn generate_ideogram_image(
+    app: tauri::AppHandle,
+    request: ImageGenRequest,
+) -> Result<ImageGenResponse, String> {
+    logging::info(&format!(
+        "Received image generation request for prompt: {}",
+        request.prompt
+    ));
+    if let Some(ratio) = &request.aspect_ratio {
+        logging::info(&format!("Using aspect ratio: {}", ratio));
+    }
+
+    let prompt_validation = check_image_prompt(&request.prompt);
+    for warning in &prompt_validation.warnings {
+        logging::warn(warning);
+    }
+    if !prompt_validation.is_valid {
+        return Err(prompt_validation.errors.join(" "));
+    }
+
+    let seed = match (request.seed, request.project_name.as_ref()) {
+        (Some(seed), _) => Some(seed),
+        (None, Some(project_name)) => get_project_settings(app.clone(), project_name.clone())
+            .await?
+            .and_then(|settings| settings.preferred_seed),
+        (None, None) => None,
+    };
+
+    if request.dry_run.unwrap_or(false) {
+        let mut form_preview = serde_json::Map::new();
+        for (name, value) in ideogram_form_fields(
+            &request.prompt,
+            request.rendering_speed.as_deref(),
+            request.aspect_ratio.as_deref(),
+            seed,
+        ) {
+            form_preview.insert(name.to_string(), JsonValue::String(value));
+        }
+        if let Some(urls) = request
+            .style_reference_urls
+            .as_ref()
+            .filter(|urls| !urls.is_empty())
+        {
+            form_preview.insert(
+                "style_reference_images".to_string(),
+                serde_json::to_value(urls).unwrap_or(JsonValue::Null),
+            );
+        }
+        logging::info(&format!(
+            "Dry run, Ideogram form not sent: {:?}",
+            form_preview
+        ));
+        return Ok(ImageGenResponse {
+            image_url: None,
+            seed,
+            original_prompt: request.prompt,
+            rewritten_prompt: None,
+            successful_prompt: None,
+            error: None,
+            form_preview: Some(form_preview),
+        });
+    }
+
+    if is_safe_mode(&app) {
+        logging::info("Safe mode enabled, skipping Ideogram image generation.");
+        return Ok(ImageGenResponse {
+            image_url: Some("https://placehold.co/1024x1024.png?text=Safe+mode".to_string()),
+            seed: request.seed,
+            original_prompt: request.prompt.clone(),
+            rewritten_prompt: None,
+            successful_prompt: Some(request.prompt),
+            error: None,
+            form_preview: None,
+        });
+    }
+
+    let api_key = get_api_key(app.clone(), STORE_KEY_IMAGE_API.to_string())
+        .await?
+        .ok_or_else(|| "Ideogram API Key (imageApiKey) not found in store.".to_string())?;
+
+    let client = shared_client(&app);
+    let style_references = match request.style_reference_urls.as_deref() {
+        Some(urls) if !urls.is_empty() => download_style_references(&client, urls).await?,
+        _ => Vec::new(),
+    };
+
+    let first_result = send_ideogram_generate_request(
+        &client,
+        &api_key,
+        &request.prompt,
+        request.rendering_speed.as_deref(),
+        request.aspect_ratio.as_deref(),
+        seed,
+        &style_references,
+        request_timeout_or(request.timeout_secs, DEFAULT_IMAGE_GEN_TIMEOUT_SECS),
+    )
+    .await?;
+
+    let generation_params = |prompt: &str, seed: Option<u64>| ImageGenerationParams {
+        prompt: prompt.to_string(),
+        rendering_speed: request.rendering_speed.clone(),
+        aspect_ratio: request.aspect_ratio.clone(),
+        seed,
+    };
+
+    if first_result.is_image_safe != Some(false) {
+        logging::info(&format!("Found image URL: {}", first_result.url));
+        remember_generated_image(
+            &first_result.url,
+            generation_params(&request.prompt, first_result.seed),
+        );
+        return Ok(ImageGenResponse {
+            image_url: Some(first_result.url),
+            seed: first_result.seed,
+            original_prompt: request.prompt.clone(),
+            rewritten_prompt: None,
+            successful_prompt: Some(request.prompt),
+            error: None,
+            form_preview: None,
+        });
+    }
+
+    logging::info("Ideogram flagged the generated image as unsafe.");
+    if !request.rewrite_unsafe_prompt.unwrap_or(false) {
+        return Err(format!(
+            "Ideogram flagged the image for prompt '{}' as unsafe. Edit the prompt or enable automatic rewriting.",
+            request.prompt
+        ));
+    }
+
+    let rewritten_prompt =
+        rewrite_prompt_for_safety(&app, request.project_name.as_deref(), &request.prompt).await?;
+    let retry_result = send_ideogram_generate_request(
+        &client,
+        &api_key,
+        &rewritten_prompt,
+        request.rendering_speed.as_deref(),
+        request.aspect_ratio.as_deref(),
+        seed,
+        &style_references,
+        request_timeout_or(request.timeout_secs, DEFAULT_IMAGE_GEN_TIMEOUT_SECS),
+    )
+    .await?;
+
+    if retry_result.is_image_safe == Some(false) {
+        logging::info("Rewritten prompt was also flagged as unsafe.");
+        return Err(format!(
+            "Ideogram flagged the image as unsafe for both the original prompt '{}' and the rewritten prompt '{}'.",
+            request.prompt, rewritten_prompt
+        ));
+    }
+
+    logging::info(&format!(
+        "Rewritten prompt succeeded. Found image URL: {}",
+        retry_result.url
+    ));
+    remember_generated_image(
+        &retry_result.url,
+        generation_params(&rewritten_prompt, retry_result.seed),
+    );
+    Ok(ImageGenResponse {
+        image_url: Some(retry_result.url),
+        seed: retry_result.seed,
+        original_prompt: request.prompt,
+        rewritten_prompt: Some(rewritten_prompt.clone()),
+        successful_prompt: Some(rewritten_prompt),
+        error: None,
+        form_preview: None,
+    })
+}
+
+fn check_image_prompt(prompt: &str) -> ImagePromptValidation {
+    let trimmed = prompt.trim();
+    let char_count = trimmed.chars().count();
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+
+    if trimmed.is_empty() {
+        errors.push("The image prompt is empty.".to_string());
+    }
+    if char_count > IDEOGRAM_MAX_PROMPT_CHARS {
+        errors.push(format!(
+            "The image prompt has {} characters; the maximum is {}.",
+            char_count, IDEOGRAM_MAX_PROMPT_CHARS
+        ));
+    }
+
+    let lowercase = trimmed.to_lowercase();
+    let words: HashSet<&str> = lowercase
+        .split(|c: char| !c.is_alphanumeric() && c != '-')
+        .filter(|word| !word.is_empty())
+        .collect();
+    let flagged: Vec<&str> = IMAGE_PROMPT_POLICY_TERMS
+        .iter()
+        .filter(|term| {
+            if term.contains(' ') {
+                lowercase.contains(*term)
+            } else {
+                words.contains(*term)
+            }
+        })
+        .copied()
+        .collect();
+    if !flagged.is_empty() {
+        warnings.push(format!(
+            "The prompt contains terms likely to trigger Ideogram's content policy: {}.",
+            flagged.join(", ")
+        ));
+    }
+
+    ImagePromptValidation {
+        is_valid: errors.is_empty(),
+        char_count,
+        errors,
+        warnings,
+    }
+}
+
+#[tauri::command]
+async fn validate_image_prompt(prompt: String) -> Result<ImagePromptValidation, String> {
+    Ok(check_image_prompt(&prompt))
+}
+
+// Prefix for Ideogram errors worth retrying: network failures, 429 and 5xx.
+const TRANSIENT_IMAGE_API_ERROR: &str = "transient_error";
+
+#[tauri::command]
+async fn generate_images_for_prompts(
+    app: tauri::AppHandle,
+    prompts: Vec<String>,
+    aspect_ratio: Option<String>,
+    rendering_speed: Option<String>,
+    project_name: Option<String>,
+) -> Result<Vec<ImageGenResponse>, String> {
+    logging::info(&format!(
+        "Generating images for {} prompt(s) with concurrency {}.",
+        prompts.len(),
+        BULK_IMAGE_CONCURRENCY
+    ));
+    let total = prompts.len();
+    let semaphore = Arc::new(Semaphore::new(BULK_IMAGE_CONCURRENCY));
+    let mut tasks = JoinSet::new();
+    for (index, prompt) in prompts.iter().cloned().enumerate() {
+        let app = app.clone();
+        let semaphore = semaphore.clone();
+        let aspect_ratio = aspect_ratio.clone();
+        let rendering_speed = rendering_speed.clone();
+        let project_name = project_name.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let emit_progress = |attempt: u32, status: &str, error: Option<String>| {
+                let progress = ImageGenerationProgress {
+                    index,
+                    total,
+                    prompt: prompt.clone(),
+                    attempt,
+                    status: status.to_string(),
+                    error,
+                };
+                if let Err(e) = app.emit("image-generation-progress", progress) {
+                    logging::warn(&format!("Failed to emit image progress event: {}", e));
+                }
+            };
+
+            let mut last_error = String::new();
+            let mut last_attempt = 1;
+            for attempt in 1..=BULK_IMAGE_MAX_ATTEMPTS {
+                emit_progress(attempt, "started", None);
+                let result = generate_ideogram_image(
+                    app.clone(),
+                    ImageGenRequest {
+                        prompt: prompt.clone(),
+                        rendering_speed: rendering_speed.clone(),
+                        aspect_ratio: aspect_ratio.clone(),
+                        project_name: project_name.clone(),
+                        seed: None,
+                        rewrite_unsafe_prompt: None,
+                        dry_run: None,
+                        style_reference_urls: None,
+                        timeout_secs: None,
+                    },
+                )
+                .await;
+                match result {
+                    Ok(response) => {
+                        emit_progress(attempt, "completed", None);
+                        return (index, response);
                     }
-                    Err(fallback_e) => {
-                        eprintln!("Rust: Fallback parse also failed: {}", fallback_e);
-                        Err(format!(
-                            "Failed to parse OpenAI response: {}. Fallback failed: {}",
-                            e, fallback_e
-                        ))
+                    Err(e) => {
+                        logging::info(&format!(
+                            "Image {} attempt {}/{} failed: {}",
+                            index + 1,
+                            attempt,
+                            BULK_IMAGE_MAX_ATTEMPTS,
+                            e
+                        ));
+                        let transient = e.starts_with(TRANSIENT_IMAGE_API_ERROR);
+                        last_error = e;
+                        last_attempt = attempt;
+                        if !transient || attempt == BULK_IMAGE_MAX_ATTEMPTS {
+                            break;
+                        }
+                        emit_progress(attempt, "retrying", Some(last_error.clone()));
+                        sleep(Duration::from_secs(2u64.pow(attempt))).await;
                     }
                 }
             }
+            emit_progress(last_attempt, "failed", Some(last_error.clone()));
+            (
+                index,
+                ImageGenResponse {
+                    image_url: None,
+                    seed: None,
+                    original_prompt: prompt,
+                    rewritten_prompt: None,
+                    successful_prompt: None,
+                    error: Some(last_error),
+                    form_preview: None,
+                },
+            )
+        });
+    }
+
+    let mut results: Vec<Option<ImageGenResponse>> = prompts.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, response)) => results[index] = Some(response),
+            Err(e) => logging::error(&format!("Image generation task failed: {}", e)),
+        }
+    }
+
+    Ok(results
+        .into_iter()
+        .zip(prompts)
+        .map(|(result, prompt)| {
+            result.unwrap_or_else(|| ImageGenResponse {
+                image_url: None,
+                seed: None,
+                original_prompt: prompt,
+                rewritten_prompt: None,
+                successful_prompt: None,
+                error: Some("Image generation task did not complete.".to_string()),
+                form_preview: None,
+            })
+        })
+        .collect())
+}
+
+#[tauri::command]
+async fn save_preferred_seed(
+    app: tauri::AppHandle,
+    project_name: String,
+    seed: Option<u64>,
+) -> Result<(), String> {
+    logging::info(&format!(
+        "Setting preferred seed for project '{}' to {:?}",
+        project_name, seed
+    ));
+    save_project_settings(
+        app,
+        project_name,
+        serde_json::json!({ "preferred_seed": seed }),
+    )
+    .await
+    .map(|_| ())
+}
+
+fn language_display_name(code: &str) -> String {
+    match code.trim().to_ascii_lowercase().as_str() {
+        "fr" => "French".to_string(),
+        "en" => "English".to_string(),
+        "es" => "Spanish".to_string(),
+        "de" => "German".to_string(),
+        "it" => "Italian".to_string(),
+        "pt" => "Portuguese".to_string(),
+        "nl" => "Dutch".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn build_article_prompt(request: &FullArticleRequest) -> String {
+    // The prompt itself stays in French; other languages get an explicit output override.
+    let (typography_instruction, language_instruction) = if is_french(request.language()) {
+        (
+            "Respecter les conventions typographiques françaises : minuscules sauf pour débuts de phrases, titres, et noms propres.".to_string(),
+            String::new(),
+        )
+    } else {
+        let language_name = language_display_name(request.language());
+        (
+            format!("Respecter les conventions typographiques de la langue de l'article ({}).", language_name),
+            format!(
+                "LANGUAGE: Write the entire article (<title>, meta description, headings and body) in {}, even though these instructions are in French.\n",
+                language_name
+            ),
+        )
+    };
+    let disambiguation = match request
+        .disambiguation_notes
+        .as_deref()
+        .map(str::trim)
+        .filter(|notes| !notes.is_empty())
+    {
+        Some(notes) => format!(
+            "Éviter toute confusion avec des outils similaires. Règles de terminologie et de désambiguïsation à respecter impérativement :\n{}",
+            notes
+        ),
+        None => "Éviter toute confusion avec des outils similaires (ex. Groq vs Grok).".to_string(),
+    };
+    let title_instruction = match request
+        .preserve_title
+        .as_deref()
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+    {
+        Some(title) => format!(
+            "Balise <title> : utiliser EXACTEMENT ce titre déjà validé, sans le modifier : \"{}\".",
+            title
+        ),
+        None => format!(
+            "Balise <title> : Optimisée pour le SEO, {min_chars}-{max_chars} caractères, avec des mots-clés comme \"avis\", \"fonctionnalités\", \"tarifs\", \"{tool_name}\", \"2025\" (ex. \"Avis {tool_name} 2025 : fonctionnalités, tarifs, alternatives\").",
+            min_chars = TITLE_MIN_CHARS,
+            max_chars = TITLE_MAX_CHARS,
+            tool_name = request.tool_name
+        ),
+    };
+
+    let mut dynamic_sections_prompt_part = String::new();
+    for (index, section) in request.sections.iter().enumerate() {
+        let section_str = format!("Section {}:\n{}\n\n", index + 1, section.instructions);
+        dynamic_sections_prompt_part.push_str(&section_str);
+    }
+
+    format!(
+        r#"{user_goal_prompt} Use {example_url} as a reference for style and structure where applicable. The article must focus on the AI tool: {tool_name}.
+
+Recherche approfondie :
+Analyser le site officiel de l'outil, les discussions pertinentes sur X.com, et des sources web fiables pour collecter des informations à jour sur les fonctionnalités, tarifs, avis utilisateurs, et alternatives.
+Vérifier les données pour 2025 afin d'assurer leur actualité et leur précision.
+{disambiguation}
+
+Structure de l'article :
+Based on the instructions below, create distinct sections with appropriate H2 titles. Develop each section thoroughly based on its instructions.
+{dynamic_sections}
+
+Rédaction :
+Produire un article de minimum {target_word_count} mots en HTML, incluant :
+{title_instruction}
+Balise <meta description> : 150-160 caractères, incluant un call-to-action engageant (ex. "Découvrez {tool_name} : fonctionnalités, tarifs, avis. Boostez vos projets IA !").
+Balise <h1> : Optimisée pour le lecteur, engageante, différente du <title>, axée sur un bénéfice clé (ex. "Pourquoi {tool_name} révolutionne vos projets IA en 2025").
+Balises H2: Générez des titres H2 descriptifs et pertinents pour chaque section définie ci-dessus en vous basant sur les instructions fournies pour cette section.
+Liens hypertextes : Inclure un lien vers le site officiel de l'outil dans l'introduction, les tarifs, et la conclusion, et des liens vers les sites des alternatives dans la section correspondante. Ne pas inclure de liens vers des sources de recherche.
+Style HTML : NE PAS INCLURE de balise <style> ni de styles CSS en ligne. Générer du HTML sémantique et brut uniquement. Si des tableaux sont nécessaires, utilisez des balises HTML standard (<table>, <tr>, <th>, <td>) sans aucun style CSS.
+{typography_instruction}
+Utiliser un ton engageant, professionnel, et accessible, avec des exemples concrets pour illustrer les cas d'usage.
+Assurez-vous que la sortie est uniquement le code HTML complet de l'article, en commençant par <!DOCTYPE html> ou <html> et se terminant par </html>. N'incluez AUCUN texte ou explication avant ou après le code HTML.
+IMPORTANT: The final article content within the HTML MUST contain at least {target_word_count} words. Expand significantly on each section's instructions to achieve this length.
+{language_instruction}"#,
+        user_goal_prompt = request.article_goal_prompt,
+        example_url = request.example_url,
+        tool_name = request.tool_name,
+        dynamic_sections = dynamic_sections_prompt_part,
+        target_word_count = request.target_word_count,
+        title_instruction = title_instruction,
+        disambiguation = disambiguation,
+        typography_instruction = typography_instruction,
+        language_instruction = language_instruction
+    )
+}
+
+static LAST_RATE_LIMIT_STATUS: std::sync::Mutex<Option<RateLimitStatus>> =
+    std::sync::Mutex::new(None);
+
+fn record_rate_limit_headers(headers: &reqwest::header::HeaderMap) {
+    let text = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+    };
+    let number = |name: &str| text(name).and_then(|value| value.parse::<u64>().ok());
+
+    let status = RateLimitStatus {
+        captured_at: unix_timestamp_secs(),
+        limit_requests: number("x-ratelimit-limit-requests"),
+        remaining_requests: number("x-ratelimit-remaining-requests"),
+        reset_requests: text("x-ratelimit-reset-requests"),
+        limit_tokens: number("x-ratelimit-limit-tokens"),
+        remaining_tokens: number("x-ratelimit-remaining-tokens"),
+        reset_tokens: text("x-ratelimit-reset-tokens"),
+    };
+    if status.remaining_requests.is_none() && status.remaining_tokens.is_none() {
+        return;
+    }
+    logging::info(&format!(
+        "OpenAI rate limits - requests {:?}/{:?} (reset {:?}), tokens {:?}/{:?} (reset {:?})",
+        status.remaining_requests,
+        status.limit_requests,
+        status.reset_requests,
+        status.remaining_tokens,
+        status.limit_tokens,
+        status.reset_tokens
+    ));
+    if let Ok(mut last) = LAST_RATE_LIMIT_STATUS.lock() {
+        *last = Some(status);
+    }
+}
+
+#[tauri::command]
+async fn get_rate_limit_status() -> Result<Option<RateLimitStatus>, String> {
+    LAST_RATE_LIMIT_STATUS
+        .lock()
+        .map(|last| last.clone())
+        .map_err(|_| "Rate limit state lock was poisoned".to_string())
+}
+
+fn build_article_messages(request: &FullArticleRequest, user_prompt: String) -> Vec<ChatMessage> {
+    vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: format!("You are a helpful assistant tasked with writing detailed AI tool review articles in {} HTML format based on user instructions and web searches. Generate appropriate H2 titles for each section based on the provided instructions. Prioritize reaching the target word count of {}.", language_display_name(request.language()), request.target_word_count),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: user_prompt,
+        },
+    ]
+}
+
+#[tauri::command]
+async fn preview_chat_messages(request: FullArticleRequest) -> Result<Vec<ChatMessage>, String> {
+    let messages = build_article_messages(&request, build_article_prompt(&request));
+    logging::info(&format!(
+        "Previewing {} chat message(s) for model '{}' (no API call).",
+        messages.len(),
+        request.model
+    ));
+    Ok(messages)
+}
+
+#[tauri::command]
+async fn regenerate_article(
+    mut request: FullArticleRequest,
+    preserve_title: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<ArticleResponse, String> {
+    logging::info(&format!(
+        "Regenerating article for '{}' (preserving title: {:?}).",
+        request.tool_name, preserve_title
+    ));
+    request.preserve_title = preserve_title.filter(|title| !title.trim().is_empty());
+    generate_full_article(request, app).await
+}
+
+fn count_words(html: &str) -> usize {
+    strip_html_tags(html).split_whitespace().count()
+}
+
+#[tauri::command]
+async fn generate_full_article(
+    mut request: FullArticleRequest,
+    app: tauri::AppHandle,
+) -> Result<ArticleResponse, String> {
+    let project_settings = match request.project_name.clone() {
+        Some(project_name) => get_project_settings(app.clone(), project_name).await?,
+        None => None,
+    };
+    if request.disambiguation_notes.is_none() {
+        request.disambiguation_notes = project_settings
+            .as_ref()
+            .and_then(|settings| settings.disambiguation_notes.clone());
+    }
+    if request.footer_html.is_none() {
+        request.footer_html = project_settings
+            .as_ref()
+            .and_then(|settings| settings.footer_html.clone());
+    }
+    if request.max_word_count.is_none() {
+        request.max_word_count = project_settings
+            .as_ref()
+            .and_then(|settings| settings.max_word_count);
+    }
+    if request.language.is_none() {
+        request.language = project_settings
+            .as_ref()
+            .map(|settings| settings.language.clone());
+    }
+    if request.french_typography.is_none() {
+        request.french_typography = project_settings
+            .as_ref()
+            .map(|settings| settings.french_typography);
+    }
+    let escalation_model = request
+        .escalate_on_short
+        .clone()
+        .or_else(|| {
+            project_settings
+                .as_ref()
+                .and_then(|settings| settings.escalate_on_short.clone())
+        })
+        .filter(|model| !model.trim().is_empty());
+
+    generate_article_once(request, app, escalation_model).await
+}
+
+// Only the delay-seconds form of Retry-After is honored; HTTP dates fall back to backoff.
+fn retry_after_duration(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(|seconds| Duration::from_secs(seconds.max(1)))
+}
+
+// Returns the complete lines in the buffer, leaving a trailing partial line for the next chunk.
+fn drain_sse_lines(buffer: &mut Vec<u8>) -> Vec<String> {
+    let Some(last_newline) = buffer.iter().rposition(|&byte| byte == b'\n') else {
+        return Vec::new();
+    };
+    let complete: Vec<u8> = buffer.drain(..=last_newline).collect();
+    String::from_utf8_lossy(&complete)
+        .lines()
+        .map(|line| line.trim_end_matches('\r').to_string())
+        .collect()
+}
+
+async fn read_openai_article_stream(
+    mut response: reqwest::Response,
+    app: &tauri::AppHandle,
+    project_name: Option<&str>,
+    idle_timeout: Duration,
+    started: std::time::Instant,
+) -> Result<(String, Option<OpenAiUsage>), String> {
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut content = String::new();
+    let mut usage = None;
+    let mut done = false;
+
+    while !done {
+        let chunk = tokio::time::timeout(idle_timeout, response.chunk())
+            .await
+            .map_err(|_| {
+                format!(
+                    "OpenAI stream stalled: no data for {:?} after {} characters.",
+                    idle_timeout,
+                    content.len()
+                )
+            })?
+            .map_err(|e| {
+                format!(
+                    "OpenAI stream was interrupted: {}",
+                    http_recording::describe_send_error(&e, None, started.elapsed())
+                )
+            })?;
+        let Some(chunk) = chunk else {
+            break;
+        };
+        buffer.extend_from_slice(&chunk);
+        for line in drain_sse_lines(&mut buffer) {
+            let Some(data) = line.strip_prefix("data:") else {
+                continue;
+            };
+            let data = data.trim();
+            if data == "[DONE]" {
+                done = true;
+                break;
+            }
+            if data.is_empty() {
+                continue;
+            }
+            let parsed: OpenAiStreamChunk = serde_json::from_str(data)
+                .map_err(|e| format!("Failed to parse OpenAI stream chunk: {} ({})", e, data))?;
+            if parsed.usage.is_some() {
+                usage = parsed.usage;
+            }
+            let delta: String = parsed
+                .choices
+                .into_iter()
+                .filter_map(|choice| choice.delta.content)
+                .collect();
+            if delta.is_empty() {
+                continue;
+            }
+            content.push_str(&delta);
+            let event = ArticleChunk {
+                project_name: project_name.map(str::to_string),
+                delta,
+            };
+            if let Err(e) = app.emit("article-chunk", event) {
+                logging::warn(&format!("Failed to emit article chunk event: {}", e));
+            }
+        }
+    }
+
+    if !done {
+        return Err(format!(
+            "OpenAI stream ended before completion after {} characters.",
+            content.len()
+        ));
+    }
+    Ok((content, usage))
+}
+
+async fn generate_article_html_with_openai(
+    app: &tauri::AppHandle,
+    request: &FullArticleRequest,
+    final_prompt: String,
+) -> Result<String, String> {
+    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+        .await?
+        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+    logging::info(&format!(
+        "[generate_full_article] Using API Key from store: {}",
+        mask_secret(&api_key)
+    ));
+
+    if api_key.is_empty() {
+        return Err("Fetched OpenAI API key is empty".to_string());
+    }
+
+    let client = shared_client(app);
+    let api_url = project_chat_completions_url(app, request.project_name.as_deref()).await?;
+
+    let request_body = serde_json::json!({
+        "model": request.model,
+        "messages": build_article_messages(request, final_prompt),
+        "temperature": 0.7,
+        "stream": true,
+        "stream_options": { "include_usage": true }
+    });
+
+    let max_retries = request.max_retries.unwrap_or(DEFAULT_ARTICLE_MAX_RETRIES);
+    let timeout = request_timeout(request.timeout_secs);
+    let mut attempts = 0;
+    let (response, started) = loop {
+        attempts += 1;
+        let started = std::time::Instant::now();
+        logging::info(&format!(
+            "Sending prompt to OpenAI API (Attempt {}/{})...",
+            attempts,
+            max_retries + 1
+        ));
+        // A reqwest timeout would also cover the body, so only the wait for the
+        // response headers is capped here; the stream has its own idle timeout.
+        let response = tokio::time::timeout(
+            timeout,
+            client
+                .post(&api_url)
+                .bearer_auth(&api_key)
+                .json(&request_body)
+                .send_recorded(),
+        )
+        .await
+        .map_err(|_| format!("OpenAI did not respond within {:?}.", timeout))?
+        .map_err(|e| format!("Failed to send request to OpenAI: {}", e))?;
+
+        let status = response.status();
+        record_rate_limit_headers(response.headers());
+        logging::info(&format!(
+            "Received response from OpenAI API (Status: {})",
+            status
+        ));
+        if status.is_success() {
+            break (response, started);
+        }
+
+        let retry_after = retry_after_duration(response.headers());
+        let response_body_text = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read OpenAI response body: {}", e))?;
+        logging::warn(&format!(
+            "OpenAI API request failed - Status: {}, Body:\n{}",
+            status, response_body_text
+        ));
+        let retryable = status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+        if !retryable || attempts > max_retries {
+            return Err(format!(
+                "OpenAI API request failed with status {} after {} attempt(s): {}",
+                status, attempts, response_body_text
+            ));
+        }
+
+        let wait_duration = retry_after.unwrap_or_else(|| {
+            let backoff = ARTICLE_INITIAL_BACKOFF_SECS.saturating_mul(1 << (attempts - 1).min(16));
+            Duration::from_secs(backoff.min(ARTICLE_MAX_BACKOFF_SECS))
+        });
+        logging::info(&format!(
+            "OpenAI returned {}. Waiting for {:?} before retry...",
+            status, wait_duration
+        ));
+        sleep(wait_duration).await;
+    };
+
+    let (full_html_from_llm, usage) = read_openai_article_stream(
+        response,
+        app,
+        request.project_name.as_deref(),
+        Duration::from_secs(STREAM_IDLE_TIMEOUT_SECS),
+        started,
+    )
+    .await?;
+    if let Some(usage) = usage {
+        let project = request
+            .project_name
+            .as_deref()
+            .unwrap_or(&request.tool_name);
+        if let Err(e) = record_usage(app, project, &request.model, usage).await {
+            logging::warn(&format!("Failed to record usage: {}", e));
+        }
+    }
+    if full_html_from_llm.trim().is_empty() {
+        logging::info("OpenAI stream completed but no content was received.");
+        return Err("OpenAI response has no content".to_string());
+    }
+    Ok(full_html_from_llm)
+}
+
+fn is_gemini_model(model: &str) -> bool {
+    model.trim().to_ascii_lowercase().starts_with("gemini-")
+}
+
+async fn generate_article_html_with_gemini(
+    app: &tauri::AppHandle,
+    request: &FullArticleRequest,
+    final_prompt: String,
+) -> Result<String, String> {
+    let api_key = get_api_key(app.clone(), STORE_KEY_GEMINI_API.to_string())
+        .await?
+        .ok_or_else(|| "Gemini API Key (geminiApiKey) not found in store.".to_string())?;
+    if api_key.trim().is_empty() {
+        return Err("Fetched Gemini API key is empty".to_string());
+    }
+
+    let messages = build_article_messages(request, final_prompt);
+    let join_role = |role: &str| -> String {
+        messages
+            .iter()
+            .filter(|message| message.role == role)
+            .map(|message| message.content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    };
+    let request_body = serde_json::json!({
+        "systemInstruction": { "parts": [{ "text": join_role("system") }] },
+        "contents": [{ "role": "user", "parts": [{ "text": join_role("user") }] }],
+        "generationConfig": { "temperature": 0.7 }
+    });
+    let api_url = format!(
+        "{}/{}:generateContent",
+        GEMINI_API_BASE_URL,
+        request.model.trim()
+    );
+
+    logging::info(&format!(
+        "Sending prompt to Gemini API (model: {})...",
+        request.model
+    ));
+    let response = shared_client(app)
+        .post(&api_url)
+        .query(&[("key", api_key.as_str())])
+        .timeout(request_timeout(request.timeout_secs))
+        .json(&request_body)
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to Gemini: {}", e))?;
+
+    let status = response.status();
+    let response_body_text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read Gemini response body: {}", e))?;
+    logging::info(&format!(
+        "Received response from Gemini API (Status: {})",
+        status
+    ));
+    if !status.is_success() {
+        logging::warn(&format!(
+            "Gemini API request failed - Status: {}, Body:\n{}",
+            status, response_body_text
+        ));
+        return Err(format!(
+            "Gemini API request failed with status {}: {}",
+            status, response_body_text
+        ));
+    }
+
+    let parsed: GeminiResponse = serde_json::from_str(&response_body_text).map_err(|e| {
+        logging::debug(&format!(
+            "Raw Gemini response body was:\n{}",
+            response_body_text
+        ));
+        format!(
+            "Failed to parse Gemini response into expected structure: {}",
+            e
+        )
+    })?;
+    if let Some(usage) = parsed.usage_metadata {
+        let project = request
+            .project_name
+            .as_deref()
+            .unwrap_or(&request.tool_name);
+        let usage = OpenAiUsage {
+            prompt_tokens: usage.prompt_token_count,
+            completion_tokens: usage.candidates_token_count,
+        };
+        if let Err(e) = record_usage(app, project, &request.model, usage).await {
+            logging::warn(&format!("Failed to record usage: {}", e));
+        }
+    }
+    let candidate = parsed
+        .candidates
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Gemini response has no candidates".to_string())?;
+    let text: String = candidate
+        .content
+        .map(|content| {
+            content
+                .parts
+                .into_iter()
+                .filter_map(|part| part.text)
+                .collect()
+        })
+        .unwrap_or_default();
+    if text.trim().is_empty() {
+        return Err(format!(
+            "Gemini returned no text (finish reason: {}).",
+            candidate.finish_reason.as_deref().unwrap_or("unknown")
+        ));
+    }
+    Ok(strip_code_fences(&text).to_string())
+}
+
+async fn generate_article_html(
+    app: &tauri::AppHandle,
+    request: &FullArticleRequest,
+    final_prompt: String,
+) -> Result<String, String> {
+    if is_gemini_model(&request.model) {
+        generate_article_html_with_gemini(app, request, final_prompt).await
+    } else {
+        generate_article_html_with_openai(app, request, final_prompt).await
+    }
+}
+
+async fn generate_article_once(
+    mut request: FullArticleRequest,
+    app: tauri::AppHandle,
+    escalation_model: Option<String>,
+) -> Result<ArticleResponse, String> {
+    logging::info(&format!(
+        "Generating full article for tool: {}",
+        request.tool_name
+    ));
+    logging::info(&format!("Using model: {}", request.model));
+    logging::info(&format!(
+        "Targeting word count: {}",
+        request.target_word_count
+    ));
+    logging::info(&format!(
+        "Using article goal: {}",
+        request.article_goal_prompt
+    ));
+    logging::info(&format!("Using example URL: {}", request.example_url));
+    logging::info(&format!(
+        "Received sections (instructions only): {:?}",
+        request.sections
+    ));
+
+    if is_safe_mode(&app) {
+        logging::info("Safe mode enabled, skipping OpenAI article generation.");
+        let mut article_text = format!(
+            "<p>Safe mode is enabled: this is a placeholder article for {}.</p>",
+            request.tool_name
+        );
+        for (index, section) in request.sections.iter().enumerate() {
+            article_text.push_str(&format!(
+                "\n<h2>Section {}</h2>\n<p>{}</p>",
+                index + 1,
+                section.instructions
+            ));
+        }
+        let word_count = count_words(&article_text);
+        if let Some(footer) = request.footer_html.as_deref() {
+            article_text = append_footer_html(&article_text, footer);
+        }
+        return Ok(ArticleResponse {
+            article_text,
+            title: Some(format!("[Safe mode] {}", request.tool_name)),
+            escalated_to_model: None,
+            word_count,
+            seo_title: Some(format!("[Safe mode] {}", request.tool_name)),
+            meta_description: None,
+            h1: None,
+        });
+    }
+
+    let final_prompt = build_article_prompt(&request);
+
+    logging::debug(&format!(
+        "--- Final Prompt Being Sent ---\n{}\n--- End Final Prompt ---",
+        final_prompt
+    ));
+
+    let mut full_html_from_llm =
+        generate_article_html(&app, &request, final_prompt.clone()).await?;
+
+    // Only the model call is escalated, so the expansion pass and the history
+    // entry below run once, on whichever draft is kept.
+    let mut escalated_to_model = None;
+    if let Some(escalation_model) = escalation_model.filter(|model| *model != request.model) {
+        let word_count = count_words(&extract_body_content(&full_html_from_llm));
+        let threshold = (request.target_word_count as f64 * ESCALATION_WORD_RATIO) as usize;
+        if word_count < threshold {
+            logging::info(&format!("Article has {} words, below the escalation threshold of {} for target {}. Retrying with '{}'.", word_count, threshold, request.target_word_count, escalation_model));
+            let mut escalated_request = request.clone();
+            escalated_request.model = escalation_model.clone();
+            match generate_article_html(&app, &escalated_request, final_prompt).await {
+                Ok(escalated_html) => {
+                    logging::info(&format!(
+                        "Escalated article has {} words.",
+                        count_words(&extract_body_content(&escalated_html))
+                    ));
+                    full_html_from_llm = escalated_html;
+                    request = escalated_request;
+                    escalated_to_model = Some(escalation_model);
+                }
+                Err(e) => {
+                    logging::info(&format!(
+                        "Escalation to '{}' failed, keeping the original article: {}",
+                        escalation_model, e
+                    ));
+                }
+            }
+        }
+    }
+    logging::info(&format!(
+        "Full HTML from LLM received. Length: {}",
+        full_html_from_llm.len()
+    ));
+
+    let mut head_fields = extract_article_head_fields(&full_html_from_llm);
+    logging::info(&format!(
+        "Extracted SEO fields - title: {}, meta description: {}, H1: {}",
+        head_fields.seo_title.is_some(),
+        head_fields.meta_description.is_some(),
+        head_fields.h1.is_some()
+    ));
+
+    // Extract title
+    let title_regex = Regex::new(r"(?i)<title>(.*?)</title>").expect("Invalid title regex");
+    let mut extracted_title = title_regex
+        .captures(&full_html_from_llm)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().trim().to_string());
+
+    if let Some(preserved) = request
+        .preserve_title
+        .as_deref()
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+    {
+        if extracted_title.as_deref() != Some(preserved) {
+            logging::info(&format!(
+                "Model changed the preserved title ({:?}), restoring '{}'.",
+                extracted_title, preserved
+            ));
+        }
+        extracted_title = Some(preserved.to_string());
+        head_fields.seo_title = Some(preserved.to_string());
+    }
+
+    if let Some(ref title) = extracted_title {
+        logging::info(&format!("Extracted title: {}", title));
+    } else {
+        logging::info("No <title> tag found in LLM response.");
+    }
+
+    // Extract body content
+    let mut body_only_html = extract_body_content(&full_html_from_llm);
+    logging::info(&format!(
+        "Body-only HTML extracted. Length: {}",
+        body_only_html.len()
+    ));
+
+    // Remove H1 tag and its content from the extracted body
+    let h1_regex = Regex::new(r"(?is)<h1(?:[^>]*)>.*?</h1>").expect("Invalid H1 regex");
+    if h1_regex.is_match(&body_only_html) {
+        logging::info("Found H1 tag in body, removing it.");
+        body_only_html = h1_regex.replace(&body_only_html, "").trim().to_string();
+        logging::info(&format!(
+            "Body HTML after H1 removal. New Length: {}",
+            body_only_html.len()
+        ));
+    } else {
+        logging::info("No H1 tag found in extracted body content.");
+    }
+
+    body_only_html = expand_to_target_word_count(&app, &request, body_only_html).await;
+    body_only_html = enforce_max_word_count(&app, &request, body_only_html).await;
+
+    if request.french_typography.unwrap_or(false) && is_french(request.language()) {
+        logging::info("Applying French typography rules.");
+        body_only_html = apply_french_typography(&body_only_html);
+    }
+
+    let word_count = count_words(&body_only_html);
+    logging::info(&format!(
+        "Final article word count: {} (target {}).",
+        word_count, request.target_word_count
+    ));
+    if let Some(footer) = request.footer_html.as_deref() {
+        body_only_html = append_footer_html(&body_only_html, footer);
+    }
+
+    if let Some(project_name) = request.project_name.as_deref() {
+        if let Err(e) = record_article_version(
+            &app,
+            project_name,
+            extracted_title.clone(),
+            body_only_html.clone(),
+        ) {
+            logging::warn(&format!("Failed to record article history: {}", e));
+        }
+    }
+
+    Ok(ArticleResponse {
+        article_text: body_only_html,
+        title: extracted_title,
+        escalated_to_model,
+        word_count,
+        seo_title: head_fields.seo_title,
+        meta_description: head_fields.meta_description,
+        h1: head_fields.h1,
+    })
+}
+
+struct ArticleHeadFields {
+    seo_title: Option<String>,
+    meta_description: Option<String>,
+    h1: Option<String>,
+}
+
+fn extract_article_head_fields(html: &str) -> ArticleHeadFields {
+    let first_text_of = |pattern: &str| -> Option<String> {
+        Regex::new(pattern)
+            .expect("Invalid article field regex")
+            .captures(html)
+            .and_then(|caps| caps.get(1))
+            .map(|m| unescape_html_attribute(&strip_html_tags(m.as_str())))
+            .filter(|text| !text.is_empty())
+    };
+    let meta_description =
+        Regex::new(r#"(?is)<meta\s+name=["']description["']\s+content=(?:"([^"]*)"|'([^']*)')"#)
+            .expect("Invalid meta description regex")
+            .captures(html)
+            .and_then(|caps| caps.get(1).or_else(|| caps.get(2)))
+            .map(|m| unescape_html_attribute(m.as_str().trim()))
+            .filter(|text| !text.is_empty());
+
+    ArticleHeadFields {
+        seo_title: first_text_of(r"(?is)<title>(.*?)</title>"),
+        meta_description,
+        h1: first_text_of(r"(?is)<h1(?:[^>]*)>(.*?)</h1>"),
+    }
+}
+
+// Headings of the H2 sections with the fewest words, thinnest first.
+fn thinnest_sections(html: &str, limit: usize) -> Vec<String> {
+    let h2_regex = Regex::new(r"(?is)<h2[^>]*>(.*?)</h2>").expect("Invalid H2 regex");
+    let headings: Vec<(usize, usize, String)> = h2_regex
+        .captures_iter(html)
+        .map(|caps| {
+            let whole = caps.get(0).expect("Regex match has a full capture");
+            (
+                whole.start(),
+                whole.end(),
+                strip_html_tags(&caps[1]).trim().to_string(),
+            )
+        })
+        .collect();
+    let mut sections: Vec<(usize, String)> = headings
+        .iter()
+        .enumerate()
+        .map(|(index, (_, end, heading))| {
+            let section_end = headings
+                .get(index + 1)
+                .map(|(start, _, _)| *start)
+                .unwrap_or(html.len());
+            (count_words(&html[*end..section_end]), heading.clone())
+        })
+        .collect();
+    sections.sort_by_key(|(words, _)| *words);
+    sections
+        .into_iter()
+        .take(limit)
+        .map(|(_, heading)| heading)
+        .collect()
+}
+
+// Best effort: a failed pass keeps the article as it is.
+async fn expand_to_target_word_count(
+    app: &tauri::AppHandle,
+    request: &FullArticleRequest,
+    article_html: String,
+) -> String {
+    let threshold = (request.target_word_count as f64 * EXPANSION_WORD_RATIO) as usize;
+    let mut article_html = article_html;
+    let mut word_count = count_words(&article_html);
+    if word_count >= threshold {
+        return article_html;
+    }
+    if is_gemini_model(&request.model) {
+        logging::info(&format!("Article has {} words (target {}), but expansion passes are only supported for OpenAI models.", word_count, request.target_word_count));
+        return article_html;
+    }
+    let api_key = match get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string()).await {
+        Ok(Some(key)) if !key.is_empty() => key,
+        _ => {
+            logging::warn("OpenAI API key unavailable, skipping expansion passes.");
+            return article_html;
+        }
+    };
+    let api_url = match project_chat_completions_url(app, request.project_name.as_deref()).await {
+        Ok(url) => url,
+        Err(e) => {
+            logging::warn(&format!(
+                "Could not resolve the OpenAI endpoint, skipping expansion passes: {}",
+                e
+            ));
+            return article_html;
+        }
+    };
+    let language_name = language_display_name(request.language());
+    let system_prompt = format!(
+        "You are an editor who expands {} HTML articles with additional, concrete content without changing their structure. You output ONLY HTML.",
+        language_name
+    );
+
+    for pass in 1..=MAX_EXPANSION_PASSES {
+        if word_count >= threshold {
+            break;
+        }
+        let thin_sections = thinnest_sections(&article_html, EXPANSION_THIN_SECTIONS);
+        logging::info(&format!(
+            "Expansion pass {}/{}: article has {} words, below {} for target {}. Expanding: {:?}",
+            pass,
+            MAX_EXPANSION_PASSES,
+            word_count,
+            threshold,
+            request.target_word_count,
+            thin_sections
+        ));
+        let focus = if thin_sections.is_empty() {
+            "the sections that are the least detailed".to_string()
+        } else {
+            thin_sections
+                .iter()
+                .map(|heading| format!("\"{}\"", heading))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let user_prompt = format!(
+            "The article below about {tool} has {words} words but must reach at least {target} words.\n\
+             Add about {missing} words of new, specific content (examples, use cases, details, comparisons), focusing on these sections: {focus}.\n\
+             Keep every existing heading, paragraph, list and table, keep the same language ({language}), and do not add an <h1> or a conclusion that repeats the existing one.\n\
+             Return the COMPLETE expanded article HTML, not just the additions.\n\n{article}",
+            tool = request.tool_name,
+            words = word_count,
+            target = request.target_word_count,
+            missing = (request.target_word_count as usize).saturating_sub(word_count),
+            focus = focus,
+            language = language_name,
+            article = article_html
+        );
+        let content = match call_openai_chat(
+            &shared_client(app),
+            &api_url,
+            &api_key,
+            &request.model,
+            &system_prompt,
+            &user_prompt,
+            0.7,
+            request_timeout_or(request.timeout_secs, LONG_LLM_REQUEST_TIMEOUT_SECS),
+        )
+        .await
+        {
+            Ok(content) => content,
+            Err(e) => {
+                logging::warn(&format!("Expansion pass {} failed: {}", pass, e));
+                break;
+            }
+        };
+        let expanded = strip_code_fences(&content).to_string();
+        let expanded = if Regex::new(r"(?i)<body\b")
+            .expect("Invalid body regex")
+            .is_match(&expanded)
+        {
+            extract_body_content(&expanded)
+        } else {
+            expanded
+        };
+        let expanded = Regex::new(r"(?is)<h1(?:[^>]*)>.*?</h1>")
+            .expect("Invalid H1 regex")
+            .replace_all(&expanded, "")
+            .trim()
+            .to_string();
+        let expanded_count = count_words(&expanded);
+        if expanded_count <= word_count {
+            logging::info(&format!(
+                "Expansion pass {} did not add words ({} -> {}), keeping the previous version.",
+                pass, word_count, expanded_count
+            ));
+            break;
+        }
+        logging::info(&format!(
+            "Expansion pass {} grew the article from {} to {} words.",
+            pass, word_count, expanded_count
+        ));
+        article_html = expanded;
+        word_count = expanded_count;
+    }
+    article_html
+}
+
+fn estimate_article_tokens(request: &FullArticleRequest) -> (u64, u64) {
+    // Roughly 4 characters per input token; French HTML output runs about 2 tokens per word.
+    let input_tokens = (build_article_prompt(request).chars().count() as u64) / 4 + 100;
+    let output_tokens = request.target_word_count as u64 * 2;
+    (input_tokens, output_tokens)
+}
+
+#[tauri::command]
+async fn get_batch_concurrency(app: tauri::AppHandle) -> Result<usize, String> {
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    let concurrency = store
+        .get(STORE_KEY_BATCH_CONCURRENCY)
+        .and_then(|value| value.as_u64())
+        .map(|value| value as usize)
+        .unwrap_or(DEFAULT_BATCH_CONCURRENCY);
+    Ok(concurrency.clamp(1, MAX_BATCH_CONCURRENCY))
+}
+
+#[tauri::command]
+async fn set_batch_concurrency(app: tauri::AppHandle, concurrency: usize) -> Result<usize, String> {
+    let concurrency = concurrency.clamp(1, MAX_BATCH_CONCURRENCY);
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store.set(
+        STORE_KEY_BATCH_CONCURRENCY.to_string(),
+        JsonValue::from(concurrency as u64),
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+    logging::info(&format!("Batch concurrency set to {}", concurrency));
+    Ok(concurrency)
+}
+
+// Every read-modify-write of the main store (batch checkpoints, upload quota,
+// project and settings saves) takes this lock so writes don't interleave. Readers
+// that reload() take it too: a reload in the middle of a write would discard the
+// writer's unsaved changes. It is not reentrant and must not be held across an await.
+static STORE_WRITE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+fn lock_store_writes() -> Result<std::sync::MutexGuard<'static, ()>, String> {
+    STORE_WRITE_LOCK
+        .lock()
+        .map_err(|_| "Store lock was poisoned".to_string())
+}
+
+fn get_batches_from_store(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+) -> Result<BatchesMap, String> {
+    match store.get(STORE_KEY_BATCHES) {
+        Some(JsonValue::Null) | None => Ok(BatchesMap::new()),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| format!("Failed to deserialize batches: {}", e)),
+    }
+}
+
+fn update_stored_batch(
+    app: &tauri::AppHandle,
+    batch_id: &str,
+    update: impl FnOnce(&mut BatchesMap) -> Result<(), String>,
+) -> Result<(), String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let mut batches = get_batches_from_store(&store)?;
+    update(&mut batches)?;
+    if let Some(batch) = batches.get_mut(batch_id) {
+        batch.updated_at = unix_timestamp_secs();
+    }
+    store.set(
+        STORE_KEY_BATCHES.to_string(),
+        serde_json::to_value(&batches)
+            .map_err(|e| format!("Failed to serialize batches: {}", e))?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))
+}
+
+// Items left in progress by a previous run of the app were interrupted; put
+// them back to pending so the batch can be resumed.
+fn requeue_interrupted_batch_items(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+) -> Result<usize, String> {
+    let _guard = lock_store_writes()?;
+    let mut batches = get_batches_from_store(store)?;
+    let mut requeued = 0;
+    for item in batches
+        .values_mut()
+        .flat_map(|batch| batch.items.iter_mut())
+    {
+        if item.status == BatchItemStatus::InProgress {
+            item.status = BatchItemStatus::Pending;
+            requeued += 1;
+        }
+    }
+    if requeued > 0 {
+        store.set(
+            STORE_KEY_BATCHES.to_string(),
+            serde_json::to_value(&batches)
+                .map_err(|e| format!("Failed to serialize batches: {}", e))?,
+        );
+        store
+            .save()
+            .map_err(|e| format!("Failed to save store: {}", e))?;
+    }
+    Ok(requeued)
+}
+
+fn load_stored_batch(app: &tauri::AppHandle, batch_id: &str) -> Result<StoredBatch, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    get_batches_from_store(&store)?
+        .remove(batch_id)
+        .ok_or_else(|| format!("Batch '{}' not found.", batch_id))
+}
+
+#[tauri::command]
+async fn generate_article_batch(
+    app: tauri::AppHandle,
+    request: BatchGenerationRequest,
+) -> Result<BatchGenerationResponse, String> {
+    logging::info(&format!(
+        "Starting batch generation of {} articles (budget: {:?}).",
+        request.items.len(),
+        request.batch_budget_usd
+    ));
+
+    let created_at = unix_timestamp_secs();
+    let batch_id = format!(
+        "batch-{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+    );
+    let batch = StoredBatch {
+        id: batch_id.clone(),
+        created_at,
+        updated_at: created_at,
+        batch_budget_usd: request.batch_budget_usd,
+        items: request
+            .items
+            .into_iter()
+            .map(|item| StoredBatchItem {
+                request: item,
+                status: BatchItemStatus::Pending,
+                error: None,
+                article: None,
+                estimated_cost_usd: None,
+            })
+            .collect(),
+    };
+    update_stored_batch(&app, &batch_id, |batches| {
+        batches.insert(batch_id.clone(), batch);
+        Ok(())
+    })?;
+    logging::info(&format!("Batch '{}' persisted.", batch_id));
+
+    run_batch(app, batch_id).await
+}
+
+#[tauri::command]
+async fn resume_batch(
+    app: tauri::AppHandle,
+    batch_id: String,
+) -> Result<BatchGenerationResponse, String> {
+    logging::info(&format!("Resuming batch '{}'.", batch_id));
+    run_batch(app, batch_id).await
+}
+
+#[tauri::command]
+async fn list_batches(app: tauri::AppHandle) -> Result<Vec<BatchSummary>, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let mut summaries: Vec<BatchSummary> = get_batches_from_store(&store)?
+        .into_values()
+        .map(|batch| {
+            let count = |status: BatchItemStatus| {
+                batch
+                    .items
+                    .iter()
+                    .filter(|item| item.status == status)
+                    .count()
+            };
+            BatchSummary {
+                total: batch.items.len(),
+                done: count(BatchItemStatus::Done),
+                failed: count(BatchItemStatus::Failed),
+                pending: count(BatchItemStatus::Pending),
+                in_progress: count(BatchItemStatus::InProgress),
+                tool_names: batch
+                    .items
+                    .iter()
+                    .map(|item| item.request.tool_name.clone())
+                    .collect(),
+                id: batch.id,
+                created_at: batch.created_at,
+                updated_at: batch.updated_at,
+            }
+        })
+        .collect();
+    summaries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(summaries)
+}
+
+// Runs every item that is not done yet (pending, or failed on a previous run),
+// checkpointing each result so an interrupted batch can be resumed.
+async fn run_batch(
+    app: tauri::AppHandle,
+    batch_id: String,
+) -> Result<BatchGenerationResponse, String> {
+    let pricing_table = load_pricing_table(&app).await?;
+    let mut total_estimated_cost_usd: f64 = 0.0;
+    let mut note: Option<String> = None;
+    let mut accepted_items: Vec<(usize, FullArticleRequest, Option<f64>)> = Vec::new();
+    let mut skipped_indices: Vec<usize> = Vec::new();
+    let mut already_done = 0;
+
+    // The budget check only depends on the estimates, so the accepted items are
+    // chosen up front and marked in progress under the store lock; a concurrent
+    // resume of the same batch then leaves them alone.
+    update_stored_batch(&app, &batch_id, |batches| {
+        let batch = batches
+            .get_mut(&batch_id)
+            .ok_or_else(|| format!("Batch '{}' not found.", batch_id))?;
+        // Items another run is still generating count against the budget too.
+        total_estimated_cost_usd = batch
+            .items
+            .iter()
+            .filter(|item| {
+                matches!(
+                    item.status,
+                    BatchItemStatus::Done | BatchItemStatus::InProgress
+                )
+            })
+            .filter_map(|item| item.estimated_cost_usd)
+            .sum();
+        already_done = batch
+            .items
+            .iter()
+            .filter(|item| item.status == BatchItemStatus::Done)
+            .count();
+
+        for (index, stored_item) in batch.items.iter_mut().enumerate() {
+            if matches!(
+                stored_item.status,
+                BatchItemStatus::Done | BatchItemStatus::InProgress
+            ) {
+                continue;
+            }
+            if note.is_some() {
+                skipped_indices.push(index);
+                continue;
+            }
+            let item = stored_item.request.clone();
+            let (input_tokens, output_tokens) = estimate_article_tokens(&item);
+            let estimated_cost_usd =
+                pricing::estimate_cost(&pricing_table, &item.model, input_tokens, output_tokens);
+
+            if let Some(budget) = batch.batch_budget_usd {
+                match estimated_cost_usd {
+                    Some(cost) if total_estimated_cost_usd + cost > budget => {
+                        logging::info(&format!("Budget exhausted before item {} (spent ~${:.4}, next ~${:.4}, budget ${:.4}).", index + 1,
+                            total_estimated_cost_usd,
+                            cost,
+                            budget));
+                        note = Some(format!(
+                            "budget exhausted: stopped before item {} of the batch (estimated spend ${:.4} of ${:.4}).",
+                            index + 1,
+                            total_estimated_cost_usd,
+                            budget
+                        ));
+                        skipped_indices.push(index);
+                        continue;
+                    }
+                    None => {
+                        note = Some(format!(
+                            "budget exhausted: cannot estimate the cost of model '{}', stopped before item {} to stay within budget.",
+                            item.model,
+                            index + 1
+                        ));
+                        skipped_indices.push(index);
+                        continue;
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            total_estimated_cost_usd += estimated_cost_usd.unwrap_or(0.0);
+            stored_item.status = BatchItemStatus::InProgress;
+            stored_item.estimated_cost_usd = estimated_cost_usd;
+            accepted_items.push((index, item, estimated_cost_usd));
+        }
+        Ok(())
+    })?;
+    logging::info(&format!(
+        "Batch '{}': {} item(s) to generate, {} already done, {} skipped by budget.",
+        batch_id,
+        accepted_items.len(),
+        already_done,
+        skipped_indices.len()
+    ));
+
+    // Each article makes its OpenAI calls one after another, so the number of
+    // in-flight OpenAI requests never exceeds the batch concurrency. Keep it at 1
+    // on free-tier keys; higher values only help if the account's rate limit
+    // allows that many simultaneous requests.
+    let concurrency = get_batch_concurrency(app.clone()).await?;
+    logging::info(&format!("Batch concurrency: {}", concurrency));
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+
+    let claimed_indices: Vec<usize> = accepted_items.iter().map(|(index, _, _)| *index).collect();
+    let mut tasks = JoinSet::new();
+    for (index, item, estimated_cost_usd) in accepted_items {
+        let app = app.clone();
+        let semaphore = semaphore.clone();
+        let batch_id = batch_id.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            logging::info(&format!(
+                "Batch item {}: generating article for '{}' (~${:?}).",
+                index + 1,
+                item.tool_name,
+                estimated_cost_usd
+            ));
+            let result = generate_full_article(item, app.clone()).await;
+            if let Err(e) = &result {
+                logging::info(&format!("Batch item {} failed: {}", index + 1, e));
+            }
+            let checkpoint = update_stored_batch(&app, &batch_id, |batches| {
+                let stored_item = batches
+                    .get_mut(&batch_id)
+                    .and_then(|batch| batch.items.get_mut(index))
+                    .ok_or_else(|| format!("Batch '{}' item {} disappeared.", batch_id, index))?;
+                stored_item.estimated_cost_usd = estimated_cost_usd;
+                match &result {
+                    Ok(article) => {
+                        stored_item.status = BatchItemStatus::Done;
+                        stored_item.article = Some(article.clone());
+                        stored_item.error = None;
+                    }
+                    Err(e) => {
+                        stored_item.status = BatchItemStatus::Failed;
+                        stored_item.error = Some(e.clone());
+                    }
+                }
+                Ok(())
+            });
+            if let Err(e) = checkpoint {
+                logging::warn(&format!(
+                    "Failed to checkpoint batch item {}: {}",
+                    index + 1,
+                    e
+                ));
+            }
+        });
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        if let Err(e) = joined {
+            logging::error(&format!("Batch generation task failed: {}", e));
+        }
+    }
+
+    // A panicked task or a lost checkpoint leaves its claim behind; release it
+    // under the lock so the item isn't skipped by later resumes.
+    update_stored_batch(&app, &batch_id, |batches| {
+        if let Some(batch) = batches.get_mut(&batch_id) {
+            for index in &claimed_indices {
+                if let Some(item) = batch.items.get_mut(*index) {
+                    if item.status == BatchItemStatus::InProgress {
+                        item.status = BatchItemStatus::Failed;
+                        item.error = Some("Generation did not finish.".to_string());
+                    }
+                }
+            }
+        }
+        Ok(())
+    })?;
+
+    let batch = load_stored_batch(&app, &batch_id)?;
+    let results: Vec<BatchItemResult> = batch
+        .items
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let skipped_by_budget = skipped_indices.contains(&index);
+            if !skipped_by_budget
+                && !matches!(item.status, BatchItemStatus::Done | BatchItemStatus::Failed)
+            {
+                return None;
+            }
+            Some(BatchItemResult {
+                index,
+                tool_name: item.request.tool_name,
+                success: item.status == BatchItemStatus::Done,
+                article: item.article,
+                error: if skipped_by_budget {
+                    Some("Skipped: the batch budget was exhausted.".to_string())
+                } else {
+                    item.error
+                },
+                estimated_cost_usd: item.estimated_cost_usd,
+                skipped_by_budget,
+            })
+        })
+        .collect();
+
+    logging::info(&format!(
+        "Batch '{}' finished with {} results, estimated spend ${:.4}.",
+        batch_id,
+        results.len(),
+        total_estimated_cost_usd
+    ));
+    Ok(BatchGenerationResponse {
+        batch_id,
+        results,
+        total_estimated_cost_usd,
+        note,
+    })
+}
+
+#[tauri::command]
+async fn suggest_image_prompts(
+    request: SuggestImagePromptsRequest,
+    app: tauri::AppHandle,
+) -> Result<SuggestImagePromptsResponse, String> {
+    logging::info("Received request to suggest image prompts.");
+
+    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+        .await?
+        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+    logging::info(&format!(
+        "Using API Key for prompt suggestion: {}",
+        mask_secret(&api_key)
+    ));
+
+    let suggestion_prompt = format!(
+        r#"Based on the following article text, suggest 3-5 diverse image prompts suitable for illustrating it. Focus on key themes, concepts, or visual metaphors described in the text.
+
+        Output ONLY a valid JSON array of strings, where each string is a suggested image prompt. Example output format: ["prompt idea 1", "prompt idea 2", "a third idea"]
+
+        Article Text:
+        ---
+        {article}
+        ---
+
+        Suggested Prompts (JSON Array Only):"#,
+        article = request.article_text
+    );
+
+    logging::debug(&format!(
+        "--- Prompt for Image Suggestion ---\n{}\n--- End Prompt ---",
+        suggestion_prompt
+    ));
+
+    let client = shared_client(&app);
+    let api_url = project_chat_completions_url(&app, request.project_name.as_deref()).await?;
+    let model = project_text_model(&app, request.project_name.as_deref()).await?;
+
+    let request_body = serde_json::json!({
+        "model": model,
+        "messages": [
+            {
+                "role": "system",
+                "content": "You are an assistant that suggests image prompts based on provided text and outputs ONLY a valid JSON array of strings."
+            },
+            {
+                "role": "user",
+                "content": suggestion_prompt
+            }
+        ],
+        "temperature": 0.5
+    });
+
+    logging::info("Sending request to OpenAI for image prompt suggestions...");
+    let response = client
+        .post(api_url)
+        .bearer_auth(&api_key)
+        .timeout(request_timeout(request.timeout_secs))
+        .json(&request_body)
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to OpenAI: {}", e))?;
+
+    let status = response.status();
+    record_rate_limit_headers(response.headers());
+    let response_body_text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read OpenAI response body: {}", e))?;
+    logging::info(&format!(
+        "Received suggestion response from OpenAI (Status: {})",
+        status
+    ));
+
+    if status.is_success() {
+        match serde_json::from_str::<OpenAiApiResponse>(&response_body_text) {
+            Ok(parsed_response) => {
+                if let Some(choice) = parsed_response.choices.get(0) {
+                    let content = &choice.message.content;
+                    logging::info(&format!(
+                        "Extracted content potentially containing JSON: {}",
+                        content
+                    ));
+                    match parse_llm_json::<Vec<String>>(content) {
+                        Ok(prompts) => {
+                            logging::info(&format!(
+                                "Successfully parsed suggested prompts: {:?}",
+                                prompts
+                            ));
+                            Ok(SuggestImagePromptsResponse { prompts })
+                        }
+                        Err(e) => {
+                            logging::warn(&format!("Failed to parse suggested prompts: {}", e));
+                            Err(format!("Failed to parse suggested image prompts: {}", e))
+                        }
+                    }
+                } else {
+                    logging::warn("OpenAI response successful but 'choices' array is empty.");
+                    Err("OpenAI response structure unexpected (no choices)".to_string())
+                }
+            }
+            Err(e) => {
+                logging::warn(&format!(
+                    "Failed to parse primary OpenAI response structure: {:?}",
+                    e
+                ));
+                logging::debug(&format!("Raw response body was:\n{}", response_body_text));
+                logging::info("Attempting fallback parse directly as JSON array...");
+                match serde_json::from_str::<Vec<String>>(&response_body_text) {
+                    Ok(prompts) => {
+                        logging::info(&format!("Fallback parse successful: {:?}", prompts));
+                        Ok(SuggestImagePromptsResponse { prompts })
+                    }
+                    Err(fallback_e) => {
+                        logging::warn(&format!("Fallback parse also failed: {}", fallback_e));
+                        Err(format!(
+                            "Failed to parse OpenAI response: {}. Fallback failed: {}",
+                            e, fallback_e
+                        ))
+                    }
+                }
+            }
+        }
+    } else {
+        logging::warn(&format!(
+            "OpenAI API request for suggestions failed - Status: {}, Body:\n{}",
+            status, response_body_text
+        ));
+        Err(format!(
+            "OpenAI API request failed with status {}: {}",
+            status, response_body_text
+        ))
+    }
+}
+
+#[tauri::command]
+async fn get_wordpress_categories(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<Vec<WordPressCategory>, String> {
+    logging::info(&format!(
+        "Fetching WP categories for project: {}",
+        project_name
+    ));
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+
+    if settings.wordpress_url.trim().is_empty()
+        || settings.wordpress_user.trim().is_empty()
+        || settings.wordpress_pass.trim().is_empty()
+    {
+        return Err(
+            "WordPress URL, User, and Application Password must be configured.".to_string(),
+        );
+    }
+
+    let categories_api_url = format!(
+        "{}/wp-json/wp/v2/categories?per_page=100",
+        settings.wordpress_url.trim_end_matches('/')
+    );
+    logging::info(&format!(
+        "Fetching categories from URL: {}",
+        categories_api_url
+    ));
+
+    let client = shared_client(&app);
+    let response = client
+        .get(&categories_api_url)
+        .basic_auth(&settings.wordpress_user, Some(&settings.wordpress_pass))
+        .timeout(request_timeout(None))
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to WordPress Categories API: {}", e))?;
+
+    let status = response.status();
+    logging::info(&format!(
+        "Received category response from WP (Status: {})",
+        status
+    ));
+
+    if status.is_success() {
+        let categories = response
+            .json::<Vec<WordPressCategory>>()
+            .await
+            .map_err(|e| format!("Failed to parse WordPress categories JSON: {}", e))?;
+        logging::info(&format!(
+            "Successfully fetched {} categories.",
+            categories.len()
+        ));
+        Ok(categories)
+    } else {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
+        logging::error(&format!(
+            "Failed to fetch categories - Status: {}, Body: {}",
+            status, error_text
+        ));
+        Err(format!(
+            "Failed to fetch categories (Status {}): {}",
+            status, error_text
+        ))
+    }
+}
+
+#[tauri::command]
+async fn get_wordpress_tags(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<Vec<WordPressTag>, String> {
+    logging::info(&format!("Fetching WP tags for project: {}", project_name));
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+
+    if settings.wordpress_url.trim().is_empty()
+        || settings.wordpress_user.trim().is_empty()
+        || settings.wordpress_pass.trim().is_empty()
+    {
+        return Err(
+            "WordPress URL, User, and Application Password must be configured.".to_string(),
+        );
+    }
+
+    let tags_api_url = format!(
+        "{}/wp-json/wp/v2/tags?per_page=100",
+        settings.wordpress_url.trim_end_matches('/')
+    );
+    logging::info(&format!("Fetching tags from URL: {}", tags_api_url));
+
+    let response = shared_client(&app)
+        .get(&tags_api_url)
+        .basic_auth(&settings.wordpress_user, Some(&settings.wordpress_pass))
+        .timeout(request_timeout(None))
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to WordPress Tags API: {}", e))?;
+
+    let status = response.status();
+    logging::info(&format!(
+        "Received tag response from WP (Status: {})",
+        status
+    ));
+
+    if status.is_success() {
+        let tags = response
+            .json::<Vec<WordPressTag>>()
+            .await
+            .map_err(|e| format!("Failed to parse WordPress tags JSON: {}", e))?;
+        logging::info(&format!("Successfully fetched {} tags.", tags.len()));
+        Ok(tags)
+    } else {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
+        logging::error(&format!(
+            "Failed to fetch tags - Status: {}, Body: {}",
+            status, error_text
+        ));
+        Err(format!(
+            "Failed to fetch tags (Status {}): {}",
+            status, error_text
+        ))
+    }
+}
+
+const RESERVED_POST_FIELDS: &[&str] = &[
+    "title",
+    "content",
+    "status",
+    "categories",
+    "tags",
+    "featured_media",
+    "slug",
+    "date",
+    "author",
+];
+
+fn is_valid_taxonomy_key(taxonomy: &str) -> bool {
+    !taxonomy.is_empty()
+        && taxonomy
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[tauri::command]
+async fn get_wordpress_terms(
+    app: tauri::AppHandle,
+    project_name: String,
+    taxonomy: String,
+) -> Result<Vec<WordPressTerm>, String> {
+    let taxonomy = taxonomy.trim().to_string();
+    logging::info(&format!(
+        "Fetching WP terms of taxonomy '{}' for project: {}",
+        taxonomy, project_name
+    ));
+    if !is_valid_taxonomy_key(&taxonomy) {
+        return Err(format!("Invalid taxonomy name '{}'.", taxonomy));
+    }
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+
+    if settings.wordpress_url.trim().is_empty()
+        || settings.wordpress_user.trim().is_empty()
+        || settings.wordpress_pass.trim().is_empty()
+    {
+        return Err(
+            "WordPress URL, User, and Application Password must be configured.".to_string(),
+        );
+    }
+
+    let terms_api_url = format!(
+        "{}/wp-json/wp/v2/{}?per_page=100",
+        settings.wordpress_url.trim_end_matches('/'),
+        taxonomy
+    );
+    logging::info(&format!("Fetching terms from URL: {}", terms_api_url));
+
+    let client = shared_client(&app);
+    let response = client
+        .get(&terms_api_url)
+        .basic_auth(&settings.wordpress_user, Some(&settings.wordpress_pass))
+        .timeout(request_timeout(None))
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to WordPress Terms API: {}", e))?;
+
+    let status = response.status();
+    if status.is_success() {
+        let terms = response
+            .json::<Vec<WordPressTerm>>()
+            .await
+            .map_err(|e| format!("Failed to parse WordPress terms JSON: {}", e))?;
+        logging::info(&format!(
+            "Successfully fetched {} terms for taxonomy '{}'.",
+            terms.len(),
+            taxonomy
+        ));
+        Ok(terms)
+    } else {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
+        logging::error(&format!(
+            "Failed to fetch terms - Status: {}, Body: {}",
+            status, error_text
+        ));
+        Err(format!(
+            "Failed to fetch terms for taxonomy '{}' (Status {}): {}",
+            taxonomy, status, error_text
+        ))
+    }
+}
+
+#[tauri::command]
+async fn get_wordpress_authors(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<Vec<WordPressAuthor>, String> {
+    logging::info(&format!(
+        "Fetching WP authors for project: {}",
+        project_name
+    ));
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+
+    if settings.wordpress_url.trim().is_empty()
+        || settings.wordpress_user.trim().is_empty()
+        || settings.wordpress_pass.trim().is_empty()
+    {
+        return Err(
+            "WordPress URL, User, and Application Password must be configured.".to_string(),
+        );
+    }
+
+    let users_api_url = format!(
+        "{}/wp-json/wp/v2/users?per_page=100",
+        settings.wordpress_url.trim_end_matches('/')
+    );
+    logging::info(&format!("Fetching authors from URL: {}", users_api_url));
+
+    let client = shared_client(&app);
+    let response = client
+        .get(&users_api_url)
+        .basic_auth(&settings.wordpress_user, Some(&settings.wordpress_pass))
+        .timeout(request_timeout(None))
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to WordPress Users API: {}", e))?;
+
+    let status = response.status();
+    logging::info(&format!(
+        "Received users response from WP (Status: {})",
+        status
+    ));
+
+    if status.is_success() {
+        let authors = response
+            .json::<Vec<WordPressAuthor>>()
+            .await
+            .map_err(|e| format!("Failed to parse WordPress users JSON: {}", e))?;
+        logging::info(&format!("Successfully fetched {} authors.", authors.len()));
+        Ok(authors)
+    } else {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
+        logging::error(&format!(
+            "Failed to fetch authors - Status: {}, Body: {}",
+            status, error_text
+        ));
+        if status == StatusCode::FORBIDDEN || status == StatusCode::UNAUTHORIZED {
+            return Err(format!(
+                "The WordPress user '{}' is not allowed to list users (Status {}). An account with the 'list_users' capability is required: {}",
+                settings.wordpress_user, status, error_text
+            ));
+        }
+        Err(format!(
+            "Failed to fetch authors (Status {}): {}",
+            status, error_text
+        ))
+    }
+}
+
+#[tauri::command]
+async fn probe_wordpress(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<WordPressProbe, String> {
+    logging::info(&format!(
+        "Probing WordPress REST API for project: {}",
+        project_name
+    ));
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+
+    if settings.wordpress_url.trim().is_empty() {
+        return Err("WordPress URL is not configured in project settings.".to_string());
+    }
+
+    let discovery_url = format!("{}/wp-json/", settings.wordpress_url.trim_end_matches('/'));
+    logging::info(&format!(
+        "Fetching REST discovery document: {}",
+        discovery_url
+    ));
+
+    let client = shared_client(&app);
+    let mut response = client
+        .get(&discovery_url)
+        .timeout(request_timeout(None))
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to reach WordPress site at {}: {}", discovery_url, e))?;
+
+    let mut authentication_required = false;
+    if response.status() == StatusCode::UNAUTHORIZED || response.status() == StatusCode::FORBIDDEN {
+        authentication_required = true;
+        logging::info(&format!(
+            "Discovery document requires authentication (Status {}), retrying with credentials.",
+            response.status()
+        ));
+        if settings.wordpress_user.trim().is_empty() || settings.wordpress_pass.trim().is_empty() {
+            return Err(format!(
+                "The WordPress REST API at {} requires authentication (Status {}), but no User/Application Password is configured.",
+                discovery_url,
+                response.status()
+            ));
+        }
+        response = client
+            .get(&discovery_url)
+            .basic_auth(&settings.wordpress_user, Some(&settings.wordpress_pass))
+            .timeout(request_timeout(None))
+            .send_recorded()
+            .await
+            .map_err(|e| format!("Failed to reach WordPress site at {}: {}", discovery_url, e))?;
+    }
+
+    let status = response.status();
+    logging::info(&format!(
+        "Received discovery response from WP (Status: {})",
+        status
+    ));
+
+    if status == StatusCode::NOT_FOUND {
+        return Err(format!(
+            "The WordPress REST API was not found at {} (404). REST may be disabled, or permalinks are set to 'Plain'.",
+            discovery_url
+        ));
+    }
+    if !status.is_success() {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
+        return Err(format!(
+            "The WordPress REST API at {} responded with status {}. It may be blocked by a security plugin or firewall: {}",
+            discovery_url, status, error_text
+        ));
+    }
+
+    let body_text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read WordPress discovery response: {}", e))?;
+    let discovery = serde_json::from_str::<serde_json::Value>(&body_text).map_err(|_| {
+        format!(
+            "{} did not return JSON. The REST API is likely disabled or intercepted by a security plugin.",
+            discovery_url
+        )
+    })?;
+
+    let namespaces: Vec<String> = discovery
+        .get("namespaces")
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(|ns| ns.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let authentication_methods: Vec<String> = discovery
+        .get("authentication")
+        .and_then(|v| v.as_object())
+        .map(|methods| methods.keys().cloned().collect())
+        .unwrap_or_default();
+
+    let probe = WordPressProbe {
+        site_name: discovery
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string()),
+        site_url: discovery
+            .get("url")
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string()),
+        has_wp_v2: namespaces.iter().any(|ns| ns == "wp/v2"),
+        namespaces,
+        authentication_required,
+        authentication_methods,
+    };
+    logging::info(&format!("WordPress probe result: {:?}", probe));
+
+    if !probe.has_wp_v2 {
+        logging::warn("'wp/v2' namespace not advertised by the site.");
+    }
+
+    Ok(probe)
+}
+
+async fn fetch_wordpress_posts(
+    client: &Client,
+    settings: &ProjectSettings,
+) -> Result<Vec<WordPressPostSummary>, String> {
+    let posts_api_url = format!(
+        "{}/wp-json/wp/v2/posts?per_page=100&status=publish&_fields=id,link,title",
+        settings.wordpress_url.trim_end_matches('/')
+    );
+    logging::info(&format!("Fetching posts from URL: {}", posts_api_url));
+
+    let response = client
+        .get(&posts_api_url)
+        .basic_auth(&settings.wordpress_user, Some(&settings.wordpress_pass))
+        .timeout(request_timeout(None))
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to WordPress Posts API: {}", e))?;
+
+    let status = response.status();
+    logging::info(&format!(
+        "Received posts response from WP (Status: {})",
+        status
+    ));
+
+    if status.is_success() {
+        let posts = response
+            .json::<Vec<WordPressPostListItem>>()
+            .await
+            .map_err(|e| format!("Failed to parse WordPress posts JSON: {}", e))?;
+        Ok(posts
+            .into_iter()
+            .map(|post| WordPressPostSummary {
+                id: post.id,
+                title: strip_html_tags(&post.title.rendered),
+                link: post.link,
+            })
+            .collect())
+    } else {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
+        logging::error(&format!(
+            "Failed to fetch posts - Status: {}, Body: {}",
+            status, error_text
+        ));
+        Err(format!(
+            "Failed to fetch posts (Status {}): {}",
+            status, error_text
+        ))
+    }
+}
+
+#[tauri::command]
+async fn list_wordpress_posts(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<Vec<WordPressPostSummary>, String> {
+    logging::info(&format!("Listing WP posts for project: {}", project_name));
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+
+    if settings.wordpress_url.trim().is_empty()
+        || settings.wordpress_user.trim().is_empty()
+        || settings.wordpress_pass.trim().is_empty()
+    {
+        return Err(
+            "WordPress URL, User, and Application Password must be configured.".to_string(),
+        );
+    }
+
+    let posts = fetch_wordpress_posts(&shared_client(&app), &settings).await?;
+    logging::info(&format!("Successfully fetched {} posts.", posts.len()));
+    Ok(posts)
+}
+
+const LLM_JSON_SNIPPET_CHARS: usize = 200;
+
+// Models sometimes wrap JSON in fences or add a sentence around it, so parse
+// the outermost [...] or {...} span.
+fn parse_llm_json<T: serde::de::DeserializeOwned>(content: &str) -> Result<T, String> {
+    let unfenced = strip_code_fences(content);
+    let start = unfenced.find(['[', '{']);
+    let json = match start {
+        Some(start) => {
+            let closer = if unfenced[start..].starts_with('[') {
+                ']'
+            } else {
+                '}'
+            };
+            match unfenced.rfind(closer) {
+                Some(end) if end > start => &unfenced[start..=end],
+                _ => &unfenced[start..],
+            }
+        }
+        None => unfenced,
+    };
+    serde_json::from_str::<T>(json).map_err(|e| {
+        let snippet: String = content
+            .trim()
+            .chars()
+            .take(LLM_JSON_SNIPPET_CHARS)
+            .collect();
+        let ellipsis = if content.trim().chars().count() > LLM_JSON_SNIPPET_CHARS {
+            "…"
+        } else {
+            ""
+        };
+        format!(
+            "The model did not return the expected JSON ({}). Content was: {}{}",
+            e, snippet, ellipsis
+        )
+    })
+}
+
+fn strip_code_fences(content: &str) -> &str {
+    let trimmed = content.trim();
+    match trimmed.strip_prefix("```") {
+        Some(rest) => {
+            let rest = rest.trim_start_matches(|c: char| c.is_ascii_alphanumeric());
+            rest.strip_suffix("```").unwrap_or(rest).trim()
+        }
+        None => trimmed,
+    }
+}
+
+#[tauri::command]
+async fn get_wordpress_post(
+    app: tauri::AppHandle,
+    project_name: String,
+    post_id: u32,
+) -> Result<WordPressPostFull, String> {
+    logging::info(&format!(
+        "Fetching WP post {} for project: {}",
+        post_id, project_name
+    ));
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+
+    if settings.wordpress_url.trim().is_empty()
+        || settings.wordpress_user.trim().is_empty()
+        || settings.wordpress_pass.trim().is_empty()
+    {
+        return Err(
+            "WordPress URL, User, and Application Password must be configured.".to_string(),
+        );
+    }
+
+    let post_api_url = format!(
+        "{}/wp-json/wp/v2/posts/{}?context=edit",
+        settings.wordpress_url.trim_end_matches('/'),
+        post_id
+    );
+    logging::info(&format!("Fetching post from URL: {}", post_api_url));
+
+    let client = shared_client(&app);
+    let response = client
+        .get(&post_api_url)
+        .basic_auth(&settings.wordpress_user, Some(&settings.wordpress_pass))
+        .timeout(request_timeout(None))
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to WordPress Posts API: {}", e))?;
+
+    let status = response.status();
+    logging::info(&format!(
+        "Received post response from WP (Status: {})",
+        status
+    ));
+
+    if status.is_success() {
+        let post = response
+            .json::<WordPressPostEditResponse>()
+            .await
+            .map_err(|e| format!("Failed to parse WordPress post JSON: {}", e))?;
+        Ok(WordPressPostFull {
+            id: post.id,
+            title: post
+                .title
+                .raw
+                .unwrap_or_else(|| strip_html_tags(&post.title.rendered)),
+            content_raw: post.content.raw.unwrap_or(post.content.rendered),
+            status: post.status,
+            categories: post.categories,
+            featured_media: Some(post.featured_media).filter(|id| *id != 0),
+            slug: post.slug,
+        })
+    } else {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
+        logging::error(&format!(
+            "Failed to fetch post - Status: {}, Body: {}",
+            status, error_text
+        ));
+        match status {
+            StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED => Err(format!(
+                "The WordPress user '{}' is not allowed to edit post {} (Status {}). The account probably lacks the 'edit_posts' capability: {}",
+                settings.wordpress_user, post_id, status, error_text
+            )),
+            StatusCode::NOT_FOUND => Err(format!("Post {} was not found on WordPress.", post_id)),
+            _ => Err(format!(
+                "Failed to fetch post (Status {}): {}",
+                status, error_text
+            )),
+        }
+    }
+}
+
+#[tauri::command]
+async fn suggest_internal_links(
+    app: tauri::AppHandle,
+    project_name: String,
+    article_html: String,
+) -> Result<Vec<InternalLinkSuggestion>, String> {
+    logging::info(&format!(
+        "Suggesting internal links for project '{}' (article length {}).",
+        project_name,
+        article_html.len()
+    ));
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+
+    if settings.wordpress_url.trim().is_empty()
+        || settings.wordpress_user.trim().is_empty()
+        || settings.wordpress_pass.trim().is_empty()
+    {
+        return Err(
+            "WordPress URL, User, and Application Password must be configured.".to_string(),
+        );
+    }
+
+    let client = shared_client(&app);
+    let existing_posts = fetch_wordpress_posts(&client, &settings).await?;
+    if existing_posts.is_empty() {
+        logging::info("No existing posts found, nothing to link to.");
+        return Ok(Vec::new());
+    }
+
+    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+        .await?
+        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+
+    let posts_list_string = existing_posts
+        .iter()
+        .map(|post| format!("- {} ({})", post.title, post.link))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let user_prompt = format!(
+        r#"Here is a new article and a list of existing posts on the same site. Suggest internal links from the new article to the existing posts.
+
+Existing Posts (title and URL):
+---
+{}
+---
+
+New Article:
+---
+{}
+---
+
+Instructions:
+1. Only suggest links to posts that are genuinely related to the surrounding text.
+2. The anchor_text must be a short phrase copied EXACTLY from the new article's visible text (not from headings or existing links).
+3. Link each existing post at most once, and suggest at most 10 links.
+4. Output ONLY a valid JSON array of objects with the keys "anchor_text", "target_post_url" and "target_title". Output [] if nothing fits."#,
+        posts_list_string, article_html
+    );
+
+    let request_body = serde_json::json!({
+        "model": settings.text_generation_model,
+        "messages": [
+            {
+                "role": "system",
+                "content": "You are an SEO assistant that builds internal links between articles and outputs ONLY a valid JSON array."
+            },
+            { "role": "user", "content": user_prompt }
+        ],
+        "temperature": 0.3
+    });
+
+    logging::info("Sending request to OpenAI for internal link suggestions...");
+    let response = client
+        .post(openai_chat_completions_url(
+            settings.openai_base_url.as_deref(),
+        ))
+        .bearer_auth(&api_key)
+        .json(&request_body)
+        .timeout(request_timeout(None))
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to OpenAI: {}", e))?;
+
+    let status = response.status();
+    record_rate_limit_headers(response.headers());
+    let response_body_text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read OpenAI response body: {}", e))?;
+    logging::info(&format!(
+        "Received internal link response from OpenAI (Status: {})",
+        status
+    ));
+
+    if !status.is_success() {
+        logging::warn(&format!(
+            "OpenAI API request for internal links failed - Status: {}, Body:\n{}",
+            status, response_body_text
+        ));
+        return Err(format!(
+            "OpenAI API request failed with status {}: {}",
+            status, response_body_text
+        ));
+    }
+
+    let parsed_response = serde_json::from_str::<OpenAiApiResponse>(&response_body_text)
+        .map_err(|e| format!("Failed to parse OpenAI response: {}", e))?;
+    let content = parsed_response
+        .choices
+        .first()
+        .map(|choice| choice.message.content.clone())
+        .ok_or_else(|| "OpenAI response has no choices".to_string())?;
+
+    let suggestions = parse_llm_json::<Vec<InternalLinkSuggestion>>(&content).map_err(|e| {
+        logging::warn(&format!("Failed to parse link suggestions: {}", e));
+        format!("Failed to parse link suggestions: {}", e)
+    })?;
+
+    let article_text = strip_html_tags(&article_html);
+    let valid_suggestions: Vec<InternalLinkSuggestion> = suggestions
+        .into_iter()
+        .filter(|suggestion| {
+            let known_target = existing_posts
+                .iter()
+                .any(|post| post.link == suggestion.target_post_url);
+            let anchor_present = !suggestion.anchor_text.trim().is_empty()
+                && article_text.contains(suggestion.anchor_text.trim());
+            if !known_target || !anchor_present {
+                logging::info(&format!(
+                    "Dropping link suggestion '{}' -> {} (known target: {}, anchor present: {})",
+                    suggestion.anchor_text,
+                    suggestion.target_post_url,
+                    known_target,
+                    anchor_present
+                ));
+            }
+            known_target && anchor_present
+        })
+        .collect();
+
+    logging::info(&format!(
+        "Returning {} internal link suggestions.",
+        valid_suggestions.len()
+    ));
+    Ok(valid_suggestions)
+}
+
+fn media_sizes_from_response(media: WordPressMediaResponse) -> Vec<MediaSize> {
+    let mut sizes: Vec<MediaSize> = media
+        .media_details
+        .unwrap_or_default()
+        .sizes
+        .into_iter()
+        .map(|(name, size)| MediaSize {
+            name,
+            width: size.width,
+            height: size.height,
+            source_url: size.source_url,
+        })
+        .collect();
+    sizes.sort_by_key(|size| (size.width, size.height));
+    sizes
+}
+
+#[tauri::command]
+async fn get_media_sizes(
+    app: tauri::AppHandle,
+    project_name: String,
+    media_id: u32,
+) -> Result<Vec<MediaSize>, String> {
+    logging::info(&format!(
+        "Fetching sizes for WP media {} in project: {}",
+        media_id, project_name
+    ));
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+
+    if settings.wordpress_url.trim().is_empty()
+        || settings.wordpress_user.trim().is_empty()
+        || settings.wordpress_pass.trim().is_empty()
+    {
+        return Err(
+            "WordPress URL, User, and Application Password must be configured.".to_string(),
+        );
+    }
+
+    let media = fetch_wordpress_media(&shared_client(&app), &settings, media_id).await?;
+    let full_size = MediaSize {
+        name: "full".to_string(),
+        width: 0,
+        height: 0,
+        source_url: media.source_url.clone(),
+    };
+    let mut sizes = media_sizes_from_response(media);
+    if !sizes.iter().any(|size| size.name == "full") {
+        sizes.push(full_size);
+    }
+    logging::info(&format!("Media {} has {} sizes.", media_id, sizes.len()));
+    Ok(sizes)
+}
+
+async fn fetch_wordpress_media(
+    client: &Client,
+    settings: &ProjectSettings,
+    media_id: u32,
+) -> Result<WordPressMediaResponse, String> {
+    let media_url = format!(
+        "{}/wp-json/wp/v2/media/{}",
+        settings.wordpress_url.trim_end_matches('/'),
+        media_id
+    );
+    logging::info(&format!("Fetching media from URL: {}", media_url));
+
+    let response = client
+        .get(&media_url)
+        .basic_auth(&settings.wordpress_user, Some(&settings.wordpress_pass))
+        .timeout(request_timeout(None))
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to WordPress Media API: {}", e))?;
+
+    let status = response.status();
+    logging::info(&format!(
+        "Received media response from WP (Status: {})",
+        status
+    ));
+
+    if status.is_success() {
+        response
+            .json::<WordPressMediaResponse>()
+            .await
+            .map_err(|e| format!("Failed to parse WordPress media JSON: {}", e))
+    } else {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
+        logging::error(&format!(
+            "Failed to fetch media - Status: {}, Body: {}",
+            status, error_text
+        ));
+        Err(format!(
+            "Failed to fetch media {} (Status {}): {}",
+            media_id, status, error_text
+        ))
+    }
+}
+
+#[tauri::command]
+async fn get_post_featured_image(
+    app: tauri::AppHandle,
+    project_name: String,
+    post_id: u32,
+) -> Result<Option<FeaturedImageInfo>, String> {
+    let post = get_wordpress_post(app.clone(), project_name.clone(), post_id).await?;
+    let Some(media_id) = post.featured_media else {
+        logging::info(&format!("Post {} has no featured image.", post_id));
+        return Ok(None);
+    };
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+    let media = fetch_wordpress_media(&shared_client(&app), &settings, media_id).await?;
+    logging::info(&format!(
+        "Post {} featured image: media {} ({})",
+        post_id, media_id, media.source_url
+    ));
+    Ok(Some(FeaturedImageInfo {
+        media_id,
+        source_url: media.source_url,
+    }))
+}
+
+#[tauri::command]
+async fn unpublish_post(
+    app: tauri::AppHandle,
+    project_name: String,
+    post_id: u32,
+) -> Result<(), String> {
+    logging::info(&format!(
+        "Unpublishing WP post {} for project: {}",
+        post_id, project_name
+    ));
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+
+    if settings.wordpress_url.trim().is_empty()
+        || settings.wordpress_user.trim().is_empty()
+        || settings.wordpress_pass.trim().is_empty()
+    {
+        return Err(
+            "WordPress URL, User, and Application Password must be configured.".to_string(),
+        );
+    }
+
+    let post_api_url = format!(
+        "{}/wp-json/wp/v2/posts/{}",
+        settings.wordpress_url.trim_end_matches('/'),
+        post_id
+    );
+    let client = shared_client(&app);
+    let response = client
+        .post(&post_api_url)
+        .basic_auth(&settings.wordpress_user, Some(&settings.wordpress_pass))
+        .json(&serde_json::json!({ "status": "draft" }))
+        .timeout(request_timeout(None))
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to WordPress Posts API: {}", e))?;
+
+    let status = response.status();
+    logging::info(&format!(
+        "Received unpublish response from WP (Status: {})",
+        status
+    ));
+    if !status.is_success() {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
+        return match status {
+            StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED => Err(format!(
+                "The WordPress user '{}' is not allowed to edit post {} (Status {}): {}",
+                settings.wordpress_user, post_id, status, error_text
+            )),
+            StatusCode::NOT_FOUND => Err(format!("Post {} was not found on WordPress.", post_id)),
+            _ => Err(format!(
+                "Failed to unpublish post (Status {}): {}",
+                status, error_text
+            )),
+        };
+    }
+
+    // Read the post back so a silently ignored update is reported instead of assumed.
+    let post = get_wordpress_post(app, project_name, post_id).await?;
+    if post.status != "draft" {
+        return Err(format!(
+            "Post {} is still '{}' after the unpublish request.",
+            post_id, post.status
+        ));
+    }
+    logging::info(&format!("Post {} is now a draft.", post_id));
+    Ok(())
+}
+
+fn extract_body_content(html: &str) -> String {
+    logging::debug(&format!(
+        "Full HTML input to extract_body_content (length {}): [FIRST 200 CHARS]{}[END_SNIPPET]",
+        html.len(),
+        html.chars().take(200).collect::<String>()
+    ));
+    let body_regex = Regex::new(r"(?is)<body(?:[^>]*)>(.*?)</body>").expect("Invalid body regex");
+
+    if let Some(caps) = body_regex.captures(html) {
+        if let Some(content_match) = caps.get(1) {
+            logging::debug("Extracted content from <body> tag.");
+            return content_match.as_str().trim().to_string();
+        } else {
+            logging::debug(
+                "<body> tag matched but no content group found. Returning empty string from body.",
+            );
+            return String::new();
+        }
+    } else {
+        logging::debug("<body> tag not found. Returning empty string as body content is specifically requested.");
+        return String::new();
+    }
+}
+
+fn append_footer_html(html: &str, footer: &str) -> String {
+    let footer = footer.trim();
+    if footer.is_empty() || html.contains(footer) {
+        return html.to_string();
+    }
+
+    let lower = html.to_ascii_lowercase();
+    let insert_at = lower.rfind("</body>").or_else(|| lower.rfind("</html>"));
+
+    match insert_at {
+        Some(pos) => format!("{}{}\n{}", &html[..pos], footer, &html[pos..]),
+        None => format!("{}\n{}", html.trim_end(), footer),
+    }
+}
+
+const NARROW_NBSP: char = '\u{202F}';
+const NBSP: char = '\u{00A0}';
+
+fn apply_french_typography_to_text(text: &str, in_quote: &mut bool) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut output = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if matches!(c, ';' | ':' | '!' | '?') {
+            let run_end = chars[i..]
+                .iter()
+                .position(|ch| !matches!(ch, ';' | ':' | '!' | '?'))
+                .map_or(chars.len(), |offset| i + offset);
+            let run: String = chars[i..run_end].iter().collect();
+            let followed_by_break = chars.get(run_end).map_or(true, |next| {
+                next.is_whitespace() || matches!(next, '»' | ')' | '"')
+            });
+            // A ';' right after "&name" or "&#123" terminates an HTML entity.
+            let ends_entity = c == ';'
+                && output
+                    .rfind('&')
+                    .map(|amp| {
+                        output[amp + 1..]
+                            .chars()
+                            .all(|ch| ch.is_ascii_alphanumeric() || ch == '#')
+                    })
+                    .unwrap_or(false);
+
+            if followed_by_break && !ends_entity {
+                let trimmed_len = output
+                    .trim_end_matches([' ', NBSP, NARROW_NBSP])
+                    .trim_end_matches("&nbsp;")
+                    .len();
+                output.truncate(trimmed_len);
+                if !output.is_empty() && !output.ends_with('(') {
+                    output.push(NARROW_NBSP);
+                }
+            }
+            output.push_str(&run);
+            i = run_end;
+            continue;
+        }
+
+        if c == '"' {
+            if *in_quote {
+                let trimmed_len = output.trim_end_matches([' ', NBSP]).len();
+                output.truncate(trimmed_len);
+                output.push(NBSP);
+                output.push('»');
+            } else {
+                output.push('«');
+                output.push(NBSP);
+                while chars.get(i + 1).is_some_and(|next| *next == ' ') {
+                    i += 1;
+                }
+            }
+            *in_quote = !*in_quote;
+            i += 1;
+            continue;
+        }
+
+        output.push(c);
+        i += 1;
+    }
+
+    output
+}
+
+fn apply_french_typography(html: &str) -> String {
+    let tag_regex = Regex::new(r"(?s)<!--.*?-->|<[^>]*>").expect("Invalid tag regex");
+    let tag_name_regex =
+        Regex::new(r"^<(/?)([a-zA-Z][a-zA-Z0-9-]*)").expect("Invalid tag name regex");
+    let raw_text_tags = ["code", "pre", "script", "style", "kbd", "samp"];
+
+    let mut output = String::with_capacity(html.len());
+    let mut raw_text_depth = 0usize;
+    let mut in_quote = false;
+    let mut last_end = 0;
+
+    for tag in tag_regex.find_iter(html) {
+        let text = &html[last_end..tag.start()];
+        if raw_text_depth == 0 {
+            output.push_str(&apply_french_typography_to_text(text, &mut in_quote));
+        } else {
+            output.push_str(text);
+        }
+        output.push_str(tag.as_str());
+        last_end = tag.end();
+
+        if let Some(caps) = tag_name_regex.captures(tag.as_str()) {
+            let name = caps[2].to_ascii_lowercase();
+            if raw_text_tags.contains(&name.as_str()) {
+                if caps[1].is_empty() {
+                    raw_text_depth += 1;
+                } else {
+                    raw_text_depth = raw_text_depth.saturating_sub(1);
+                }
+            }
+        }
+    }
+
+    let tail = &html[last_end..];
+    if raw_text_depth == 0 {
+        output.push_str(&apply_french_typography_to_text(tail, &mut in_quote));
+    } else {
+        output.push_str(tail);
+    }
+    output
+}
+
+#[tauri::command]
+async fn fix_french_typography(article_html: String) -> Result<String, String> {
+    logging::info(&format!(
+        "Applying French typography to article (length {}).",
+        article_html.len()
+    ));
+    Ok(apply_french_typography(&article_html))
+}
+
+fn make_tables_responsive(html: &str) -> String {
+    let table_regex = Regex::new(r"(?is)<table\b.*?</table\s*>").expect("Invalid table regex");
+    let wrapper_open = r#"<div class="table-responsive" style="overflow-x:auto">"#;
+
+    let mut output = String::with_capacity(html.len());
+    let mut last_end = 0;
+    for table in table_regex.find_iter(html) {
+        let before = &html[last_end..table.start()];
+        output.push_str(before);
+        if output.trim_end().ends_with(wrapper_open) {
+            output.push_str(table.as_str());
+        } else {
+            output.push_str(wrapper_open);
+            output.push_str(table.as_str());
+            output.push_str("</div>");
+        }
+        last_end = table.end();
+    }
+    output.push_str(&html[last_end..]);
+    output
+}
+
+fn shift_heading_levels(html: &str, shift: i32) -> String {
+    if shift == 0 {
+        return html.to_string();
+    }
+    let heading_regex = Regex::new(r"(?i)<(/?)h([1-6])\b").expect("Invalid heading regex");
+    heading_regex
+        .replace_all(html, |caps: &regex::Captures| {
+            let level: i32 = caps[2].parse().unwrap_or(1);
+            format!("<{}h{}", &caps[1], level.saturating_add(shift).clamp(1, 6))
+        })
+        .to_string()
+}
+
+fn tidy_html(html: &str) -> String {
+    // <style>, <script> and <pre> content is whitespace-sensitive and left untouched.
+    let protected_regex =
+        Regex::new(r"(?is)<(style|script|pre)\b[^>]*>.*?</(style|script|pre)\s*>")
+            .expect("Invalid protected block regex");
+    let mut protected: Vec<String> = Vec::new();
+    let mut output = protected_regex
+        .replace_all(html, |caps: &regex::Captures| {
+            protected.push(caps[0].to_string());
+            format!("\u{0}TIDY_PROTECTED_{}\u{0}", protected.len() - 1)
+        })
+        .to_string();
+
+    let filler = r"(?:\s|&nbsp;|&#160;|<br\s*/?>)*";
+    // Text-level blocks are dropped even with attributes; containers only when bare,
+    // since an empty <div class="..."> is often intentional.
+    let empty_patterns = [
+        format!(
+            r"(?is)<(p|h[1-6]|li|blockquote)\b[^>]*>{}</(p|h[1-6]|li|blockquote)\s*>",
+            filler
+        ),
+        format!(
+            r"(?is)<(div|section|span|strong|em|b|i)\s*>{}</(div|section|span|strong|em|b|i)\s*>",
+            filler
+        ),
+        r"(?is)<(ul|ol)\b[^>]*>\s*</(ul|ol)\s*>".to_string(),
+    ];
+    let empty_regexes: Vec<Regex> = empty_patterns
+        .iter()
+        .map(|pattern| Regex::new(pattern).expect("Invalid empty element regex"))
+        .collect();
+    // Removing an empty <p> can leave its parent empty, so repeat until stable.
+    loop {
+        let before = output.len();
+        for regex in &empty_regexes {
+            output = regex
+                .replace_all(&output, |caps: &regex::Captures| {
+                    if caps[1].eq_ignore_ascii_case(&caps[2]) {
+                        String::new()
+                    } else {
+                        caps[0].to_string()
+                    }
+                })
+                .to_string();
+        }
+        if output.len() == before {
+            break;
+        }
+    }
+
+    let br_run_regex = Regex::new(r"(?i)(?:<br\s*/?>\s*){2,}").expect("Invalid br regex");
+    output = br_run_regex.replace_all(&output, "<br>\n").to_string();
+
+    // Only whitespace runs containing a line break are collapsed, so spaces between
+    // inline elements on the same line are kept.
+    let gap_regex = Regex::new(r">[ \t]*(?:\r?\n[ \t]*)+<").expect("Invalid gap regex");
+    output = gap_regex.replace_all(&output, ">\n<").to_string();
+    let blank_lines_regex = Regex::new(r"[ \t]*\n(?:[ \t]*\n)+").expect("Invalid blank line regex");
+    output = blank_lines_regex.replace_all(&output, "\n").to_string();
+    let trailing_space_regex = Regex::new(r"[ \t]+\n").expect("Invalid trailing space regex");
+    output = trailing_space_regex.replace_all(&output, "\n").to_string();
+
+    for (index, block) in protected.iter().enumerate() {
+        output = output.replace(&format!("\u{0}TIDY_PROTECTED_{}\u{0}", index), block);
+    }
+    output.trim().to_string()
+}
+
+fn merge_rel_tokens(existing: &str, additional: &str) -> String {
+    let mut tokens: Vec<String> = Vec::new();
+    for token in existing
+        .split_whitespace()
+        .chain(additional.split_whitespace())
+    {
+        let token = token.to_ascii_lowercase();
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    tokens.join(" ")
+}
+
+fn normalized_host(url: &reqwest::Url) -> Option<String> {
+    url.host_str()
+        .map(|host| host.trim_start_matches("www.").to_ascii_lowercase())
+}
+
+// Relative, fragment and mailto links have no host and are never external.
+fn parse_external_url(href: &str, site_host: Option<&str>) -> Option<reqwest::Url> {
+    let url = reqwest::Url::parse(href)
+        .ok()
+        .filter(|url| url.scheme() == "http" || url.scheme() == "https")?;
+    let link_host = normalized_host(&url)?;
+    if site_host == Some(link_host.as_str()) {
+        return None;
+    }
+    Some(url)
+}
+
+fn apply_outbound_link_rel(html: &str, site_url: &str, rel: &str) -> String {
+    let site_host = reqwest::Url::parse(site_url.trim())
+        .ok()
+        .and_then(|url| normalized_host(&url));
+    let anchor_regex = Regex::new(r"(?is)<a\b([^>]*)>").expect("Invalid anchor regex");
+    let href_regex =
+        Regex::new(r#"(?is)\bhref\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("Invalid href regex");
+    let rel_regex =
+        Regex::new(r#"(?is)\s\brel\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("Invalid rel regex");
+
+    anchor_regex
+        .replace_all(html, |caps: &regex::Captures| {
+            let attributes = &caps[1];
+            let Some(href) = href_regex
+                .captures(attributes)
+                .and_then(|href_caps| href_caps.get(1).or_else(|| href_caps.get(2)))
+                .map(|m| m.as_str().trim())
+            else {
+                return caps[0].to_string();
+            };
+            if parse_external_url(href, site_host.as_deref()).is_none() {
+                return caps[0].to_string();
+            }
+
+            match rel_regex.captures(attributes) {
+                Some(rel_caps) => {
+                    let existing = rel_caps
+                        .get(1)
+                        .or_else(|| rel_caps.get(2))
+                        .map(|m| m.as_str())
+                        .unwrap_or("");
+                    let merged = format!(r#" rel="{}""#, merge_rel_tokens(existing, rel));
+                    let whole = rel_caps.get(0).expect("Capture group 0 always exists");
+                    format!(
+                        "<a{}{}{}>",
+                        &attributes[..whole.start()],
+                        merged,
+                        &attributes[whole.end()..]
+                    )
+                }
+                None => format!(
+                    r#"<a{} rel="{}">"#,
+                    attributes.trim_end_matches('/').trim_end(),
+                    merge_rel_tokens("", rel)
+                ),
+            }
+        })
+        .to_string()
+}
+
+// Text inside these elements is never turned into a link.
+const NO_AUTOLINK_ELEMENTS: &[&str] = &[
+    "a", "h1", "h2", "h3", "h4", "h5", "h6", "title", "script", "style", "button", "code", "pre",
+];
+
+fn link_first_mention(html: &str, term: &str, url: &str) -> String {
+    let term = term.trim();
+    let url = url.trim();
+    if term.is_empty() || url.is_empty() {
+        return html.to_string();
+    }
+
+    // Skip entirely when the article already links to the official site.
+    let wanted = reqwest::Url::parse(url).ok();
+    let wanted_host = wanted.as_ref().and_then(normalized_host);
+    let already_linked = Regex::new(r#"(?is)<a\b[^>]*\bhref\s*=\s*["']([^"']*)["']"#)
+        .expect("Invalid anchor href regex")
+        .captures_iter(html)
+        .any(|caps| {
+            let href = caps[1].trim();
+            href == url
+                || reqwest::Url::parse(href)
+                    .ok()
+                    .and_then(|link| normalized_host(&link))
+                    .is_some_and(|host| Some(&host) == wanted_host.as_ref())
+        });
+    if already_linked {
+        return html.to_string();
+    }
+
+    let Ok(term_regex) = Regex::new(&format!(r"(?i)\b{}\b", regex::escape(term))) else {
+        return html.to_string();
+    };
+    let tag_regex = Regex::new(r"(?s)<(/?)([a-zA-Z][a-zA-Z0-9]*)\b[^>]*>|<!--.*?-->")
+        .expect("Invalid tag regex");
+    let body_start = Regex::new(r"(?i)<body\b[^>]*>")
+        .expect("Invalid body regex")
+        .find(html)
+        .map_or(0, |m| m.end());
+
+    let mut excluded_depth = 0usize;
+    let mut text_start = body_start;
+    let mut found: Option<(usize, usize)> = None;
+    for tag in tag_regex.captures_iter(&html[body_start..]) {
+        let whole = tag.get(0).expect("Capture group 0 always exists");
+        let (start, end) = (body_start + whole.start(), body_start + whole.end());
+        if excluded_depth == 0 {
+            if let Some(m) = term_regex.find(&html[text_start..start]) {
+                found = Some((text_start + m.start(), text_start + m.end()));
+                break;
+            }
+        }
+        if let Some(name) = tag.get(2) {
+            if NO_AUTOLINK_ELEMENTS.contains(&name.as_str().to_ascii_lowercase().as_str()) {
+                if tag.get(1).is_some_and(|slash| !slash.as_str().is_empty()) {
+                    excluded_depth = excluded_depth.saturating_sub(1);
+                } else {
+                    excluded_depth += 1;
+                }
+            }
+        }
+        text_start = end;
+    }
+    if found.is_none() && excluded_depth == 0 {
+        found = term_regex
+            .find(&html[text_start..])
+            .map(|m| (text_start + m.start(), text_start + m.end()));
+    }
+
+    match found {
+        Some((start, end)) => format!(
+            r#"{}<a href="{}">{}</a>{}"#,
+            &html[..start],
+            escape_html_attribute(url),
+            &html[start..end],
+            &html[end..]
+        ),
+        None => html.to_string(),
+    }
+}
+
+fn apply_utm_params(html: &str, site_url: &str, params: &HashMap<String, String>) -> String {
+    let mut params: Vec<(&str, &str)> = params
+        .iter()
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .filter(|(key, value)| !key.is_empty() && !value.is_empty())
+        .collect();
+    if params.is_empty() {
+        return html.to_string();
+    }
+    params.sort();
+
+    let site_host = reqwest::Url::parse(site_url.trim())
+        .ok()
+        .and_then(|url| normalized_host(&url));
+    let href_regex = Regex::new(r#"(?is)(<a\b[^>]*?\bhref\s*=\s*)(?:"([^"]*)"|'([^']*)')"#)
+        .expect("Invalid anchor href regex");
+
+    href_regex
+        .replace_all(html, |caps: &regex::Captures| {
+            let (raw_href, quote) = match caps.get(2) {
+                Some(m) => (m.as_str(), '"'),
+                None => (caps.get(3).map_or("", |m| m.as_str()), '\''),
+            };
+            let href = raw_href.trim().replace("&amp;", "&");
+            let Some(mut url) = parse_external_url(&href, site_host.as_deref()) else {
+                return caps[0].to_string();
+            };
+            // Parameters already on the link (e.g. a hand-set utm_source) win over the defaults.
+            let existing: HashSet<String> =
+                url.query_pairs().map(|(key, _)| key.into_owned()).collect();
+            let missing: Vec<&(&str, &str)> = params
+                .iter()
+                .filter(|(key, _)| !existing.contains(*key))
+                .collect();
+            if missing.is_empty() {
+                return caps[0].to_string();
+            }
+            {
+                let mut query = url.query_pairs_mut();
+                for (key, value) in missing {
+                    query.append_pair(key, value);
+                }
+            }
+            format!(
+                "{}{}{}{}",
+                &caps[1],
+                quote,
+                url.as_str().replace('&', "&amp;"),
+                quote
+            )
+        })
+        .to_string()
+}
+
+fn add_table_data_labels(html: &str) -> String {
+    let table_regex = Regex::new(r"(?is)<table\b.*?</table\s*>").expect("Invalid table regex");
+    let row_regex = Regex::new(r"(?is)<tr\b[^>]*>.*?</tr\s*>").expect("Invalid row regex");
+    let header_regex = Regex::new(r"(?is)<th\b([^>]*)>(.*?)</th\s*>").expect("Invalid th regex");
+    let cell_regex = Regex::new(r"(?is)<td\b([^>]*)>").expect("Invalid td regex");
+    let colspan_regex =
+        Regex::new(r#"(?i)\bcolspan\s*=\s*["']?(\d+)"#).expect("Invalid colspan regex");
+    // A header spanning several columns labels each of them; a spanning cell skips as many.
+    let colspan_of = |attributes: &str| -> usize {
+        colspan_regex
+            .captures(attributes)
+            .and_then(|caps| caps[1].parse::<usize>().ok())
+            .unwrap_or(1)
+            .clamp(1, 100)
+    };
+
+    table_regex
+        .replace_all(html, |table_caps: &regex::Captures| {
+            let table_html = &table_caps[0];
+            let headers: Vec<String> = row_regex
+                .find_iter(table_html)
+                .map(|row| {
+                    header_regex
+                        .captures_iter(row.as_str())
+                        .flat_map(|caps| {
+                            std::iter::repeat_n(strip_html_tags(&caps[2]), colspan_of(&caps[1]))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .find(|headers| !headers.is_empty())
+                .unwrap_or_default();
+            if headers.is_empty() {
+                return table_html.to_string();
+            }
+
+            row_regex
+                .replace_all(table_html, |row_caps: &regex::Captures| {
+                    let mut cell_index = 0;
+                    cell_regex
+                        .replace_all(&row_caps[0], |cell_caps: &regex::Captures| {
+                            let attributes = &cell_caps[1];
+                            let label = headers.get(cell_index);
+                            cell_index += colspan_of(attributes);
+                            match label {
+                                Some(label) if !attributes.contains("data-label") => format!(
+                                    r#"<td{} data-label="{}">"#,
+                                    attributes,
+                                    escape_html_attribute(label)
+                                ),
+                                _ => cell_caps[0].to_string(),
+                            }
+                        })
+                        .to_string()
+                })
+                .to_string()
+        })
+        .to_string()
+}
+
+fn parse_wordpress_error(status: StatusCode, body: &str) -> WordPressError {
+    let parsed = serde_json::from_str::<serde_json::Value>(body).ok();
+    let field = |name: &str| {
+        parsed
+            .as_ref()
+            .and_then(|json| json.get(name))
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string())
+    };
+
+    match (field("code"), field("message")) {
+        (Some(code), Some(message)) => WordPressError {
+            code,
+            message: strip_html_tags(&message),
+            status: parsed
+                .as_ref()
+                .and_then(|json| json.pointer("/data/status"))
+                .and_then(|value| value.as_u64())
+                .map(|value| value as u16)
+                .or(Some(status.as_u16())),
+        },
+        _ => WordPressError {
+            code: format!("http_{}", status.as_u16()),
+            message: if body.trim().is_empty() {
+                format!("WordPress API request failed with status {}", status)
+            } else {
+                body.trim().to_string()
+            },
+            status: Some(status.as_u16()),
+        },
+    }
+}
+
+// Matches PHP's default post_max_size of 8M.
+const DEFAULT_MAX_PAYLOAD_BYTES: u64 = 8 * 1024 * 1024;
+
+fn format_byte_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+#[tauri::command]
+async fn publish_to_wordpress(
+    app: tauri::AppHandle,
+    request: PublishRequest,
+) -> Result<String, WordPressError> {
+    logging::info(&format!(
+        "Received request to publish article for project: {}",
+        request.project_name
+    ));
+    if let Some(cat_id) = request.category_id {
+        logging::info(&format!("Requested category ID: {}", cat_id));
+    }
+    match request.featured_media_id {
+        Some(fm_id) => logging::info(&format!("Requested Featured Media ID: {}", fm_id)),
+        None => logging::info("No Featured Media ID requested (was None)."),
+    }
+    if let Some(slug_val) = &request.slug {
+        logging::info(&format!("Requested slug: {:?}", slug_val));
+    }
+    if let Some(ref s_date) = request.schedule_date {
+        logging::info(&format!("Requested schedule date: {}", s_date));
+    }
+    if let Some(author_id) = request.author_id {
+        logging::info(&format!("Requested author ID: {}", author_id));
+    }
+    logging::info(&format!(
+        "Requested publish status: {:?}",
+        request.publish_status
+    ));
+
+    if is_safe_mode(&app) {
+        logging::info("Safe mode enabled, skipping WordPress publish.");
+        return Ok(format!(
+            "Safe mode is enabled: article for project '{}' was not sent to WordPress.",
+            request.project_name
+        ));
+    }
+
+    let settings = get_project_settings(app.clone(), request.project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", request.project_name))?;
+
+    if settings.wordpress_url.trim().is_empty() {
+        return Err("WordPress URL is not configured in project settings."
+            .to_string()
+            .into());
+    }
+    if settings.wordpress_user.trim().is_empty() {
+        return Err("WordPress User is not configured in project settings."
+            .to_string()
+            .into());
+    }
+    if settings.wordpress_pass.trim().is_empty() {
+        return Err("WordPress Application Password is not configured."
+            .to_string()
+            .into());
+    }
+
+    if let Some(taxonomies) = &request.custom_taxonomies {
+        if let Some(key) = taxonomies
+            .keys()
+            .find(|key| !is_valid_taxonomy_key(key) || RESERVED_POST_FIELDS.contains(&key.as_str()))
+        {
+            return Err(format!("Invalid custom taxonomy key '{}'.", key).into());
+        }
+        logging::info(&format!("Requested custom taxonomies: {:?}", taxonomies));
+    }
+
+    let default_title = format!("Generated Article for {}", settings.tool_name);
+    let post_title = request
+        .article_title
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or(&default_title);
+
+    logging::info(&format!("Using post title: '{}'", post_title));
+
+    let mut final_content_for_wp = request.article_html.trim().to_string();
+    if request.tidy_html.unwrap_or(false) {
+        logging::info("Tidying HTML whitespace and empty elements.");
+        final_content_for_wp = tidy_html(&final_content_for_wp);
+    }
+    if request.demote_headings.unwrap_or(false) {
+        logging::info("Demoting heading levels by one.");
+        final_content_for_wp = shift_heading_levels(&final_content_for_wp, 1);
+    }
+    if request.table_data_labels.unwrap_or(false) {
+        logging::info("Adding data-label attributes to table cells.");
+        final_content_for_wp = add_table_data_labels(&final_content_for_wp);
+    }
+    if request.responsive_tables.unwrap_or(false) {
+        logging::info("Wrapping tables in responsive containers.");
+        final_content_for_wp = make_tables_responsive(&final_content_for_wp);
+    }
+    if let Some(official_url) = settings
+        .official_url
+        .as_deref()
+        .filter(|url| !url.trim().is_empty())
+    {
+        logging::info(&format!(
+            "Linking first mention of '{}' to {}.",
+            settings.tool_name, official_url
+        ));
+        final_content_for_wp =
+            link_first_mention(&final_content_for_wp, &settings.tool_name, official_url);
+    }
+    if let Some(rel) = settings
+        .outbound_link_rel
+        .as_deref()
+        .filter(|rel| !rel.trim().is_empty())
+    {
+        logging::info(&format!("Adding rel=\"{}\" to outbound links.", rel.trim()));
+        final_content_for_wp =
+            apply_outbound_link_rel(&final_content_for_wp, &settings.wordpress_url, rel);
+    }
+    if let Some(utm_params) = settings.utm_params.as_ref().filter(|p| !p.is_empty()) {
+        logging::info("Adding UTM parameters to outbound links.");
+        final_content_for_wp =
+            apply_utm_params(&final_content_for_wp, &settings.wordpress_url, utm_params);
+    }
+    if request.include_jsonld.unwrap_or(false) {
+        match extract_seo_metadata(&final_content_for_wp) {
+            Ok(mut meta) => {
+                if let Some(title) = request
+                    .article_title
+                    .as_deref()
+                    .filter(|s| !s.trim().is_empty())
+                {
+                    meta.title = title.trim().to_string();
+                }
+                let author = request
+                    .jsonld_author
+                    .as_deref()
+                    .filter(|s| !s.trim().is_empty())
+                    .unwrap_or(&settings.wordpress_user);
+                let published = request
+                    .schedule_date
+                    .clone()
+                    .filter(|s| !s.trim().is_empty())
+                    .unwrap_or_else(|| format_iso8601_utc(unix_timestamp_secs()));
+                // Post content has no <head>; WordPress outputs the script where it
+                // appears, which search engines accept for JSON-LD.
+                let jsonld = build_article_jsonld(
+                    &meta,
+                    author,
+                    &published,
+                    request.featured_image_url.as_deref(),
+                );
+                logging::info("Injecting JSON-LD BlogPosting schema.");
+                final_content_for_wp = format!("{}\n{}", jsonld, final_content_for_wp);
+            }
+            Err(e) => logging::warn(&format!("Skipping JSON-LD injection: {}", e)),
+        }
+    }
+    logging::info(&format!(
+        "Content for WordPress (already body-only). Length: {}",
+        final_content_for_wp.len()
+    ));
+    if final_content_for_wp.is_empty() {
+        logging::warn("Content for WordPress is empty after processing.");
+    }
+
+    let api_url = format!(
+        "{}/wp-json/wp/v2/posts",
+        settings.wordpress_url.trim_end_matches('/')
+    );
+    logging::info(&format!("Posting to WordPress API URL: {}", api_url));
+
+    let mut final_status = request
+        .publish_status
+        .as_deref()
+        .filter(|s| ["publish", "draft", "pending"].contains(s))
+        .unwrap_or("publish");
+
+    let mut date_to_set: Option<&str> = None;
+
+    if let Some(schedule_str) = request.schedule_date.as_deref() {
+        if !schedule_str.trim().is_empty() {
+            final_status = "future";
+            date_to_set = Some(schedule_str);
+            logging::info(&format!(
+                "Scheduling post. Status set to 'future', date to '{}'",
+                schedule_str
+            ));
+        }
+    }
+
+    logging::info(&format!(
+        "Final publish status for WP API: '{}'",
+        final_status
+    ));
+
+    let post_payload = WordPressPostPayload {
+        title: post_title,
+        content: &final_content_for_wp,
+        status: final_status,
+        categories: request.category_id.map(|id| vec![id]),
+        tags: request.tags.as_deref().filter(|tags| !tags.is_empty()),
+        // WordPress uses 0 for "no featured image", so treat it like None.
+        featured_media: request.featured_media_id.filter(|id| *id != 0),
+        slug: request.slug.as_deref(),
+        date: date_to_set,
+        author: request.author_id,
+        custom_taxonomies: request.custom_taxonomies.as_ref(),
+    };
+
+    let payload_bytes = serde_json::to_vec(&post_payload)
+        .map_err(|e| format!("Failed to serialize WordPress post payload: {}", e))?;
+    let max_payload_bytes = settings
+        .max_payload_bytes
+        .unwrap_or(DEFAULT_MAX_PAYLOAD_BYTES);
+    logging::info(&format!(
+        "Post payload is {} bytes (limit {} bytes).",
+        payload_bytes.len(),
+        max_payload_bytes
+    ));
+    if payload_bytes.len() as u64 > max_payload_bytes {
+        return Err(WordPressError {
+            code: "payload_too_large".to_string(),
+            message: format!(
+                "The post payload is {} ({} bytes), which exceeds the configured limit of {} ({} bytes). The server would likely reject it with 413 or truncate it. Remove embedded base64 images or shorten the article, or raise the limit if the server's post_max_size allows it.",
+                format_byte_size(payload_bytes.len() as u64),
+                payload_bytes.len(),
+                format_byte_size(max_payload_bytes),
+                max_payload_bytes
+            ),
+            status: None,
+        });
+    }
+
+    let client = shared_client(&app);
+    logging::info(&format!(
+        "Authenticating with WP User: {}",
+        settings.wordpress_user
+    ));
+    let response = client
+        .post(&api_url)
+        .basic_auth(&settings.wordpress_user, Some(&settings.wordpress_pass))
+        .timeout(request_timeout(request.timeout_secs))
+        .header(CONTENT_TYPE, "application/json")
+        .body(payload_bytes)
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to WordPress API: {}", e))?;
+
+    let status = response.status();
+    logging::info(&format!(
+        "Received response from WordPress API (Status: {})",
+        status
+    ));
+
+    if status.is_success() || status.as_u16() == 201 {
+        let response_text = response.text().await.unwrap_or_default();
+        logging::info(&format!(
+            "WordPress API Success Response: {}",
+            response_text
+        ));
+        let mut post_link_msg = "".to_string();
+        if let Ok(json_response) = serde_json::from_str::<serde_json::Value>(&response_text) {
+            if let Some(link) = json_response.get("link").and_then(|v| v.as_str()) {
+                post_link_msg = format!(" View post: {}", link);
+            }
+        }
+
+        let mut success_message = format!(
+            "Article successfully processed by WordPress with status '{}'",
+            final_status
+        );
+        if let Some(d) = date_to_set {
+            success_message.push_str(&format!(" and scheduled for {}", d));
+        }
+        if let Some(cat_id) = request.category_id {
+            success_message.push_str(&format!(" in category ID {}", cat_id));
+        }
+        if let Some(fm_id) = request.featured_media_id.filter(|id| *id != 0) {
+            success_message.push_str(&format!(" with featured image ID {}", fm_id));
+        }
+        if let Some(author_id) = request.author_id {
+            success_message.push_str(&format!(" by author ID {}", author_id));
+        }
+        success_message.push_str("!");
+        success_message.push_str(&post_link_msg);
+
+        Ok(success_message)
+    } else {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
+        logging::info(&format!(
+            "WordPress API request failed - Status: {}, Body: {}",
+            status, error_text
+        ));
+        let mut wp_error = parse_wordpress_error(status, &error_text);
+        if status == StatusCode::FORBIDDEN {
+            if let Some(author_id) = request.author_id {
+                wp_error.message = format!(
+                    "WordPress refused to publish as author ID {}. The configured user needs permission to publish on behalf of other authors: {}",
+                    author_id, wp_error.message
+                );
+            }
+        }
+        Err(wp_error)
+    }
+}
+
+#[tauri::command]
+async fn upload_images_to_wordpress(
+    app: tauri::AppHandle,
+    request: UploadImageRequest,
+) -> Result<UploadImagesResponse, String> {
+    logging::info(&format!(
+        "Received request to upload {} images for project: {}",
+        request.image_urls.len(),
+        request.project_name
+    ));
+
+    if is_safe_mode(&app) {
+        logging::info("Safe mode enabled, skipping WordPress media upload.");
+        return Ok(UploadImagesResponse {
+            results: request
+                .image_urls
+                .iter()
+                .map(|image_url| ImageUploadResult::dry_run(image_url))
+                .collect(),
+        });
+    }
+
+    let settings = get_project_settings(app.clone(), request.project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", request.project_name))?;
+
+    if settings.wordpress_url.trim().is_empty()
+        || settings.wordpress_user.trim().is_empty()
+        || settings.wordpress_pass.trim().is_empty()
+    {
+        return Err(
+            "WordPress URL, User, and Application Password must be configured.".to_string(),
+        );
+    }
+
+    let media_api_url = format!(
+        "{}/wp-json/wp/v2/media",
+        settings.wordpress_url.trim_end_matches('/')
+    );
+    logging::info(&format!("Uploading media to URL: {}", media_api_url));
+
+    let client = shared_client(&app);
+    let download_timeout = Duration::from_secs(settings.image_download_timeout_secs.max(1));
+    let download_semaphore = Arc::new(Semaphore::new(settings.image_download_concurrency.max(1)));
+    logging::info(&format!(
+        "Downloading images with concurrency {} and timeout {:?}",
+        settings.image_download_concurrency.max(1),
+        download_timeout
+    ));
+
+    let mut existing_media: Vec<Option<WordPressMediaItem>> =
+        request.image_urls.iter().map(|_| None).collect();
+    if request.dedup.unwrap_or(false) {
+        for (index, image_url) in request.image_urls.iter().enumerate() {
+            let Some(filename) = filename_from_image_url(image_url) else {
+                continue;
+            };
+            match search_wordpress_media(&client, &settings, &filename).await {
+                Ok(found) => existing_media[index] = found,
+                Err(e) => logging::warn(&format!(
+                    "Media lookup for '{}' failed, uploading anyway: {}",
+                    filename, e
+                )),
+            }
+        }
+    }
+
+    // Images past the daily quota are neither downloaded nor uploaded.
+    let mut quota_blocked: Vec<bool> = request.image_urls.iter().map(|_| false).collect();
+    let mut reserved_uploads = 0;
+    if let Some(limit) = settings.daily_upload_limit {
+        let needed = existing_media
+            .iter()
+            .filter(|found| found.is_none())
+            .count() as u32;
+        reserved_uploads = reserve_daily_uploads(&app, &request.project_name, limit, needed)?;
+        let mut remaining = reserved_uploads;
+        logging::info(&format!(
+            "Daily upload quota: reserved {} of {} upload(s) needed (limit {}).",
+            reserved_uploads, needed, limit
+        ));
+        for (index, blocked) in quota_blocked.iter_mut().enumerate() {
+            if existing_media[index].is_some() {
+                continue;
+            }
+            if remaining == 0 {
+                *blocked = true;
+            } else {
+                remaining -= 1;
+            }
+        }
+    }
+
+    let mut download_tasks = JoinSet::new();
+    for (index, image_url) in request.image_urls.iter().enumerate() {
+        if existing_media[index].is_some() || quota_blocked[index] {
+            continue;
+        }
+        let client = client.clone();
+        let image_url = image_url.clone();
+        let semaphore = download_semaphore.clone();
+        download_tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = download_image_bytes(&client, &image_url, download_timeout).await;
+            (index, result)
+        });
+    }
+
+    let mut downloads: Vec<Option<Result<Vec<u8>, String>>> =
+        request.image_urls.iter().map(|_| None).collect();
+    while let Some(joined) = download_tasks.join_next().await {
+        match joined {
+            Ok((index, result)) => downloads[index] = Some(result),
+            Err(e) => logging::error(&format!("Image download task failed: {}", e)),
+        }
+    }
+
+    let upload_retry = Arc::new(settings.upload_retry.clone().unwrap_or_default());
+    let upload_timeout = request_timeout_or(request.timeout_secs, DEFAULT_UPLOAD_TIMEOUT_SECS);
+    let upload_concurrency = request
+        .upload_concurrency
+        .unwrap_or(DEFAULT_UPLOAD_CONCURRENCY)
+        .clamp(1, MAX_UPLOAD_CONCURRENCY);
+    let upload_semaphore = Arc::new(Semaphore::new(upload_concurrency));
+    logging::info(&format!(
+        "Uploading images with concurrency {}",
+        upload_concurrency
+    ));
+    let mut upload_results: Vec<Option<ImageUploadResult>> =
+        request.image_urls.iter().map(|_| None).collect();
+    let mut upload_tasks = JoinSet::new();
+
+    for (index, image_url) in request.image_urls.iter().enumerate() {
+        logging::info(&format!(
+            "Processing image URL {}: {}",
+            index + 1,
+            image_url
+        ));
+        if let Some(existing) = existing_media[index].take() {
+            logging::info(&format!(
+                "Reusing existing media {} for {}",
+                existing.id, image_url
+            ));
+            upload_results[index] = Some(ImageUploadResult::uploaded(
+                image_url,
+                existing.id,
+                existing.source_url,
+                true,
+            ));
+            continue;
+        }
+        if quota_blocked[index] {
+            logging::info(&format!(
+                "Daily upload quota reached, skipping {}",
+                image_url
+            ));
+            let message = format!(
+                "Daily upload quota of {} image(s) reached for project '{}'. Retry after midnight.",
+                settings.daily_upload_limit.unwrap_or(0),
+                request.project_name
+            );
+            upload_results[index] = Some(ImageUploadResult {
+                error_code: Some(DAILY_QUOTA_REACHED_ERROR.to_string()),
+                ..ImageUploadResult::failed(image_url, message)
+            });
+            continue;
+        }
+        let image_bytes = match downloads[index].take() {
+            Some(Ok(bytes)) => bytes,
+            Some(Err(err_msg)) => {
+                let expired = err_msg.starts_with(IMAGE_URL_EXPIRED_ERROR);
+                upload_results[index] = Some(ImageUploadResult {
+                    error_code: expired.then(|| IMAGE_URL_EXPIRED_ERROR.to_string()),
+                    regenerate_with: if expired {
+                        generated_image_params(image_url)
+                    } else {
+                        None
+                    },
+                    ..ImageUploadResult::failed(image_url, err_msg)
+                });
+                continue;
+            }
+            None => {
+                upload_results[index] = Some(ImageUploadResult::failed(
+                    image_url,
+                    format!("Download task for {} did not complete.", image_url),
+                ));
+                continue;
+            }
+        };
+        let alt_text = request
+            .alt_texts
+            .as_ref()
+            .and_then(|alt_texts| alt_texts.get(index))
+            .map(|alt_text| alt_text.trim().to_string())
+            .filter(|alt_text| !alt_text.is_empty());
+
+        let client = client.clone();
+        let media_api_url = media_api_url.clone();
+        let wp_user = settings.wordpress_user.clone();
+        let wp_pass = settings.wordpress_pass.clone();
+        let image_url = image_url.clone();
+        let upload_retry = upload_retry.clone();
+        let semaphore = upload_semaphore.clone();
+        upload_tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = process_single_image_upload(
+                &client,
+                &media_api_url,
+                &wp_user,
+                &wp_pass,
+                &image_url,
+                image_bytes,
+                upload_timeout,
+                &upload_retry,
+            )
+            .await;
+            if let (Some(media_id), Some(alt_text)) = (result.wordpress_media_id, alt_text) {
+                if let Err(e) = update_media_alt_text(
+                    &client,
+                    &media_api_url,
+                    &wp_user,
+                    &wp_pass,
+                    media_id,
+                    &alt_text,
+                    upload_timeout,
+                )
+                .await
+                {
+                    logging::warn(&format!(
+                        "Uploaded media {} but failed to set its alt text: {}",
+                        media_id, e
+                    ));
+                }
+            }
+            (index, result)
+        });
+    }
+
+    while let Some(joined) = upload_tasks.join_next().await {
+        match joined {
+            Ok((index, result)) => upload_results[index] = Some(result),
+            Err(e) => logging::error(&format!("Image upload task failed: {}", e)),
+        }
+    }
+
+    let upload_results: Vec<ImageUploadResult> = upload_results
+        .into_iter()
+        .zip(request.image_urls.iter())
+        .map(|(result, image_url)| {
+            result.unwrap_or_else(|| {
+                ImageUploadResult::failed(
+                    image_url,
+                    format!("Upload task for {} did not complete.", image_url),
+                )
+            })
+        })
+        .collect();
+
+    let uploaded = upload_results
+        .iter()
+        .filter(|result| result.success && !result.reused)
+        .count() as u32;
+    if let Err(e) = release_daily_uploads(
+        &app,
+        &request.project_name,
+        reserved_uploads.saturating_sub(uploaded),
+    ) {
+        logging::warn(&format!("Failed to release unused upload quota: {}", e));
+    }
+
+    logging::info("Finished processing all image uploads.");
+    Ok(UploadImagesResponse {
+        results: upload_results,
+    })
+}
+
+async fn update_media_alt_text(
+    client: &Client,
+    media_api_url: &str,
+    wp_user: &str,
+    wp_pass: &str,
+    media_id: u32,
+    alt_text: &str,
+    timeout: Duration,
+) -> Result<(), String> {
+    let media_url = format!("{}/{}", media_api_url.trim_end_matches('/'), media_id);
+    logging::info(&format!(
+        "Setting alt text on WP media {}: '{}'",
+        media_id, alt_text
+    ));
+    let response = client
+        .post(&media_url)
+        .basic_auth(wp_user, Some(wp_pass))
+        .timeout(timeout)
+        .json(&serde_json::json!({ "alt_text": alt_text }))
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send alt text update to WordPress: {}", e))?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
+        Err(format!(
+            "Failed to set alt text on media {} (Status {}): {}",
+            media_id, status, error_text
+        ))
+    }
+}
+
+#[tauri::command]
+async fn set_media_alt_text(
+    app: tauri::AppHandle,
+    project_name: String,
+    media_id: u32,
+    alt_text: String,
+) -> Result<(), String> {
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+
+    if settings.wordpress_url.trim().is_empty()
+        || settings.wordpress_user.trim().is_empty()
+        || settings.wordpress_pass.trim().is_empty()
+    {
+        return Err(
+            "WordPress URL, User, and Application Password must be configured.".to_string(),
+        );
+    }
+
+    let media_api_url = format!(
+        "{}/wp-json/wp/v2/media",
+        settings.wordpress_url.trim_end_matches('/')
+    );
+    update_media_alt_text(
+        &shared_client(&app),
+        &media_api_url,
+        &settings.wordpress_user,
+        &settings.wordpress_pass,
+        media_id,
+        alt_text.trim(),
+        request_timeout_or(None, DEFAULT_UPLOAD_TIMEOUT_SECS),
+    )
+    .await
+}
+
+const IMAGE_URL_EXPIRED_ERROR: &str = "url_expired";
+const MAX_REMEMBERED_IMAGE_PARAMS: usize = 200;
+
+static GENERATED_IMAGE_PARAMS: std::sync::Mutex<Vec<(String, ImageGenerationParams)>> =
+    std::sync::Mutex::new(Vec::new());
+
+fn remember_generated_image(image_url: &str, params: ImageGenerationParams) {
+    if let Ok(mut remembered) = GENERATED_IMAGE_PARAMS.lock() {
+        remembered.retain(|(url, _)| url != image_url);
+        if remembered.len() >= MAX_REMEMBERED_IMAGE_PARAMS {
+            remembered.remove(0);
+        }
+        remembered.push((image_url.to_string(), params));
+    }
+}
+
+fn generated_image_params(image_url: &str) -> Option<ImageGenerationParams> {
+    let remembered = GENERATED_IMAGE_PARAMS.lock().ok()?;
+    remembered
+        .iter()
+        .find(|(url, _)| url == image_url)
+        .map(|(_, params)| params.clone())
+}
+
+// Ideogram serves generated images from short-lived signed URLs; once the
+// signature lapses the CDN answers with a 4xx instead of the image. Other
+// hosts' 4xx responses are ordinary download failures.
+fn is_expired_image_url_response(image_url: &str, status: StatusCode) -> bool {
+    if !matches!(
+        status,
+        StatusCode::BAD_REQUEST | StatusCode::FORBIDDEN | StatusCode::NOT_FOUND | StatusCode::GONE
+    ) {
+        return false;
+    }
+    reqwest::Url::parse(image_url)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host.to_ascii_lowercase()))
+        .is_some_and(|host| host == "ideogram.ai" || host.ends_with(".ideogram.ai"))
+}
+
+const DAILY_QUOTA_REACHED_ERROR: &str = "daily_quota_reached";
+
+// Counters are keyed by the local calendar day, so they reset at local midnight.
+fn local_day_key() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn get_daily_uploads_from_store(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+) -> Result<DailyUploadsMap, String> {
+    match store.get(STORE_KEY_DAILY_UPLOADS) {
+        Some(JsonValue::Null) | None => Ok(DailyUploadsMap::new()),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| format!("Failed to deserialize daily upload counters: {}", e)),
+    }
+}
+
+fn get_daily_upload_count(app: &tauri::AppHandle, project_name: &str) -> Result<u32, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let today = local_day_key();
+    Ok(get_daily_uploads_from_store(&store)?
+        .get(project_name)
+        .filter(|counter| counter.date == today)
+        .map_or(0, |counter| counter.count))
+}
+
+fn update_daily_upload_counter(
+    app: &tauri::AppHandle,
+    project_name: &str,
+    update: impl FnOnce(&mut DailyUploadCounter) -> u32,
+) -> Result<u32, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let today = local_day_key();
+    let mut counters = get_daily_uploads_from_store(&store)?;
+    let counter = counters
+        .entry(project_name.to_string())
+        .or_insert_with(|| DailyUploadCounter {
+            date: today.clone(),
+            count: 0,
+        });
+    if counter.date != today {
+        counter.date = today;
+        counter.count = 0;
+    }
+    let result = update(counter);
+    store.set(
+        STORE_KEY_DAILY_UPLOADS.to_string(),
+        serde_json::to_value(&counters)
+            .map_err(|e| format!("Failed to serialize daily upload counters: {}", e))?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+    Ok(result)
+}
+
+// Quota is taken before uploading so concurrent uploads can't overshoot the limit;
+// returns how many of the requested uploads were granted.
+fn reserve_daily_uploads(
+    app: &tauri::AppHandle,
+    project_name: &str,
+    limit: u32,
+    requested: u32,
+) -> Result<u32, String> {
+    update_daily_upload_counter(app, project_name, |counter| {
+        let granted = requested.min(limit.saturating_sub(counter.count));
+        counter.count += granted;
+        granted
+    })
+}
+
+// Gives back reservations for uploads that did not go through.
+fn release_daily_uploads(
+    app: &tauri::AppHandle,
+    project_name: &str,
+    unused: u32,
+) -> Result<(), String> {
+    if unused == 0 {
+        return Ok(());
+    }
+    update_daily_upload_counter(app, project_name, |counter| {
+        counter.count = counter.count.saturating_sub(unused);
+        counter.count
+    })
+    .map(|_| ())
+}
+
+#[tauri::command]
+async fn get_daily_upload_usage(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<DailyUploadUsage, String> {
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+    Ok(DailyUploadUsage {
+        date: local_day_key(),
+        count: get_daily_upload_count(&app, &project_name)?,
+        limit: settings.daily_upload_limit,
+    })
+}
+
+async fn download_image_bytes(
+    client: &Client,
+    image_url: &str,
+    timeout: Duration,
+) -> Result<Vec<u8>, String> {
+    let download_response =
+        user_agent::apply_to_builder(client.get(image_url), UserAgentTarget::ImageDownload)
+            .timeout(timeout)
+            .send_recorded()
+            .await
+            .map_err(|e| {
+                let err_msg = format!("Failed to start download for {}: {}", image_url, e);
+                logging::error(&err_msg);
+                err_msg
+            })?;
+
+    if !download_response.status().is_success() {
+        let status = download_response.status();
+        let err_msg = if is_expired_image_url_response(image_url, status) {
+            format!(
+                "{}: The image URL {} has expired (Status {}). Regenerate the image and upload the new URL.",
+                IMAGE_URL_EXPIRED_ERROR, image_url, status
+            )
+        } else {
+            format!(
+                "Failed to download image from {}: Status {}",
+                image_url, status
+            )
+        };
+        logging::error(&err_msg);
+        return Err(err_msg);
+    }
+
+    let image_bytes = download_response.bytes().await.map_err(|e| {
+        let err_msg = format!("Failed to read image bytes from {}: {}", image_url, e);
+        logging::error(&err_msg);
+        err_msg
+    })?;
+    logging::info(&format!(
+        "Successfully downloaded {} bytes from {}",
+        image_bytes.len(),
+        image_url
+    ));
+    Ok(image_bytes.to_vec())
+}
+
+fn filename_from_image_url(image_url: &str) -> Option<String> {
+    let url_path = image_url.split('?').next().unwrap_or(image_url);
+    let url_path = url_path.split('#').next().unwrap_or(url_path);
+    Path::new(url_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_string())
+        .filter(|name| !name.is_empty())
+}
+
+// WordPress renames uploads on collision ("image-1.png") and scales large ones
+// ("image-scaled.png"), so those variants count as the same file.
+fn media_matches_filename(source_url: &str, filename: &str) -> bool {
+    let Some(source_name) = filename_from_image_url(source_url) else {
+        return false;
+    };
+    let stem = |name: &str| {
+        Path::new(name)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(name)
+            .to_ascii_lowercase()
+    };
+    let wanted = stem(filename);
+    let source = stem(&source_name);
+    let suffix = match source.strip_prefix(&wanted) {
+        Some(suffix) => suffix,
+        None => return false,
+    };
+    suffix.is_empty()
+        || suffix == "-scaled"
+        || suffix
+            .strip_prefix('-')
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+async fn search_wordpress_media(
+    client: &Client,
+    settings: &ProjectSettings,
+    filename: &str,
+) -> Result<Option<WordPressMediaItem>, String> {
+    let search_term = Path::new(filename)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(filename);
+    let media_api_url = format!(
+        "{}/wp-json/wp/v2/media",
+        settings.wordpress_url.trim_end_matches('/')
+    );
+    logging::info(&format!("Searching media library for '{}'", search_term));
+
+    let response = client
+        .get(&media_api_url)
+        .query(&[
+            ("search", search_term),
+            ("per_page", "20"),
+            ("_fields", "id,source_url"),
+        ])
+        .basic_auth(&settings.wordpress_user, Some(&settings.wordpress_pass))
+        .timeout(request_timeout(None))
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to WordPress Media API: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
+        return Err(format!(
+            "Failed to search media library (Status {}): {}",
+            status, error_text
+        ));
+    }
+    let items = response
+        .json::<Vec<WordPressMediaItem>>()
+        .await
+        .map_err(|e| format!("Failed to parse WordPress media search JSON: {}", e))?;
+    Ok(items
+        .into_iter()
+        .find(|item| media_matches_filename(&item.source_url, filename)))
+}
+
+#[tauri::command]
+async fn find_existing_media(
+    app: tauri::AppHandle,
+    project_name: String,
+    filename: String,
+) -> Result<Option<WordPressMediaItem>, String> {
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+
+    if settings.wordpress_url.trim().is_empty()
+        || settings.wordpress_user.trim().is_empty()
+        || settings.wordpress_pass.trim().is_empty()
+    {
+        return Err(
+            "WordPress URL, User, and Application Password must be configured.".to_string(),
+        );
+    }
+
+    let existing = search_wordpress_media(&shared_client(&app), &settings, filename.trim()).await?;
+    match &existing {
+        Some(item) => logging::info(&format!(
+            "Found existing media {} for '{}': {}",
+            item.id, filename, item.source_url
+        )),
+        None => logging::info(&format!("No existing media found for '{}'.", filename)),
+    }
+    Ok(existing)
+}
+
+const MAX_UPLOAD_RETRIES: u32 = 10;
+
+fn check_upload_config(config: &UploadConfig) -> Result<(), String> {
+    if config.initial_backoff_secs == 0 {
+        return Err("Initial backoff must be at least 1 second.".to_string());
+    }
+    if !config.backoff_multiplier.is_finite() || config.backoff_multiplier < 1.0 {
+        return Err(format!(
+            "Backoff multiplier must be at least 1.0 (got {}).",
+            config.backoff_multiplier
+        ));
+    }
+    if config.max_backoff_secs < config.initial_backoff_secs {
+        return Err(format!(
+            "Maximum backoff {}s is below the initial backoff {}s.",
+            config.max_backoff_secs, config.initial_backoff_secs
+        ));
+    }
+    if config.max_retries > MAX_UPLOAD_RETRIES {
+        return Err(format!(
+            "At most {} retries are allowed (got {}).",
+            MAX_UPLOAD_RETRIES, config.max_retries
+        ));
+    }
+    Ok(())
+}
+
+// One wait per retry: initial * multiplier^n, capped at max_backoff_secs.
+fn compute_backoff_schedule(config: &UploadConfig) -> Vec<Duration> {
+    let cap = config.max_backoff_secs.max(1) as f64;
+    (0..config.max_retries)
+        .map(|retry| {
+            let secs =
+                config.initial_backoff_secs as f64 * config.backoff_multiplier.powi(retry as i32);
+            Duration::from_secs_f64(secs.max(0.0).min(cap))
+        })
+        .collect()
+}
+
+async fn process_single_image_upload(
+    client: &Client,
+    media_api_url: &str,
+    wp_user: &str,
+    wp_pass: &str,
+    image_url: &str,
+    image_bytes: Vec<u8>,
+    timeout: Duration,
+    retry: &UploadConfig,
+) -> ImageUploadResult {
+    let backoff_schedule = compute_backoff_schedule(retry);
+    let max_attempts = retry.max_retries + 1;
+
+    let filename = filename_from_image_url(image_url).unwrap_or_else(|| {
+        logging::warn(&format!(
+            "Could not extract filename from '{}', using fallback.",
+            image_url
+        ));
+        format!(
+            "upload_{}.png",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        )
+    });
+
+    let mime_type = mime_guess::from_path(&filename)
+        .first_or_octet_stream()
+        .to_string();
+
+    logging::info(&format!(
+        "Using cleaned filename '{}' and guessed MIME type '{}' for upload.",
+        filename, mime_type
+    ));
+
+    let content_disposition_value = format!("attachment; filename=\"{}\"", filename);
+
+    logging::info("Sending raw image data to WordPress...");
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        logging::info(&format!("Upload attempt {} for {}", attempts, image_url));
+
+        let current_image_bytes = image_bytes.clone();
+        let upload_response = match client
+            .post(media_api_url)
+            .basic_auth(wp_user, Some(wp_pass))
+            .timeout(timeout)
+            .header(CONTENT_TYPE, &mime_type)
+            .header(CONTENT_DISPOSITION, &content_disposition_value)
+            .body(current_image_bytes)
+            .send_recorded()
+            .await
+        {
+            Ok(resp) => resp,
+            Err(e) => {
+                let err_msg = format!(
+                    "Failed to send upload request (Attempt {}): {}",
+                    attempts, e
+                );
+                logging::error(&err_msg);
+                return ImageUploadResult::failed(image_url, err_msg);
+            }
+        };
+
+        let status = upload_response.status();
+        logging::info(&format!(
+            "Received upload response (Attempt {}) - Status: {}",
+            attempts, status
+        ));
+
+        match status {
+            StatusCode::OK | StatusCode::CREATED => {
+                match upload_response.json::<WordPressMediaResponse>().await {
+                    Ok(wp_media) => {
+                        logging::info(&format!(
+                            "Success (Attempt {}) - WP Media ID: {}, URL: {}",
+                            attempts, wp_media.id, wp_media.source_url
+                        ));
+                        return ImageUploadResult::uploaded(
+                            image_url,
+                            wp_media.id,
+                            wp_media.source_url,
+                            false,
+                        );
+                    }
+                    Err(e) => {
+                        let err_msg = format!(
+                            "Failed to parse successful WP media response (Attempt {}): {}",
+                            attempts, e
+                        );
+                        logging::error(&err_msg);
+                        return ImageUploadResult::failed(image_url, err_msg);
+                    }
+                }
+            }
+            StatusCode::TOO_MANY_REQUESTS => {
+                if attempts >= max_attempts {
+                    let err_msg = format!(
+                        "Upload failed after {} attempts due to rate limiting (429).",
+                        attempts
+                    );
+                    logging::error(&err_msg);
+                    let body_text = upload_response
+                        .text()
+                        .await
+                        .unwrap_or_else(|_| "Could not read 429 error body".to_string());
+                    logging::info(&format!("Last 429 Body: {}", body_text));
+                    return ImageUploadResult::failed(image_url, err_msg);
+                }
+
+                let wait_duration = match upload_response.headers().get(RETRY_AFTER) {
+                    Some(retry_header) => {
+                        if let Ok(seconds_str) = retry_header.to_str() {
+                            if let Ok(seconds) = seconds_str.parse::<u64>() {
+                                logging::info(&format!(
+                                    "Rate limited (429). Obeying Retry-After: {} seconds.",
+                                    seconds
+                                ));
+                                Duration::from_secs(seconds.max(1))
+                            } else {
+                                let backoff = backoff_schedule[(attempts - 1) as usize];
+                                logging::info(&format!("Rate limited (429). Couldn't parse Retry-After header '{}'. Using exponential backoff: {:?}.", seconds_str, backoff));
+                                backoff
+                            }
+                        } else {
+                            let backoff = backoff_schedule[(attempts - 1) as usize];
+                            logging::info(&format!("Rate limited (429). Invalid Retry-After header value. Using exponential backoff: {:?}.", backoff));
+                            backoff
+                        }
+                    }
+                    None => {
+                        let backoff = backoff_schedule[(attempts - 1) as usize];
+                        logging::info(&format!("Rate limited (429). No Retry-After header. Using exponential backoff: {:?}.", backoff));
+                        backoff
+                    }
+                };
+
+                logging::info(&format!("Waiting for {:?} before retry...", wait_duration));
+                sleep(wait_duration).await;
+            }
+            _ => {
+                let error_text = upload_response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Could not read error body".to_string());
+                let err_msg = format!(
+                    "WordPress media upload failed (Attempt {}) with status {}: {}",
+                    attempts, status, error_text
+                );
+                logging::error(&err_msg);
+                return ImageUploadResult::failed(image_url, err_msg);
+            }
+        }
+    }
+}
+
+#[tauri::command]
+async fn get_article_with_image_placeholders_llm(
+    app: tauri::AppHandle,
+    request: InsertPlaceholdersLLMRequest,
+) -> Result<InsertPlaceholdersLLMResponse, String> {
+    logging::info(&format!(
+        "Received request to get article with {} image placeholders via LLM.",
+        request.images.len()
+    ));
+
+    if request.images.is_empty() {
+        logging::info("No images provided, returning original HTML.");
+        return Ok(InsertPlaceholdersLLMResponse {
+            article_with_placeholders: request.article_html,
+        });
+    }
+
+    let incremental = request.incremental.unwrap_or(false);
+    let placeholder_regex =
+        Regex::new(r"\[INSERT_IMAGE_HERE_\d+\]").expect("Invalid placeholder regex");
+    let img_tag_regex = Regex::new(r"(?is)<img\b[^>]*>").expect("Invalid img regex");
+    let existing_placeholders: Vec<String> = placeholder_regex
+        .find_iter(&request.article_html)
+        .map(|m| m.as_str().to_string())
+        .collect();
+    let existing_img_tags: Vec<String> = img_tag_regex
+        .find_iter(&request.article_html)
+        .map(|m| m.as_str().to_string())
+        .collect();
+
+    let images_to_insert: Vec<&ImageDetailsForLLM> = if incremental {
+        request
+            .images
+            .iter()
+            .filter(|img| {
+                let placeholder = format!("[INSERT_IMAGE_HERE_{}]", img.placeholder_index);
+                !existing_placeholders.contains(&placeholder)
+                    && !existing_img_tags
+                        .iter()
+                        .any(|tag| tag.contains(&img.wordpress_media_url))
+            })
+            .collect()
+    } else {
+        request.images.iter().collect()
+    };
+
+    if incremental {
+        logging::info(&format!("Incremental mode - {} existing placeholder(s), {} existing <img> tag(s), {} new image(s) to insert.", existing_placeholders.len(),
+            existing_img_tags.len(),
+            images_to_insert.len()));
+        if images_to_insert.is_empty() {
+            logging::info("No new images to insert, returning original HTML.");
+            return Ok(InsertPlaceholdersLLMResponse {
+                article_with_placeholders: request.article_html,
+            });
+        }
+    }
+
+    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+        .await?
+        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+
+    let api_url = project_chat_completions_url(&app, request.project_name.as_deref()).await?;
+    let model = project_text_model(&app, request.project_name.as_deref()).await?;
+    let timeout = request_timeout(request.timeout_secs);
+    if request.article_html.chars().count() <= PLACEHOLDER_CHUNK_MAX_CHARS {
+        let article_with_placeholders = insert_placeholders_with_llm(
+            &shared_client(&app),
+            &api_url,
+            &api_key,
+            &model,
+            &request.article_html,
+            &images_to_insert,
+            incremental,
+            None,
+            timeout,
+        )
+        .await?;
+        if incremental {
+            let lost: Vec<&String> = existing_placeholders
+                .iter()
+                .chain(existing_img_tags.iter())
+                .filter(|existing| !article_with_placeholders.contains(existing.as_str()))
+                .collect();
+            if !lost.is_empty() {
+                logging::warn(&format!(
+                    "LLM altered existing images/placeholders: {:?}",
+                    lost
+                ));
+                return Err(format!(
+                    "The LLM modified or removed {} existing image(s)/placeholder(s); the article was left unchanged.",
+                    lost.len()
+                ));
+            }
+        }
+        return Ok(InsertPlaceholdersLLMResponse {
+            article_with_placeholders,
+        });
+    }
+
+    // Long articles are split at top-level block boundaries; each chunk only
+    // receives the images assigned to it, so every placeholder lands exactly once.
+    let body_regex = Regex::new(r"(?is)<body\b[^>]*>(.*)</body\s*>").expect("Invalid body regex");
+    let (prefix, body, suffix) = match body_regex
+        .captures(&request.article_html)
+        .and_then(|caps| caps.get(1))
+    {
+        Some(body) => (
+            &request.article_html[..body.start()],
+            body.as_str(),
+            &request.article_html[body.end()..],
+        ),
+        None => ("", request.article_html.as_str(), ""),
+    };
+    let chunks = split_html_into_chunks(body, PLACEHOLDER_CHUNK_MAX_CHARS);
+    let assignments = assign_images_to_chunks(&chunks, &images_to_insert);
+    logging::info(&format!(
+        "Article is long ({} chars), inserting placeholders in {} chunk(s).",
+        request.article_html.len(),
+        chunks.len()
+    ));
+
+    let mut assembled = String::from(prefix);
+    for (chunk_index, chunk) in chunks.iter().enumerate() {
+        let chunk_images: Vec<&ImageDetailsForLLM> = images_to_insert
+            .iter()
+            .zip(&assignments)
+            .filter(|(_, assigned)| **assigned == chunk_index)
+            .map(|(img, _)| *img)
+            .collect();
+        if chunk_images.is_empty() {
+            assembled.push_str(chunk);
+            continue;
+        }
+        logging::info(&format!(
+            "Chunk {}/{} ({} chars) gets placeholders {:?}.",
+            chunk_index + 1,
+            chunks.len(),
+            chunk.len(),
+            chunk_images
+                .iter()
+                .map(|img| img.placeholder_index)
+                .collect::<Vec<_>>()
+        ));
+        let fragment_note = format!(
+            "This HTML is part {} of {} of a longer article. Return ONLY the modified fragment, without adding <html>, <head> or <body> tags.",
+            chunk_index + 1,
+            chunks.len()
+        );
+        let result = insert_placeholders_with_llm(
+            &shared_client(&app),
+            &api_url,
+            &api_key,
+            &model,
+            chunk,
+            &chunk_images,
+            incremental,
+            Some(&fragment_note),
+            timeout,
+        )
+        .await?;
+        let result = if Regex::new(r"(?is)<body\b")
+            .expect("Invalid body regex")
+            .is_match(&result)
+        {
+            extract_body_content(&result)
+        } else {
+            result
+        };
+
+        let lost: Vec<&str> = placeholder_regex
+            .find_iter(chunk)
+            .map(|m| m.as_str())
+            .chain(img_tag_regex.find_iter(chunk).map(|m| m.as_str()))
+            .filter(|existing| !result.contains(existing))
+            .collect();
+        if !lost.is_empty() {
+            logging::warn(&format!(
+                "LLM altered existing images/placeholders in chunk {}: {:?}",
+                chunk_index + 1,
+                lost
+            ));
+            return Err(format!(
+                "The LLM modified or removed {} existing image(s)/placeholder(s) in part {}; the article was left unchanged.",
+                lost.len(),
+                chunk_index + 1
+            ));
+        }
+
+        assembled.push_str(&enforce_chunk_placeholders(&result, &chunk_images));
+        assembled.push('\n');
+    }
+    assembled.push_str(suffix);
+
+    Ok(InsertPlaceholdersLLMResponse {
+        article_with_placeholders: assembled,
+    })
+}
+
+#[tauri::command]
+async fn replace_image_placeholders(
+    article_html: String,
+    images: Vec<ImageDetailsForLLM>,
+) -> Result<ReplacePlaceholdersResponse, String> {
+    logging::info(&format!(
+        "Replacing {} image placeholder(s) with <img> tags.",
+        images.len()
+    ));
+    let mut output = article_html;
+    let mut missing_placeholders = Vec::new();
+    for img in &images {
+        let placeholder = format!("[INSERT_IMAGE_HERE_{}]", img.placeholder_index);
+        if !output.contains(&placeholder) {
+            logging::info(&format!(
+                "Placeholder {} not found in the article.",
+                placeholder
+            ));
+            missing_placeholders.push(placeholder);
+            continue;
+        }
+        let img_tag = format!(
+            "<img src=\"{}\" alt=\"{}\" class=\"wp-image-{}\" />",
+            escape_html_attribute(&img.wordpress_media_url),
+            escape_html_attribute(&img.alt_text),
+            img.wordpress_media_id
+        );
+        output = output.replace(&placeholder, &img_tag);
+    }
+
+    let leftover: Vec<String> = Regex::new(r"\[INSERT_IMAGE_HERE_\d+\]")
+        .expect("Invalid placeholder regex")
+        .find_iter(&output)
+        .map(|m| m.as_str().to_string())
+        .collect();
+    if !leftover.is_empty() {
+        logging::warn(&format!(
+            "{} placeholder(s) have no matching image: {:?}",
+            leftover.len(),
+            leftover
+        ));
+    }
+
+    let error = if missing_placeholders.is_empty() {
+        None
+    } else {
+        Some(format!(
+            "Placeholder(s) not found in the article: {}",
+            missing_placeholders.join(", ")
+        ))
+    };
+    Ok(ReplacePlaceholdersResponse {
+        article_html: output,
+        missing_placeholders,
+        error,
+    })
+}
+
+// Keeps exactly one occurrence of each assigned placeholder: duplicates are
+// dropped and missing ones are appended at the end of the chunk.
+fn enforce_chunk_placeholders(html: &str, images: &[&ImageDetailsForLLM]) -> String {
+    let mut output = html.to_string();
+    for img in images {
+        let placeholder = format!("[INSERT_IMAGE_HERE_{}]", img.placeholder_index);
+        match output.find(&placeholder) {
+            Some(first) => {
+                let keep_until = first + placeholder.len();
+                let rest = output[keep_until..].replace(&placeholder, "");
+                output.truncate(keep_until);
+                output.push_str(&rest);
+            }
+            None => {
+                logging::info(&format!(
+                    "Placeholder {} missing from its chunk, appending it.",
+                    placeholder
+                ));
+                output.push_str(&format!("\n<p>{}</p>", placeholder));
+            }
+        }
+    }
+    output
+}
+
+const HTML_BLOCK_CONTAINERS: &[&str] = &[
+    "div",
+    "section",
+    "article",
+    "table",
+    "ul",
+    "ol",
+    "blockquote",
+    "figure",
+    "pre",
+    "details",
+];
+
+fn split_html_into_chunks(html: &str, max_chars: usize) -> Vec<String> {
+    let tag_regex = Regex::new(r"(?is)<(/?)([a-z][a-z0-9]*)\b[^>]*>").expect("Invalid tag regex");
+    let mut boundaries: Vec<usize> = Vec::new();
+    let mut depth: usize = 0;
+    for caps in tag_regex.captures_iter(html) {
+        let whole = caps.get(0).expect("Capture group 0 always exists");
+        let is_closing = !caps[1].is_empty();
+        let name = caps[2].to_ascii_lowercase();
+        let is_container = HTML_BLOCK_CONTAINERS.contains(&name.as_str());
+        if is_container && !is_closing {
+            depth += 1;
+        } else if is_container && is_closing {
+            depth = depth.saturating_sub(1);
+        }
+        let is_block_end = is_closing
+            && (is_container
+                || matches!(name.as_str(), "p" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"));
+        if is_block_end && depth == 0 {
+            boundaries.push(whole.end());
+        }
+    }
+
+    let mut chunks: Vec<String> = Vec::new();
+    let mut chunk_start = 0;
+    let mut last_boundary = 0;
+    for boundary in boundaries {
+        if html[chunk_start..boundary].chars().count() > max_chars && last_boundary > chunk_start {
+            chunks.push(html[chunk_start..last_boundary].to_string());
+            chunk_start = last_boundary;
+        }
+        last_boundary = boundary;
+    }
+    if chunk_start < html.len() {
+        chunks.push(html[chunk_start..].to_string());
+    }
+    chunks.retain(|chunk| !chunk.trim().is_empty());
+    chunks
+}
+
+// Each image goes to the chunk sharing the most words with its alt text; ties
+// go to the chunk with the fewest images so far.
+fn assign_images_to_chunks(chunks: &[String], images: &[&ImageDetailsForLLM]) -> Vec<usize> {
+    let chunk_words: Vec<HashSet<String>> = chunks
+        .iter()
+        .map(|chunk| {
+            strip_html_tags(chunk)
+                .to_lowercase()
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| word.chars().count() > 3)
+                .map(String::from)
+                .collect()
+        })
+        .collect();
+    let mut counts = vec![0usize; chunks.len()];
+    images
+        .iter()
+        .map(|img| {
+            let alt_words: Vec<String> = img
+                .alt_text
+                .to_lowercase()
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| word.chars().count() > 3)
+                .map(String::from)
+                .collect();
+            let best = (0..chunks.len())
+                .max_by_key(|&index| {
+                    let overlap = alt_words
+                        .iter()
+                        .filter(|word| chunk_words[index].contains(*word))
+                        .count();
+                    (
+                        overlap,
+                        std::cmp::Reverse(counts[index]),
+                        std::cmp::Reverse(index),
+                    )
+                })
+                .unwrap_or(0);
+            if let Some(count) = counts.get_mut(best) {
+                *count += 1;
+            }
+            best
+        })
+        .collect()
+}
+
+async fn insert_placeholders_with_llm(
+    client: &Client,
+    api_url: &str,
+    api_key: &str,
+    model: &str,
+    article_html: &str,
+    images: &[&ImageDetailsForLLM],
+    incremental: bool,
+    fragment_note: Option<&str>,
+    timeout: Duration,
+) -> Result<String, String> {
+    let image_list_string = images
+        .iter()
+        .map(|img| {
+            format!(
+                "Image Placeholder: [INSERT_IMAGE_HERE_{}]\n   Context/Alt Text: {}\n   (URL: {}, WP ID: {})",
+                img.placeholder_index,
+                img.alt_text,
+                img.wordpress_media_url,
+                img.wordpress_media_id
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let system_prompt = "You are an AI assistant that modifies HTML articles. Your task is to insert unique image placeholders (like [INSERT_IMAGE_HERE_1], [INSERT_IMAGE_HERE_2], etc.) into the provided HTML article at semantically relevant locations based on the image's context/alt text and the surrounding article content. Ensure the final output is ONLY the complete, valid HTML code for the modified article including the placeholders, starting with <!DOCTYPE html> or <html> and ending with </html>. Do not include any explanations or preamble.";
+
+    let mut user_prompt = format!(
+        r#"Please modify the following HTML article by inserting the unique placeholders provided for each image.
+
+Placeholders and Context:
+---
+{}
+---
+
+HTML Article to Modify:
+---
+{}
+---
+
+Instructions:
+1. Analyze the article content and the context/alt text for each image placeholder.
+2. For each image placeholder (e.g., `[INSERT_IMAGE_HERE_1]`), insert it exactly as provided into the most semantically relevant location within the article body.
+3. Place placeholders where they enhance the content, ideally near paragraphs discussing related topics. Avoid breaking HTML structure. Do not place placeholders inside header tags (h1, h2, etc.) or within other HTML tags. Place them between paragraphs or block elements where an image would naturally fit.
+4. Ensure ALL provided placeholders are inserted exactly once.
+5. Return ONLY the complete, modified HTML article content including the inserted placeholders. Do not add any introductory text, explanations, or code fences.
+
+Modified HTML Article with Placeholders:"#,
+        image_list_string, article_html
+    );
+    if let Some(note) = fragment_note {
+        user_prompt.push_str("\n\n");
+        user_prompt.push_str(note);
+    }
+    if incremental {
+        user_prompt.push_str(
+            "\n\nIMPORTANT: The article already contains images and/or placeholders. Keep every existing [INSERT_IMAGE_HERE_*] placeholder and every existing <img> tag exactly where it is and unchanged. Only insert the new placeholders listed above.",
+        );
+    }
+
+    logging::info("Sending request to LLM for image placeholder insertion.");
+
+    let request_body = serde_json::json!({
+        "model": model,
+        "messages": [
+            { "role": "system", "content": system_prompt },
+            { "role": "user", "content": user_prompt }
+        ],
+        "temperature": 0.5
+    });
+
+    let response = client
+        .post(api_url)
+        .bearer_auth(api_key)
+        .timeout(timeout)
+        .json(&request_body)
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to send request to OpenAI: {}", e))?;
+
+    let status = response.status();
+    record_rate_limit_headers(response.headers());
+    let response_body_text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read OpenAI response body: {}", e))?;
+
+    logging::info(&format!(
+        "Received LLM placeholder insertion response (Status: {})",
+        status
+    ));
+
+    if status.is_success() {
+        match serde_json::from_str::<OpenAiApiResponse>(&response_body_text) {
+            Ok(parsed_response) => {
+                if let Some(choice) = parsed_response.choices.get(0) {
+                    logging::info("Successfully extracted HTML with placeholders from LLM.");
+                    Ok(choice.message.content.trim().to_string())
+                } else {
+                    Err("OpenAI response successful but 'choices' array was empty.".to_string())
+                }
+            }
+            Err(e) => {
+                logging::error(&format!(
+                    "Error parsing LLM response JSON: {}. Using raw response body as fallback.",
+                    e
+                ));
+                Ok(response_body_text.trim().to_string())
+            }
+        }
+    } else {
+        logging::warn(&format!(
+            "LLM placeholder insertion request failed - Status: {}, Body:\n{}",
+            status, response_body_text
+        ));
+        Err(format!(
+            "OpenAI API request failed with status {}: {}",
+            status, response_body_text
+        ))
+    }
+}
+
+const HTML_VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+fn extract_image_urls(html: &str) -> Vec<String> {
+    let src_regex = Regex::new(r#"(?is)<img\b[^>]*?\ssrc\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#)
+        .expect("Invalid img src regex");
+    let mut urls: Vec<String> = Vec::new();
+    for caps in src_regex.captures_iter(html) {
+        let Some(src) = caps.get(1).or_else(|| caps.get(2)).or_else(|| caps.get(3)) else {
+            continue;
+        };
+        let src = src.as_str().trim().replace("&amp;", "&");
+        if !src.is_empty() && !urls.contains(&src) {
+            urls.push(src);
+        }
+    }
+    urls
+}
+
+// extract_image_urls unescapes "&amp;", so the URL may appear in either form.
+fn replace_image_url(html: &str, image_url: &str, replacement: &str) -> String {
+    let escaped_url = image_url.replace('&', "&amp;");
+    html.replace(image_url, replacement)
+        .replace(&escaped_url, replacement)
+}
+
+#[tauri::command]
+async fn extract_article_images(article_html: String) -> Result<Vec<String>, String> {
+    let urls = extract_image_urls(&article_html);
+    logging::info(&format!(
+        "Found {} distinct image(s) in article.",
+        urls.len()
+    ));
+    Ok(urls)
+}
+
+#[tauri::command]
+async fn validate_html(article_html: String) -> HtmlValidation {
+    logging::info(&format!(
+        "Validating HTML structure (length {}).",
+        article_html.len()
+    ));
+    let validation = check_html(&article_html);
+    logging::info(&format!(
+        "HTML validation finished. Root elements: {}, issues: {}",
+        validation.root_element_count,
+        validation.issues.len()
+    ));
+    validation
+}
+
+fn check_html(article_html: &str) -> HtmlValidation {
+    let mut issues: Vec<HtmlValidationIssue> = Vec::new();
+
+    // Comments, doctype and raw text blocks are skipped so their content isn't scanned as tags.
+    let skip_regex = Regex::new(
+        r"(?is)<!--.*?-->|<!doctype[^>]*>|<(script|style)\b[^>]*>.*?</(script|style)\s*>",
+    )
+    .expect("Invalid skip regex");
+    let mut scannable = article_html.to_string();
+    for m in skip_regex.find_iter(article_html) {
+        let blank = " ".repeat(m.end() - m.start());
+        scannable.replace_range(m.start()..m.end(), &blank);
+    }
+
+    let stray_lt_regex = Regex::new(r"<([^a-zA-Z/!]|$)").expect("Invalid stray '<' regex");
+    for m in stray_lt_regex.find_iter(&scannable) {
+        issues.push(HtmlValidationIssue {
+            kind: "stray_lt".to_string(),
+            message: "Found a '<' that does not start a valid tag.".to_string(),
+            tag: None,
+            position: Some(m.start()),
+        });
+    }
+
+    let tag_regex =
+        Regex::new(r"<(/?)([a-zA-Z][a-zA-Z0-9-]*)\b[^<>]*?(/?)>").expect("Invalid tag regex");
+    let mut open_stack: Vec<(String, usize)> = Vec::new();
+    let mut root_element_count = 0;
+
+    for caps in tag_regex.captures_iter(&scannable) {
+        let position = caps.get(0).map(|m| m.start()).unwrap_or(0);
+        let is_closing = !caps[1].is_empty();
+        let is_self_closing = !caps[3].is_empty();
+        let tag_name = caps[2].to_ascii_lowercase();
+
+        if is_closing {
+            if HTML_VOID_ELEMENTS.contains(&tag_name.as_str()) {
+                issues.push(HtmlValidationIssue {
+                    kind: "void_closing_tag".to_string(),
+                    message: format!("Void element <{}> must not have a closing tag.", tag_name),
+                    tag: Some(tag_name),
+                    position: Some(position),
+                });
+                continue;
+            }
+            match open_stack.iter().rposition(|(name, _)| *name == tag_name) {
+                Some(index) => {
+                    for (unclosed_name, unclosed_pos) in open_stack.drain(index + 1..).rev() {
+                        issues.push(HtmlValidationIssue {
+                            kind: "mismatched_nesting".to_string(),
+                            message: format!(
+                                "<{}> was closed by </{}> before being closed itself.",
+                                unclosed_name, tag_name
+                            ),
+                            tag: Some(unclosed_name),
+                            position: Some(unclosed_pos),
+                        });
+                    }
+                    open_stack.pop();
+                }
+                None => {
+                    issues.push(HtmlValidationIssue {
+                        kind: "unexpected_closing_tag".to_string(),
+                        message: format!(
+                            "Closing tag </{}> has no matching opening tag.",
+                            tag_name
+                        ),
+                        tag: Some(tag_name),
+                        position: Some(position),
+                    });
+                }
+            }
+            continue;
+        }
+
+        if open_stack.is_empty() {
+            root_element_count += 1;
+        }
+        if !is_self_closing && !HTML_VOID_ELEMENTS.contains(&tag_name.as_str()) {
+            open_stack.push((tag_name, position));
+        }
+    }
+
+    for (unclosed_name, unclosed_pos) in open_stack {
+        issues.push(HtmlValidationIssue {
+            kind: "unclosed_tag".to_string(),
+            message: format!("<{}> is never closed.", unclosed_name),
+            tag: Some(unclosed_name),
+            position: Some(unclosed_pos),
+        });
+    }
+
+    issues.sort_by_key(|issue| issue.position.unwrap_or(usize::MAX));
+
+    HtmlValidation {
+        is_valid: issues.is_empty(),
+        has_single_root: root_element_count == 1,
+        root_element_count,
+        issues,
+    }
+}
+
+#[tauri::command]
+async fn export_article_bundle(
+    app: tauri::AppHandle,
+    project_name: String,
+    article_html: String,
+    image_urls: Vec<String>,
+) -> Result<String, String> {
+    logging::info(&format!(
+        "Exporting article bundle for project '{}' with {} images.",
+        project_name,
+        image_urls.len()
+    ));
+
+    let exports_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("exports");
+    std::fs::create_dir_all(&exports_dir)
+        .map_err(|e| format!("Failed to create exports directory: {}", e))?;
+
+    let archive_path = exports_dir.join(format!(
+        "{}_{}.zip",
+        sanitize_file_name(&project_name),
+        unix_timestamp_secs()
+    ));
+
+    let client = shared_client(&app);
+    let mut bundled_html = article_html;
+    let mut bundled_images: Vec<(String, Vec<u8>)> = Vec::new();
+
+    for (index, image_url) in image_urls.iter().enumerate() {
+        let image_bytes =
+            match download_image_bytes(&client, image_url, request_timeout(None)).await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    logging::warn(&format!("{}. Keeping remote URL.", e));
+                    continue;
+                }
+            };
+
+        let url_path = image_url.split(['?', '#']).next().unwrap_or(image_url);
+        let extension = Path::new(url_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .filter(|ext| !ext.is_empty())
+            .unwrap_or("png");
+        let local_path = format!("images/image_{}.{}", index + 1, extension);
+
+        logging::info(&format!(
+            "Bundling {} ({} bytes) as {}",
+            image_url,
+            image_bytes.len(),
+            local_path
+        ));
+        bundled_html = replace_image_url(&bundled_html, image_url, &local_path);
+        bundled_images.push((local_path, image_bytes));
+    }
+
+    let archive_file = std::fs::File::create(&archive_path)
+        .map_err(|e| format!("Failed to create archive file: {}", e))?;
+    let mut zip_writer = zip::ZipWriter::new(archive_file);
+    let options = zip::write::SimpleFileOptions::default();
+
+    zip_writer
+        .start_file("article.html", options)
+        .map_err(|e| format!("Failed to add article.html to archive: {}", e))?;
+    zip_writer
+        .write_all(bundled_html.as_bytes())
+        .map_err(|e| format!("Failed to write article.html to archive: {}", e))?;
+
+    zip_writer
+        .add_directory("images/", options)
+        .map_err(|e| format!("Failed to add images directory to archive: {}", e))?;
+    for (local_path, bytes) in &bundled_images {
+        zip_writer
+            .start_file(local_path.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to archive: {}", local_path, e))?;
+        zip_writer
+            .write_all(bytes)
+            .map_err(|e| format!("Failed to write {} to archive: {}", local_path, e))?;
+    }
+
+    zip_writer
+        .finish()
+        .map_err(|e| format!("Failed to finalize archive: {}", e))?;
+
+    logging::info(&format!(
+        "Article bundle written to {:?} ({} of {} images bundled).",
+        archive_path,
+        bundled_images.len(),
+        image_urls.len()
+    ));
+    Ok(archive_path.to_string_lossy().to_string())
+}
+
+const SOCIAL_TITLE_MAX_CHARS: usize = 60;
+const SOCIAL_DESCRIPTION_MAX_CHARS: usize = 160;
+
+fn strip_html_tags(html: &str) -> String {
+    let tag_regex = Regex::new(r"(?s)<[^>]*>").expect("Invalid tag regex");
+    let whitespace_regex = Regex::new(r"\s+").expect("Invalid whitespace regex");
+    let text = tag_regex.replace_all(html, " ");
+    whitespace_regex.replace_all(&text, " ").trim().to_string()
+}
+
+fn truncate_at_word_boundary(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    let trimmed = match cut.rfind(' ') {
+        Some(pos) if pos > 0 => &cut[..pos],
+        _ => cut.as_str(),
+    };
+    format!(
+        "{}…",
+        trimmed.trim_end_matches(|c: char| c.is_whitespace() || c == ',' || c == ';' || c == ':')
+    )
+}
+
+fn escape_html_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn extract_seo_metadata(article_html: &str) -> Result<SeoMetadata, String> {
+    let first_text_of = |pattern: &str| -> Option<String> {
+        Regex::new(pattern)
+            .expect("Invalid SEO metadata regex")
+            .captures(article_html)
+            .and_then(|caps| caps.get(1))
+            .map(|m| strip_html_tags(m.as_str()))
+            .filter(|text| !text.is_empty())
+    };
+
+    let title = first_text_of(r"(?is)<title>(.*?)</title>")
+        .or_else(|| first_text_of(r"(?is)<h1(?:[^>]*)>(.*?)</h1>"))
+        .or_else(|| first_text_of(r"(?is)<h2(?:[^>]*)>(.*?)</h2>"))
+        .ok_or_else(|| "Could not find a title, H1 or H2 in the article.".to_string())?;
+
+    let description =
+        Regex::new(r#"(?is)<meta\s+name=["']description["']\s+content=["'](.*?)["']"#)
+            .expect("Invalid meta description regex")
+            .captures(article_html)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|text| !text.is_empty())
+            .or_else(|| first_text_of(r"(?is)<p(?:[^>]*)>(.*?)</p>"))
+            .unwrap_or_else(|| strip_html_tags(article_html));
+
+    Ok(SeoMetadata { title, description })
+}
+
+// Lowercases and strips common Latin accents so "Fonctionnalités" matches "fonctionnalites".
+fn fold_for_matching(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'à' | 'á' | 'â' | 'ä' | 'ã' => 'a',
+            'ç' => 'c',
+            'è' | 'é' | 'ê' | 'ë' => 'e',
+            'ì' | 'í' | 'î' | 'ï' => 'i',
+            'ñ' => 'n',
+            'ò' | 'ó' | 'ô' | 'ö' | 'õ' => 'o',
+            'ù' | 'ú' | 'û' | 'ü' => 'u',
+            'ÿ' => 'y',
+            other => other,
+        })
+        .collect()
+}
+
+// Keywords the article prompt asks for in the title and headings.
+const DEFAULT_HEADING_KEYWORDS: &[&str] = &["avis", "fonctionnalités", "tarifs"];
+
+fn check_heading_keywords(html: &str, required: &[&str]) -> Vec<HeadingKeywordResult> {
+    let headings: Vec<String> = Regex::new(r"(?is)<(title|h1|h2)\b[^>]*>(.*?)</(title|h1|h2)\s*>")
+        .expect("Invalid heading regex")
+        .captures_iter(html)
+        .map(|caps| strip_html_tags(&caps[2]).trim().to_string())
+        .filter(|heading| !heading.is_empty())
+        .collect();
+    let folded: Vec<String> = headings.iter().map(|h| fold_for_matching(h)).collect();
+
+    required
+        .iter()
+        .map(|keyword| keyword.trim())
+        .filter(|keyword| !keyword.is_empty())
+        .map(|keyword| {
+            let folded_keyword = fold_for_matching(keyword);
+            let matching: Vec<String> = headings
+                .iter()
+                .zip(&folded)
+                .filter(|(_, folded_heading)| folded_heading.contains(&folded_keyword))
+                .map(|(heading, _)| heading.clone())
+                .collect();
+            HeadingKeywordResult {
+                keyword: keyword.to_string(),
+                found: !matching.is_empty(),
+                headings: matching,
+            }
+        })
+        .collect()
+}
+
+// The keyword should start within the first third of the title.
+const KEYWORD_FRONT_LOADED_RATIO: f64 = 1.0 / 3.0;
+
+fn check_keyword_title_alignment(title: &str, primary_keyword: &str) -> KeywordTitleCheck {
+    let title = title.trim().to_string();
+    let title_length = title.chars().count();
+    let folded_title = fold_for_matching(&title);
+    let folded_keyword = fold_for_matching(primary_keyword.trim());
+
+    let keyword_position = if folded_keyword.is_empty() {
+        None
+    } else {
+        folded_title
+            .find(&folded_keyword)
+            .map(|byte_index| folded_title[..byte_index].chars().count())
+    };
+    let front_loaded = keyword_position.is_some_and(|position| {
+        (position as f64) <= (title_length as f64) * KEYWORD_FRONT_LOADED_RATIO
+    });
+
+    let mut suggestions = Vec::new();
+    match keyword_position {
+        None => suggestions.push(format!(
+            "Add the primary keyword \"{}\" to the title, ideally at the start.",
+            primary_keyword.trim()
+        )),
+        Some(_) if !front_loaded => suggestions.push(format!(
+            "Move \"{}\" closer to the start of the title; front-loaded keywords rank and scan better.",
+            primary_keyword.trim()
+        )),
+        Some(_) => {}
+    }
+    if title_length > TITLE_MAX_CHARS {
+        suggestions.push(format!(
+            "Shorten the title to {} characters or fewer ({} now) so it isn't truncated in search results.",
+            TITLE_MAX_CHARS, title_length
+        ));
+    } else if title_length < TITLE_MIN_CHARS {
+        suggestions.push(format!(
+            "Lengthen the title to at least {} characters ({} now) to use the available space.",
+            TITLE_MIN_CHARS, title_length
+        ));
+    }
+
+    KeywordTitleCheck {
+        title,
+        title_length,
+        contains_keyword: keyword_position.is_some(),
+        keyword_position,
+        front_loaded,
+        suggestions,
+    }
+}
+
+fn format_iso8601_utc(timestamp_secs: u64) -> String {
+    chrono::Utc
+        .timestamp_opt(timestamp_secs as i64, 0)
+        .single()
+        .map(|time| time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        .unwrap_or_default()
+}
+
+fn build_article_jsonld(
+    meta: &SeoMetadata,
+    author: &str,
+    published: &str,
+    image: Option<&str>,
+) -> String {
+    let mut schema = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "BlogPosting",
+        "headline": truncate_at_word_boundary(&meta.title, 110),
+        "description": truncate_at_word_boundary(&meta.description, SOCIAL_DESCRIPTION_MAX_CHARS),
+        "author": {
+            "@type": "Person",
+            "name": author,
+        },
+        "datePublished": published,
+        "dateModified": published,
+    });
+    if let Some(image_url) = image.filter(|url| !url.trim().is_empty()) {
+        schema["image"] = JsonValue::String(image_url.trim().to_string());
+    }
+    // "</" inside the JSON would close the script element early.
+    let json = serde_json::to_string_pretty(&schema)
+        .unwrap_or_default()
+        .replace("</", "<\\/");
+    format!("<script type=\"application/ld+json\">\n{}\n</script>", json)
+}
+
+#[tauri::command]
+async fn generate_social_meta(
+    article_html: String,
+    featured_image_url: Option<String>,
+) -> Result<SocialMeta, String> {
+    logging::info(&format!(
+        "Generating social meta (article length {}).",
+        article_html.len()
+    ));
+
+    let seo = extract_seo_metadata(&article_html)?;
+
+    let og_title = truncate_at_word_boundary(&seo.title, SOCIAL_TITLE_MAX_CHARS);
+    let og_description = truncate_at_word_boundary(&seo.description, SOCIAL_DESCRIPTION_MAX_CHARS);
+    let og_image = featured_image_url.filter(|url| !url.trim().is_empty());
+    let twitter_card = if og_image.is_some() {
+        "summary_large_image"
+    } else {
+        "summary"
+    }
+    .to_string();
+
+    let mut meta_tags = vec![
+        format!(
+            r#"<meta property="og:title" content="{}" />"#,
+            escape_html_attribute(&og_title)
+        ),
+        format!(
+            r#"<meta property="og:description" content="{}" />"#,
+            escape_html_attribute(&og_description)
+        ),
+        r#"<meta property="og:type" content="article" />"#.to_string(),
+        format!(r#"<meta name="twitter:card" content="{}" />"#, twitter_card),
+        format!(
+            r#"<meta name="twitter:title" content="{}" />"#,
+            escape_html_attribute(&og_title)
+        ),
+        format!(
+            r#"<meta name="twitter:description" content="{}" />"#,
+            escape_html_attribute(&og_description)
+        ),
+    ];
+    if let Some(image_url) = &og_image {
+        meta_tags.push(format!(
+            r#"<meta property="og:image" content="{}" />"#,
+            escape_html_attribute(image_url)
+        ));
+        meta_tags.push(format!(
+            r#"<meta name="twitter:image" content="{}" />"#,
+            escape_html_attribute(image_url)
+        ));
+    }
+
+    logging::info(&format!(
+        "Social meta generated. Title: '{}', description length: {}",
+        og_title,
+        og_description.chars().count()
+    ));
+
+    Ok(SocialMeta {
+        og_title,
+        og_description,
+        og_image,
+        twitter_card,
+        meta_tags_html: meta_tags.join("\n"),
+    })
+}
+
+fn get_pricing_overrides_from_store(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+) -> Result<pricing::PricingTable, String> {
+    match store.get(STORE_KEY_PRICING) {
+        Some(JsonValue::Null) | None => Ok(pricing::PricingTable::new()),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| format!("Failed to deserialize pricing table: {}", e)),
+    }
+}
+
+async fn load_pricing_table(app: &tauri::AppHandle) -> Result<pricing::PricingTable, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let overrides = get_pricing_overrides_from_store(&store)?;
+    Ok(pricing::merge_pricing_tables(
+        &pricing::default_pricing_table(),
+        &overrides,
+    ))
+}
+
+#[tauri::command]
+async fn get_pricing_table(app: tauri::AppHandle) -> Result<pricing::PricingTable, String> {
+    load_pricing_table(&app).await
+}
+
+#[tauri::command]
+async fn update_pricing_table(
+    app: tauri::AppHandle,
+    json: String,
+) -> Result<pricing::PricingTable, String> {
+    let updates: pricing::PricingTable = serde_json::from_str(&json).map_err(|e| {
+        format!(
+            "Invalid pricing table JSON (expected {{\"model\": {{\"input_per_million\": .., \"output_per_million\": ..}}}}): {}",
+            e
+        )
+    })?;
+    if let Some((model, _)) = updates.iter().find(|(_, p)| {
+        !p.input_per_million.is_finite()
+            || !p.output_per_million.is_finite()
+            || p.input_per_million < 0.0
+            || p.output_per_million < 0.0
+    }) {
+        return Err(format!(
+            "Invalid (negative or non-finite) price for model '{}'.",
+            model
+        ));
+    }
+
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let overrides =
+        pricing::merge_pricing_tables(&get_pricing_overrides_from_store(&store)?, &updates);
+    logging::info(&format!(
+        "Updating pricing table with {} model(s); {} override(s) stored.",
+        updates.len(),
+        overrides.len()
+    ));
+
+    store.set(
+        STORE_KEY_PRICING.to_string(),
+        serde_json::to_value(&overrides)
+            .map_err(|e| format!("Failed to serialize pricing table: {}", e))?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+
+    Ok(pricing::merge_pricing_tables(
+        &pricing::default_pricing_table(),
+        &overrides,
+    ))
+}
+
+#[tauri::command]
+async fn estimate_generation_cost(
+    app: tauri::AppHandle,
+    request: CostEstimateRequest,
+) -> Result<CostEstimate, String> {
+    let table = load_pricing_table(&app).await?;
+    let estimated_cost_usd = pricing::estimate_cost(
+        &table,
+        &request.model,
+        request.input_tokens,
+        request.output_tokens,
+    );
+    if estimated_cost_usd.is_none() {
+        logging::info(&format!(
+            "No pricing known for model '{}', cost left empty.",
+            request.model
+        ));
+    }
+
+    Ok(CostEstimate {
+        pricing: pricing::lookup_pricing(&table, &request.model).copied(),
+        model: request.model,
+        input_tokens: request.input_tokens,
+        output_tokens: request.output_tokens,
+        estimated_cost_usd,
+    })
+}
+
+async fn load_image_pricing_table(
+    app: &tauri::AppHandle,
+) -> Result<pricing::ImagePricingTable, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    image_pricing_table_from_store(&store)
+}
+
+fn image_pricing_table_from_store(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+) -> Result<pricing::ImagePricingTable, String> {
+    let overrides: pricing::ImagePricingTable = match store.get(STORE_KEY_IMAGE_PRICING) {
+        Some(JsonValue::Null) | None => pricing::ImagePricingTable::new(),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| format!("Failed to deserialize image pricing table: {}", e))?,
+    };
+    Ok(pricing::merge_image_pricing_tables(
+        &pricing::default_image_pricing_table(),
+        &overrides,
+    ))
+}
+
+#[tauri::command]
+async fn get_image_pricing_table(
+    app: tauri::AppHandle,
+) -> Result<pricing::ImagePricingTable, String> {
+    load_image_pricing_table(&app).await
+}
+
+#[tauri::command]
+async fn update_image_pricing_table(
+    app: tauri::AppHandle,
+    json: String,
+) -> Result<pricing::ImagePricingTable, String> {
+    let updates: pricing::ImagePricingTable = serde_json::from_str(&json).map_err(|e| {
+        format!(
+            "Invalid image pricing table JSON (expected {{\"TURBO\": {{\"cost_per_image\": .., \"seconds_per_image\": ..}}}}): {}",
+            e
+        )
+    })?;
+    if let Some((speed, _)) = updates.iter().find(|(_, p)| {
+        !p.cost_per_image.is_finite()
+            || !p.seconds_per_image.is_finite()
+            || p.cost_per_image < 0.0
+            || p.seconds_per_image < 0.0
+    }) {
+        return Err(format!(
+            "Invalid (negative or non-finite) values for rendering speed '{}'.",
+            speed
+        ));
+    }
+
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let current = image_pricing_table_from_store(&store)?;
+    let merged = pricing::merge_image_pricing_tables(&current, &updates);
+    store.set(
+        STORE_KEY_IMAGE_PRICING.to_string(),
+        serde_json::to_value(&merged)
+            .map_err(|e| format!("Failed to serialize image pricing table: {}", e))?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+    logging::info(&format!(
+        "Updated image pricing table with {} rendering speed(s).",
+        updates.len()
+    ));
+    Ok(merged)
+}
+
+#[tauri::command]
+async fn estimate_image_cost(
+    app: tauri::AppHandle,
+    num_images: u32,
+    rendering_speed: Option<String>,
+) -> Result<pricing::ImageCostEstimate, String> {
+    let table = load_image_pricing_table(&app).await?;
+    let rendering_speed = rendering_speed.unwrap_or_else(|| "TURBO".to_string());
+    let estimate =
+        pricing::estimate_image_cost(&table, num_images, &rendering_speed).ok_or_else(|| {
+            format!(
+                "No image pricing known for rendering speed '{}'.",
+                rendering_speed
+            )
+        })?;
+    logging::info(&format!(
+        "Estimated {} {} image(s) at ~${:.2} and ~{:.0}s.",
+        estimate.num_images,
+        estimate.rendering_speed,
+        estimate.estimated_cost_usd,
+        estimate.estimated_seconds
+    ));
+    Ok(estimate)
+}
+
+#[tauri::command]
+async fn export_all_articles(
+    app: tauri::AppHandle,
+    dir_path: String,
+) -> Result<ExportSummary, String> {
+    logging::info(&format!(
+        "Exporting all article histories to '{}'",
+        dir_path
+    ));
+
+    let output_dir = PathBuf::from(&dir_path);
+    if dir_path.trim().is_empty() {
+        return Err("Export directory path cannot be empty.".to_string());
+    }
+
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let projects = load_projects(&app, &store)?;
+    let history = load_article_history(&app, &store)?;
+
+    let mut project_names: Vec<String> = projects.keys().chain(history.keys()).cloned().collect();
+    project_names.sort_unstable();
+    project_names.dedup();
+
+    let mut projects_exported = 0;
+    let mut articles_exported = 0;
+    let mut projects_without_articles: Vec<String> = Vec::new();
+
+    for project_name in project_names {
+        let versions = match history.get(&project_name) {
+            Some(versions) if !versions.is_empty() => versions,
+            _ => {
+                logging::info(&format!(
+                    "Project '{}' has no stored articles, skipping.",
+                    project_name
+                ));
+                projects_without_articles.push(project_name);
+                continue;
+            }
+        };
+
+        let project_dir = output_dir.join(sanitize_file_name(&project_name));
+        std::fs::create_dir_all(&project_dir)
+            .map_err(|e| format!("Failed to create export directory {:?}: {}", project_dir, e))?;
+
+        for (index, version) in versions.iter().enumerate() {
+            let title = version.title.as_deref().unwrap_or("article");
+            let file_name = format!(
+                "{:03}_{}_{}.html",
+                index + 1,
+                version.created_at,
+                sanitize_file_name(title)
+            );
+            // Stored titles come straight from the model's <title>, so they may already hold entities.
+            let escaped_title = escape_html_attribute(&unescape_html_attribute(title));
+            let document = format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+                escaped_title, version.article_html
+            );
+            std::fs::write(project_dir.join(&file_name), document)
+                .map_err(|e| format!("Failed to write {}: {}", file_name, e))?;
+            articles_exported += 1;
+        }
+        projects_exported += 1;
+    }
+
+    logging::info(&format!(
+        "Exported {} articles across {} projects ({} without articles).",
+        articles_exported,
+        projects_exported,
+        projects_without_articles.len()
+    ));
+
+    Ok(ExportSummary {
+        output_path: output_dir.to_string_lossy().to_string(),
+        projects_exported,
+        articles_exported,
+        projects_without_articles,
+    })
+}
+
+#[tauri::command]
+async fn generate_and_upload_featured_image(
+    app: tauri::AppHandle,
+    project_name: String,
+    topic: String,
+    use_llm_prompt: Option<bool>,
+) -> Result<u32, String> {
+    logging::info(&format!(
+        "Generating featured image for project '{}' on topic: {}",
+        project_name, topic
+    ));
+    if topic.trim().is_empty() {
+        return Err("Topic cannot be empty.".to_string());
+    }
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+    let api_url = openai_chat_completions_url(settings.openai_base_url.as_deref());
+
+    if settings.wordpress_url.trim().is_empty()
+        || settings.wordpress_user.trim().is_empty()
+        || settings.wordpress_pass.trim().is_empty()
+    {
+        return Err(
+            "WordPress URL, User, and Application Password must be configured.".to_string(),
+        );
+    }
+
+    if let Some(limit) = settings.daily_upload_limit {
+        if get_daily_upload_count(&app, &project_name)? >= limit {
+            return Err(format!(
+                "{}: Daily upload quota of {} image(s) reached for project '{}'. Retry after midnight.",
+                DAILY_QUOTA_REACHED_ERROR, limit, project_name
+            ));
+        }
+    }
+
+    let default_prompt = format!(
+        "Featured header illustration for a blog article about {}, clean modern editorial style, vibrant colors, no text",
+        topic.trim()
+    );
+    let prompt = if use_llm_prompt.unwrap_or(false) {
+        let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+            .await?
+            .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+        let suggested = call_openai_chat(
+            &shared_client(&app),
+            &api_url,
+            &api_key,
+            &settings.text_generation_model,
+            "You write concise image generation prompts for blog featured images. Output ONLY the prompt, without quotes or explanations. The image must not contain any text.",
+            &format!("Write an image prompt for the featured image of an article about: {}", topic),
+            0.7,
+            request_timeout(None),
+        )
+        .await
+        .map_err(|e| format!("Featured image prompt generation failed: {}", e))?;
+        let suggested = suggested.trim().trim_matches('"').trim().to_string();
+        if suggested.is_empty() {
+            default_prompt
+        } else {
+            suggested
+        }
+    } else {
+        default_prompt
+    };
+    logging::info(&format!("Featured image prompt: {}", prompt));
+
+    let image = generate_ideogram_image(
+        app.clone(),
+        ImageGenRequest {
+            prompt,
+            rendering_speed: None,
+            aspect_ratio: Some("16x9".to_string()),
+            project_name: Some(project_name.clone()),
+            seed: None,
+            rewrite_unsafe_prompt: Some(true),
+            dry_run: None,
+            style_reference_urls: None,
+            timeout_secs: None,
+        },
+    )
+    .await
+    .map_err(|e| format!("Featured image generation failed: {}", e))?;
+    let image_url = image
+        .image_url
+        .ok_or_else(|| "Featured image generation returned no image URL.".to_string())?;
+
+    let client = shared_client(&app);
+    let image_bytes = download_image_bytes(
+        &client,
+        &image_url,
+        Duration::from_secs(settings.image_download_timeout_secs.max(1)),
+    )
+    .await
+    .map_err(|e| format!("Featured image download failed: {}", e))?;
+
+    let media_api_url = format!(
+        "{}/wp-json/wp/v2/media",
+        settings.wordpress_url.trim_end_matches('/')
+    );
+    // The early check only avoids paying for a generation; this is the binding one.
+    if let Some(limit) = settings.daily_upload_limit {
+        if reserve_daily_uploads(&app, &project_name, limit, 1)? == 0 {
+            return Err(format!(
+                "{}: Daily upload quota of {} image(s) reached for project '{}'. Retry after midnight.",
+                DAILY_QUOTA_REACHED_ERROR, limit, project_name
+            ));
+        }
+    }
+    let upload_result = process_single_image_upload(
+        &client,
+        &media_api_url,
+        &settings.wordpress_user,
+        &settings.wordpress_pass,
+        &image_url,
+        image_bytes,
+        request_timeout_or(None, DEFAULT_UPLOAD_TIMEOUT_SECS),
+        &settings.upload_retry.clone().unwrap_or_default(),
+    )
+    .await;
+    if !upload_result.success && settings.daily_upload_limit.is_some() {
+        if let Err(e) = release_daily_uploads(&app, &project_name, 1) {
+            logging::warn(&format!("Failed to release unused upload quota: {}", e));
+        }
+    }
+
+    match upload_result.wordpress_media_id {
+        Some(media_id) if upload_result.success => {
+            logging::info(&format!("Featured image uploaded as media ID {}", media_id));
+            Ok(media_id)
+        }
+        _ => Err(format!(
+            "Featured image upload failed: {}",
+            upload_result
+                .error
+                .unwrap_or_else(|| "unknown error".to_string())
+        )),
+    }
+}
+
+fn store_file_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(STORE_FILE))
+        .map_err(|e| format!("Failed to get app data dir: {}", e))
+}
+
+fn store_backup_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("backups"))
+        .map_err(|e| format!("Failed to get app data dir: {}", e))
+}
+
+fn read_store_backup_settings(app: &tauri::AppHandle) -> StoreBackupSettings {
+    let store = app.store(PathBuf::from(STORE_FILE)).ok();
+    let get_u64 = |key: &str| {
+        store
+            .as_ref()
+            .and_then(|s| s.get(key))
+            .and_then(|value| value.as_u64())
+    };
+    StoreBackupSettings {
+        interval_minutes: get_u64(STORE_KEY_BACKUP_INTERVAL_MINS)
+            .filter(|minutes| *minutes > 0)
+            .unwrap_or(DEFAULT_BACKUP_INTERVAL_MINS),
+        retention: get_u64(STORE_KEY_BACKUP_RETENTION)
+            .filter(|retention| *retention > 0)
+            .map(|retention| retention as usize)
+            .unwrap_or(DEFAULT_BACKUP_RETENTION),
+    }
+}
+
+// Writes to a temporary file first and renames it into place, so readers never see a
+// partially written file.
+fn write_file_atomically(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, bytes)
+        .map_err(|e| format!("Failed to write temporary file {:?}: {}", tmp_path, e))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to move {:?} into place: {}", tmp_path, e))
+}
+
+fn list_backup_files(backup_dir: &Path) -> Result<Vec<StoreBackup>, String> {
+    if !backup_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups: Vec<StoreBackup> = std::fs::read_dir(backup_dir)
+        .map_err(|e| format!("Failed to read backup directory: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let stem = name.strip_prefix(BACKUP_FILE_PREFIX)?;
+            let created_at = stem
+                .strip_suffix(BACKUP_FILE_SUFFIX)
+                .or_else(|| stem.strip_suffix(LEGACY_BACKUP_FILE_SUFFIX))?
+                .parse::<u64>()
+                .ok()?;
+            let size_bytes = entry.metadata().map(|meta| meta.len()).unwrap_or(0);
+            Some(StoreBackup {
+                name,
+                created_at,
+                size_bytes,
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(backups)
+}
+
+// (file name inside projects/, contents)
+type ProjectFileSnapshot = Vec<(String, Vec<u8>)>;
+
+// Per-project files are archived next to the main store so a restore never leaves the
+// project index pointing at missing or newer files.
+fn read_project_files_for_backup(app: &tauri::AppHandle) -> Result<ProjectFileSnapshot, String> {
+    let projects_dir = project_store_dir(app)?;
+    if !projects_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&projects_dir)
+        .map_err(|e| format!("Failed to read project directory: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("dat"))
+        .collect();
+    paths.sort();
+
+    let mut files = Vec::new();
+    for path in paths {
+        let bytes = std::fs::read(&path)
+            .map_err(|e| format!("Failed to read project file {:?}: {}", path, e))?;
+        if serde_json::from_slice::<serde_json::Value>(&bytes).is_err() {
+            return Err(format!(
+                "Project file {:?} is not valid JSON right now (possibly mid-write), skipping backup.",
+                path
+            ));
+        }
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        files.push((file_name, bytes));
+    }
+    Ok(files)
+}
+
+fn build_backup_archive(
+    store_bytes: &[u8],
+    project_files: &[(String, Vec<u8>)],
+) -> Result<Vec<u8>, String> {
+    let mut zip_writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    zip_writer
+        .start_file(STORE_FILE, options)
+        .map_err(|e| format!("Failed to add store to backup: {}", e))?;
+    zip_writer
+        .write_all(store_bytes)
+        .map_err(|e| format!("Failed to write store to backup: {}", e))?;
+    for (file_name, bytes) in project_files {
+        let entry_name = format!("{}/{}", PROJECT_STORE_DIR, file_name);
+        zip_writer
+            .start_file(entry_name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to backup: {}", entry_name, e))?;
+        zip_writer
+            .write_all(bytes)
+            .map_err(|e| format!("Failed to write {} to backup: {}", entry_name, e))?;
+    }
+    zip_writer
+        .finish()
+        .map(|cursor| cursor.into_inner())
+        .map_err(|e| format!("Failed to finalize backup: {}", e))
+}
+
+fn read_backup_archive(bytes: &[u8]) -> Result<(Vec<u8>, ProjectFileSnapshot), String> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+        .map_err(|e| format!("Backup is not a valid archive: {}", e))?;
+    let mut store_bytes = None;
+    let mut project_files = Vec::new();
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read backup entry: {}", e))?;
+        let entry_name = entry.name().to_string();
+        let mut contents = Vec::new();
+        std::io::Read::read_to_end(&mut entry, &mut contents)
+            .map_err(|e| format!("Failed to read {} from backup: {}", entry_name, e))?;
+        if entry_name == STORE_FILE {
+            store_bytes = Some(contents);
+            continue;
+        }
+        let Some(file_name) = entry_name
+            .strip_prefix(PROJECT_STORE_DIR)
+            .and_then(|rest| rest.strip_prefix('/'))
+        else {
+            continue;
+        };
+        if file_name.is_empty()
+            || file_name.contains(['/', '\\'])
+            || file_name.contains("..")
+            || !file_name.ends_with(".dat")
+        {
+            return Err(format!(
+                "Backup contains an invalid entry '{}'.",
+                entry_name
+            ));
+        }
+        serde_json::from_slice::<serde_json::Value>(&contents)
+            .map_err(|e| format!("Backup entry '{}' is not valid JSON: {}", entry_name, e))?;
+        project_files.push((file_name.to_string(), contents));
+    }
+    let store_bytes =
+        store_bytes.ok_or_else(|| format!("Backup does not contain {}.", STORE_FILE))?;
+    Ok((store_bytes, project_files))
+}
+
+// Files that did not exist when the backup was taken are removed, so the restored
+// index and directory match.
+fn restore_project_files(
+    app: &tauri::AppHandle,
+    project_files: &[(String, Vec<u8>)],
+) -> Result<(), String> {
+    let projects_dir = project_store_dir(app)?;
+    std::fs::create_dir_all(&projects_dir)
+        .map_err(|e| format!("Failed to create project directory: {}", e))?;
+    for (file_name, bytes) in project_files {
+        let path = projects_dir.join(file_name);
+        write_file_atomically(&path, bytes)?;
+        if let Some(project_store) = app.get_store(path.clone()) {
+            project_store
+                .reload()
+                .map_err(|e| format!("Failed to reload project store {:?}: {}", path, e))?;
+        }
+    }
+    let stale: Vec<PathBuf> = std::fs::read_dir(&projects_dir)
+        .map_err(|e| format!("Failed to read project directory: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("dat"))
+        .filter(|path| {
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            !project_files.iter().any(|(name, _)| *name == file_name)
+        })
+        .collect();
+    for path in stale {
+        if let Some(project_store) = app.get_store(path.clone()) {
+            project_store.clear();
+        }
+        std::fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove project file {:?}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+fn backup_store_once(app: &tauri::AppHandle, retention: usize) -> Result<Option<String>, String> {
+    let store_path = store_file_path(app)?;
+    if !store_path.exists() {
+        return Ok(None);
+    }
+    let store_bytes =
+        std::fs::read(&store_path).map_err(|e| format!("Failed to read store file: {}", e))?;
+    if serde_json::from_slice::<serde_json::Value>(&store_bytes).is_err() {
+        return Err(
+            "Store file is not valid JSON right now (possibly mid-write), skipping backup."
+                .to_string(),
+        );
+    }
+    let project_files = read_project_files_for_backup(app)?;
+    let bytes = build_backup_archive(&store_bytes, &project_files)?;
+
+    let backup_dir = store_backup_dir(app)?;
+    std::fs::create_dir_all(&backup_dir)
+        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    let name = format!(
+        "{}{}{}",
+        BACKUP_FILE_PREFIX,
+        unix_timestamp_secs(),
+        BACKUP_FILE_SUFFIX
+    );
+    write_file_atomically(&backup_dir.join(&name), &bytes)?;
+
+    for old_backup in list_backup_files(&backup_dir)?.into_iter().skip(retention) {
+        if let Err(e) = std::fs::remove_file(backup_dir.join(&old_backup.name)) {
+            logging::warn(&format!(
+                "Failed to remove old backup {}: {}",
+                old_backup.name, e
+            ));
+        }
+    }
+    Ok(Some(name))
+}
+
+fn start_store_backup_task(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let backup_settings = read_store_backup_settings(&app);
+            sleep(Duration::from_secs(backup_settings.interval_minutes * 60)).await;
+            match backup_store_once(&app, backup_settings.retention) {
+                Ok(Some(name)) => logging::info(&format!("Store backed up to {}", name)),
+                Ok(None) => logging::info("No store file yet, skipping backup."),
+                Err(e) => logging::warn(&format!("Store backup failed: {}", e)),
+            }
+        }
+    });
+}
+
+#[tauri::command]
+async fn get_store_backup_settings(app: tauri::AppHandle) -> Result<StoreBackupSettings, String> {
+    Ok(read_store_backup_settings(&app))
+}
+
+#[tauri::command]
+async fn set_store_backup_settings(
+    app: tauri::AppHandle,
+    settings: StoreBackupSettings,
+) -> Result<(), String> {
+    if settings.interval_minutes == 0 || settings.retention == 0 {
+        return Err("Backup interval and retention must both be greater than 0.".to_string());
+    }
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store.set(
+        STORE_KEY_BACKUP_INTERVAL_MINS.to_string(),
+        JsonValue::from(settings.interval_minutes),
+    );
+    store.set(
+        STORE_KEY_BACKUP_RETENTION.to_string(),
+        JsonValue::from(settings.retention),
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))
+}
+
+#[tauri::command]
+async fn list_store_backups(app: tauri::AppHandle) -> Result<Vec<StoreBackup>, String> {
+    list_backup_files(&store_backup_dir(&app)?)
+}
+
+#[tauri::command]
+async fn restore_store_backup(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    logging::info(&format!("Restoring store backup '{}'", name));
+    if name.contains(['/', '\\']) || name.contains("..") {
+        return Err(format!("Invalid backup name '{}'.", name));
+    }
+
+    let backup_path = store_backup_dir(&app)?.join(&name);
+    let bytes = std::fs::read(&backup_path)
+        .map_err(|e| format!("Failed to read backup '{}': {}", name, e))?;
+    let (store_bytes, project_files) = if name.ends_with(BACKUP_FILE_SUFFIX) {
+        let (store_bytes, project_files) =
+            read_backup_archive(&bytes).map_err(|e| format!("Backup '{}': {}", name, e))?;
+        (store_bytes, Some(project_files))
+    } else {
+        (bytes, None)
+    };
+    serde_json::from_slice::<serde_json::Value>(&store_bytes)
+        .map_err(|e| format!("Backup '{}' is not a valid store file: {}", name, e))?;
+
+    let _guard = lock_store_writes()?;
+    match backup_store_once(&app, usize::MAX) {
+        Ok(Some(safety_backup)) => logging::info(&format!(
+            "Saved current store as {} before restoring.",
+            safety_backup
+        )),
+        Ok(None) => {}
+        Err(e) => logging::warn(&format!(
+            "Could not back up current store before restore: {}",
+            e
+        )),
+    }
+
+    match &project_files {
+        Some(project_files) => restore_project_files(&app, project_files)?,
+        None => logging::info(&format!(
+            "Backup '{}' predates per-project files; leaving project files untouched.",
+            name
+        )),
+    }
+    write_file_atomically(&store_file_path(&app)?, &store_bytes)?;
+    app.store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?
+        .reload()
+        .map_err(|e| format!("Failed to reload restored store: {}", e))?;
+
+    logging::info(&format!("Store restored from '{}'.", name));
+    Ok(())
+}
+
+const MAX_TITLE_VARIANTS: usize = 10;
+
+#[tauri::command]
+async fn generate_title_variants(
+    app: tauri::AppHandle,
+    project_name: String,
+    article_html: String,
+    count: usize,
+) -> Result<Vec<String>, String> {
+    logging::info(&format!(
+        "Generating {} title variants for project '{}'.",
+        count, project_name
+    ));
+    if count == 0 || count > MAX_TITLE_VARIANTS {
+        return Err(format!(
+            "Title variant count must be between 1 and {}.",
+            MAX_TITLE_VARIANTS
+        ));
+    }
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+    let api_url = openai_chat_completions_url(settings.openai_base_url.as_deref());
+    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+        .await?
+        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+
+    let user_prompt = format!(
+        r#"Propose {count} distinct SEO-optimized titles for the article below about the tool "{tool_name}".
+
+Rules:
+- Each title must be between {min_chars} and {max_chars} characters long.
+- Include the main keyword "{tool_name}" and relevant keywords such as "avis", "fonctionnalités", "tarifs" or "2025".
+- Write in the same language as the article, following its typographic conventions.
+- Vary the angle (benefit, comparison, question, list...) so the titles are genuinely different.
+- Output ONLY a valid JSON array of {count} strings.
+
+Article:
+---
+{article}
+---"#,
+        count = count,
+        min_chars = TITLE_MIN_CHARS,
+        max_chars = TITLE_MAX_CHARS,
+        tool_name = settings.tool_name,
+        article = article_html
+    );
+
+    let content = call_openai_chat(
+        &shared_client(&app),
+        &api_url,
+        &api_key,
+        &settings.text_generation_model,
+        "You are an SEO copywriter who writes article titles and outputs ONLY a valid JSON array of strings.",
+        &user_prompt,
+        0.9,
+        request_timeout(None),
+    )
+    .await?;
+
+    let candidates = parse_llm_json::<Vec<String>>(&content).map_err(|e| {
+        logging::warn(&format!("Failed to parse title variants: {}", e));
+        format!("Failed to parse title variants: {}", e)
+    })?;
+
+    let mut titles: Vec<String> = Vec::new();
+    for candidate in candidates {
+        let title = strip_html_tags(&candidate);
+        let length = title.chars().count();
+        if !(TITLE_MIN_CHARS..=TITLE_MAX_CHARS).contains(&length) {
+            logging::info(&format!(
+                "Dropping title variant with {} chars: {}",
+                length, title
+            ));
+            continue;
+        }
+        if titles
+            .iter()
+            .any(|existing| existing.to_lowercase() == title.to_lowercase())
+        {
+            continue;
+        }
+        titles.push(title);
+    }
+    titles.truncate(count);
+
+    if titles.is_empty() {
+        return Err("The model did not return any usable title variants.".to_string());
+    }
+    logging::info(&format!("Returning {} title variants.", titles.len()));
+    Ok(titles)
+}
+
+fn classify_request_error(error: &reqwest::Error) -> &'static str {
+    if error.is_timeout() {
+        return "timeout";
+    }
+    let mut chain = error.to_string().to_lowercase();
+    let mut source = std::error::Error::source(error);
+    while let Some(inner) = source {
+        chain.push(' ');
+        chain.push_str(&inner.to_string().to_lowercase());
+        source = inner.source();
+    }
+    if chain.contains("dns")
+        || chain.contains("failed to lookup")
+        || chain.contains("name or service not known")
+    {
+        "dns_failure"
+    } else if chain.contains("certificate")
+        || chain.contains("tls")
+        || chain.contains("handshake")
+        || chain.contains("ssl")
+    {
+        "tls_error"
+    } else if chain.contains("connection refused") {
+        "connection_refused"
+    } else if chain.contains("proxy") {
+        "proxy_error"
+    } else if error.is_connect() {
+        "connect_error"
+    } else {
+        "request_error"
+    }
+}
+
+async fn run_dns_check(host: &str) -> NetworkCheck {
+    let started = std::time::Instant::now();
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        tokio::net::lookup_host((host, 443)),
+    )
+    .await;
+    let duration_ms = started.elapsed().as_millis();
+    let (success, error_class, detail) = match result {
+        Ok(Ok(addresses)) => {
+            let addresses: Vec<String> = addresses.map(|addr| addr.ip().to_string()).collect();
+            (true, None, Some(addresses.join(", ")))
+        }
+        Ok(Err(e)) => (false, Some("dns_failure".to_string()), Some(e.to_string())),
+        Err(_) => (false, Some("timeout".to_string()), None),
+    };
+    NetworkCheck {
+        name: "dns".to_string(),
+        target: host.to_string(),
+        success,
+        duration_ms,
+        error_class,
+        detail,
+    }
+}
+
+async fn run_http_check(
+    client: &Client,
+    name: &str,
+    url: &str,
+    bearer_token: Option<&str>,
+) -> NetworkCheck {
+    let started = std::time::Instant::now();
+    let mut builder = client.get(url).timeout(Duration::from_secs(15));
+    if let Some(token) = bearer_token {
+        builder = builder.bearer_auth(token);
+    }
+    let result = builder.send().await;
+    let duration_ms = started.elapsed().as_millis();
+
+    let (success, error_class, detail) = match result {
+        Ok(response) => {
+            let status = response.status();
+            match status {
+                StatusCode::UNAUTHORIZED if bearer_token.is_some() => (
+                    false,
+                    Some("invalid_credentials".to_string()),
+                    Some(format!(
+                        "Status {}: the stored API key was rejected",
+                        status
+                    )),
+                ),
+                _ if status.is_server_error() => (
+                    false,
+                    Some("server_error".to_string()),
+                    Some(format!("Status {}", status)),
+                ),
+                // Any other HTTP answer proves DNS, TCP and TLS all work.
+                _ => (true, None, Some(format!("Status {}", status))),
+            }
+        }
+        Err(e) => (
+            false,
+            Some(classify_request_error(&e).to_string()),
+            Some(e.to_string()),
+        ),
+    };
+    NetworkCheck {
+        name: name.to_string(),
+        target: url.to_string(),
+        success,
+        duration_ms,
+        error_class,
+        detail,
+    }
+}
+
+#[tauri::command]
+async fn diagnose_network(app: tauri::AppHandle) -> Result<NetworkDiagnostics, String> {
+    logging::info("Running network diagnostics.");
+
+    let proxy = [
+        "HTTPS_PROXY",
+        "https_proxy",
+        "HTTP_PROXY",
+        "http_proxy",
+        "ALL_PROXY",
+    ]
+    .iter()
+    .find_map(|var| std::env::var(var).ok().filter(|value| !value.is_empty()))
+    .map(|proxy_url| redact_url_credentials(&proxy_url));
+    if let Some(proxy_url) = &proxy {
+        logging::info(&format!("Proxy configured via environment: {}", proxy_url));
+    }
+
+    let client = shared_client(&app);
+    let mut checks = vec![
+        run_http_check(
+            &client,
+            "internet",
+            "https://www.gstatic.com/generate_204",
+            None,
+        )
+        .await,
+        run_dns_check("api.openai.com").await,
+        run_dns_check("api.ideogram.ai").await,
+        run_http_check(
+            &client,
+            "openai_tls",
+            "https://api.openai.com/v1/models",
+            None,
+        )
+        .await,
+        run_http_check(&client, "ideogram_tls", "https://api.ideogram.ai/", None).await,
+    ];
+
+    match get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string()).await {
+        Ok(Some(api_key)) if !api_key.is_empty() => {
+            checks.push(
+                run_http_check(
+                    &client,
+                    "openai_auth",
+                    "https://api.openai.com/v1/models",
+                    Some(&api_key),
+                )
+                .await,
+            );
+        }
+        _ => checks.push(NetworkCheck {
+            name: "openai_auth".to_string(),
+            target: "https://api.openai.com/v1/models".to_string(),
+            success: false,
+            duration_ms: 0,
+            error_class: Some("missing_credentials".to_string()),
+            detail: Some("No OpenAI API key is stored.".to_string()),
+        }),
+    }
+
+    for check in &checks {
+        logging::info(&format!(
+            "[{}] {} -> success: {}, {} ms, class: {:?}, detail: {:?}",
+            check.name,
+            check.target,
+            check.success,
+            check.duration_ms,
+            check.error_class,
+            check.detail
+        ));
+    }
+
+    Ok(NetworkDiagnostics { proxy, checks })
+}
+
+#[tauri::command]
+async fn merge_articles(
+    app: tauri::AppHandle,
+    project_name: Option<String>,
+    article_htmls: Vec<String>,
+    title: String,
+) -> Result<String, String> {
+    logging::info(&format!(
+        "Merging {} articles under title '{}'.",
+        article_htmls.len(),
+        title
+    ));
+    if article_htmls.len() < 2 {
+        return Err("At least two articles are required to merge.".to_string());
+    }
+    if title.trim().is_empty() {
+        return Err("The merged article title cannot be empty.".to_string());
+    }
+
+    let style_regex =
+        Regex::new(r"(?is)<style\b[^>]*>.*?</style\s*>").expect("Invalid style regex");
+    let h1_regex = Regex::new(r"(?is)<h1(?:[^>]*)>.*?</h1>").expect("Invalid H1 regex");
+    let head_only_regex =
+        Regex::new(r"(?is)<title>.*?</title>|<meta\b[^>]*>").expect("Invalid head element regex");
+
+    // Identical <style> blocks are kept once; different ones are all kept in article order.
+    let mut style_blocks: Vec<String> = Vec::new();
+    let mut parts: Vec<String> = Vec::new();
+    for html in &article_htmls {
+        let body = if Regex::new(r"(?is)<body\b")
+            .expect("Invalid body regex")
+            .is_match(html)
+        {
+            extract_body_content(html)
+        } else {
+            html.trim().to_string()
+        };
+        for style in style_regex.find_iter(&body) {
+            let style = style.as_str().trim().to_string();
+            if !style_blocks.contains(&style) {
+                style_blocks.push(style);
+            }
+        }
+        let body = style_regex.replace_all(&body, "");
+        let body = h1_regex.replace_all(&body, "");
+        let body = head_only_regex.replace_all(&body, "");
+        let body = body.trim();
+        if !body.is_empty() {
+            parts.push(body.to_string());
+        }
+    }
+    if parts.is_empty() {
+        return Err("All articles were empty after extracting their body content.".to_string());
+    }
+
+    let merged_body = if is_safe_mode(&app) {
+        logging::info("Safe mode enabled, concatenating articles without OpenAI.");
+        parts.join("\n")
+    } else {
+        let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+            .await?
+            .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+        let api_url = project_chat_completions_url(&app, project_name.as_deref()).await?;
+        let model = project_text_model(&app, project_name.as_deref()).await?;
+        let user_prompt = format!(
+            "Merge the following {} HTML articles into one coherent article titled \"{}\".\n\
+             - Keep one introduction at the start and one conclusion at the end; merge or drop the repeated ones.\n\
+             - Keep every unique section, fact, table and link; remove content that is repeated across articles.\n\
+             - Use <h2>/<h3> headings, no <h1>, no <style>, no <html>, <head> or <body> tags.\n\
+             - Keep every <img> tag and [INSERT_IMAGE_HERE_*] placeholder unchanged.\n\
+             Output ONLY the merged HTML.\n\n{}",
+            parts.len(),
+            title.trim(),
+            parts
+                .iter()
+                .enumerate()
+                .map(|(index, part)| format!("--- ARTICLE {} ---\n{}", index + 1, part))
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        );
+        let content = call_openai_chat(
+            &shared_client(&app),
+            &api_url,
+            &api_key,
+            &model,
+            "You are an editor who merges several HTML articles into a single well-structured article in the same language. You output ONLY HTML.",
+            &user_prompt,
+            0.4,
+            Duration::from_secs(LONG_LLM_REQUEST_TIMEOUT_SECS),
+        )
+        .await?;
+        strip_code_fences(&content).trim().to_string()
+    };
+
+    let mut merged = String::new();
+    for style in &style_blocks {
+        merged.push_str(style);
+        merged.push('\n');
+    }
+    merged.push_str(&format!(
+        "<h1>{}</h1>\n{}",
+        escape_html_attribute(title.trim()),
+        merged_body
+    ));
+    let merged = consolidate_styles(&merged);
+    logging::info(&format!(
+        "Merged article length: {} ({} style block(s) consolidated).",
+        merged.len(),
+        style_blocks.len()
+    ));
+    Ok(merged)
+}
+
+// Splits a stylesheet into top-level rules, keeping @media/@supports blocks whole.
+fn split_css_rules(css: &str) -> Vec<String> {
+    let comment_regex = Regex::new(r"(?s)/\*.*?\*/").expect("Invalid CSS comment regex");
+    let css = comment_regex.replace_all(css, "");
+    let mut rules = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    for c in css.chars() {
+        current.push(c);
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    rules.push(current.trim().to_string());
+                    current.clear();
+                }
+            }
+            // Statements such as @import end with ';' outside any block.
+            ';' if depth == 0 => {
+                rules.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => {}
+        }
+    }
+    if !current.trim().is_empty() {
+        rules.push(current.trim().to_string());
+    }
+    rules.retain(|rule| !rule.is_empty());
+    rules
+}
+
+fn consolidate_styles(html: &str) -> String {
+    let style_regex =
+        Regex::new(r"(?is)<style\b[^>]*>(.*?)</style\s*>").expect("Invalid style regex");
+    let blocks: Vec<String> = style_regex
+        .captures_iter(html)
+        .map(|caps| caps[1].to_string())
+        .collect();
+    if blocks.is_empty() {
+        return html.to_string();
+    }
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut rules: Vec<String> = Vec::new();
+    for block in &blocks {
+        for rule in split_css_rules(block) {
+            let normalized = rule.split_whitespace().collect::<Vec<_>>().join(" ");
+            if seen.insert(normalized) {
+                rules.push(rule);
+            }
+        }
+    }
+
+    let without_styles = style_regex.replace_all(html, "").to_string();
+    let blank_lines = Regex::new(r"\n\s*\n\s*\n").expect("Invalid blank line regex");
+    let without_styles = blank_lines.replace_all(&without_styles, "\n\n").to_string();
+    if rules.is_empty() {
+        return without_styles;
+    }
+    let style_block = format!("<style>\n{}\n</style>", rules.join("\n"));
+
+    let lower = without_styles.to_ascii_lowercase();
+    match lower.find("</head>") {
+        Some(position) => format!(
+            "{}{}\n{}",
+            &without_styles[..position],
+            style_block,
+            &without_styles[position..]
+        ),
+        None => format!("{}\n{}", style_block, without_styles.trim_start()),
+    }
+}
+
+// (platform, max characters, style guidance for the prompt)
+const SOCIAL_PLATFORMS: &[(&str, usize, &str)] = &[
+    ("x", 280, "a punchy tweet with at most 2 hashtags"),
+    (
+        "linkedin",
+        3000,
+        "a professional post of 3 to 5 short paragraphs ending with a question",
+    ),
+    (
+        "instagram",
+        2200,
+        "a friendly caption followed by 10 to 15 relevant hashtags",
+    ),
+    (
+        "facebook",
+        2000,
+        "a conversational post of 2 or 3 short paragraphs",
+    ),
+    (
+        "threads",
+        500,
+        "a casual short post with at most 3 hashtags",
+    ),
+    ("bluesky", 300, "a concise post with at most 2 hashtags"),
+    (
+        "mastodon",
+        500,
+        "a concise post with a few CamelCase hashtags",
+    ),
+];
+
+fn social_platform_spec(platform: &str) -> Option<(&'static str, usize, &'static str)> {
+    let platform = match platform.trim().to_ascii_lowercase().as_str() {
+        "twitter" => "x".to_string(),
+        other => other.to_string(),
+    };
+    SOCIAL_PLATFORMS
+        .iter()
+        .find(|(name, _, _)| *name == platform)
+        .copied()
+}
+
+#[tauri::command]
+async fn generate_social_posts(
+    app: tauri::AppHandle,
+    project_name: Option<String>,
+    article_html: String,
+    platforms: Vec<String>,
+) -> Result<HashMap<String, String>, String> {
+    logging::info(&format!(
+        "Generating social posts for platforms: {:?}",
+        platforms
+    ));
+    if platforms.is_empty() {
+        return Err("At least one platform is required.".to_string());
+    }
+    let mut specs = Vec::new();
+    for platform in &platforms {
+        let spec = social_platform_spec(platform).ok_or_else(|| {
+            format!(
+                "Unsupported platform '{}'. Supported: {}.",
+                platform,
+                SOCIAL_PLATFORMS
+                    .iter()
+                    .map(|(name, _, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+        if !specs.contains(&spec) {
+            specs.push(spec);
+        }
+    }
+
+    let seo = extract_seo_metadata(&article_html)?;
+    if is_safe_mode(&app) {
+        logging::info("Safe mode enabled, returning placeholder social posts.");
+        return Ok(specs
+            .iter()
+            .map(|(name, max_chars, _)| {
+                (
+                    name.to_string(),
+                    truncate_at_word_boundary(
+                        &format!("[Safe mode] {} - {}", seo.title, seo.description),
+                        *max_chars,
+                    ),
+                )
+            })
+            .collect());
+    }
+
+    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+        .await?
+        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+    let api_url = project_chat_completions_url(&app, project_name.as_deref()).await?;
+    let model = project_text_model(&app, project_name.as_deref()).await?;
+
+    let article_text = truncate_at_word_boundary(&strip_html_tags(&article_html), 6000);
+    let platform_lines: Vec<String> = specs
+        .iter()
+        .map(|(name, max_chars, style)| {
+            format!(
+                "- \"{}\": {}, at most {} characters",
+                name, style, max_chars
+            )
+        })
+        .collect();
+    let user_prompt = format!(
+        "Write one promotional post per platform for the article below, in the article's language.\n\
+         Platforms:\n{}\n\n\
+         Return ONLY a JSON object mapping each platform key to its post text.\n\n\
+         Title: {}\nSummary: {}\n\nArticle:\n{}",
+        platform_lines.join("\n"),
+        seo.title,
+        seo.description,
+        article_text
+    );
+
+    let content = call_openai_chat(
+        &shared_client(&app),
+        &api_url,
+        &api_key,
+        &model,
+        "You are a social media manager who writes platform-specific promotional posts and outputs ONLY a valid JSON object of strings.",
+        &user_prompt,
+        0.8,
+        request_timeout(None),
+    )
+    .await?;
+    let generated: HashMap<String, String> =
+        parse_llm_json(&content).map_err(|e| format!("Failed to parse social posts: {}", e))?;
+
+    let mut posts = HashMap::new();
+    for (name, max_chars, _) in specs {
+        let Some(post) = generated.get(name) else {
+            logging::warn(&format!("No post returned for platform '{}'.", name));
+            continue;
+        };
+        let post = post.trim();
+        if post.chars().count() > max_chars {
+            logging::info(&format!(
+                "Post for '{}' exceeded {} characters, truncating.",
+                name, max_chars
+            ));
+        }
+        posts.insert(name.to_string(), truncate_at_word_boundary(post, max_chars));
+    }
+    Ok(posts)
+}
+
+fn text_shingles(html: &str) -> HashSet<String> {
+    let words: Vec<String> = strip_html_tags(html)
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(String::from)
+        .collect();
+    if words.len() < SIMILARITY_SHINGLE_SIZE {
+        return std::iter::once(words.join(" "))
+            .filter(|shingle| !shingle.is_empty())
+            .collect();
+    }
+    words
+        .windows(SIMILARITY_SHINGLE_SIZE)
+        .map(|window| window.join(" "))
+        .collect()
+}
+
+fn jaccard_similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+#[tauri::command]
+async fn check_similarity(
+    app: tauri::AppHandle,
+    project_name: String,
+    candidate_html: String,
+) -> Result<Vec<SimilarityHit>, String> {
+    logging::info(&format!(
+        "Checking similarity of candidate article for project '{}'.",
+        project_name
+    ));
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let history = load_article_history(&app, &store)?;
+
+    let candidate_shingles = text_shingles(&candidate_html);
+    if candidate_shingles.is_empty() {
+        return Err("The candidate article has no text to compare.".to_string());
+    }
+
+    // Related tools often live in other projects, so every history is compared.
+    let mut hits: Vec<SimilarityHit> = Vec::new();
+    for (history_project, articles) in &history {
+        for article in articles {
+            if article.article_html.trim() == candidate_html.trim() {
+                continue;
+            }
+            let similarity =
+                jaccard_similarity(&candidate_shingles, &text_shingles(&article.article_html));
+            if similarity <= 0.0 {
+                continue;
+            }
+            hits.push(SimilarityHit {
+                project_name: history_project.clone(),
+                created_at: article.created_at,
+                title: article.title.clone(),
+                similarity,
+                exceeds_threshold: similarity >= SIMILARITY_WARNING_THRESHOLD,
+            });
+        }
+    }
+    hits.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    hits.truncate(MAX_SIMILARITY_HITS);
+
+    for hit in hits.iter().filter(|hit| hit.exceeds_threshold) {
+        logging::warn(&format!(
+            "Candidate is {:.0}% similar to '{}' ({:?}, created {}).",
+            hit.similarity * 100.0,
+            hit.project_name,
+            hit.title,
+            hit.created_at
+        ));
+    }
+    Ok(hits)
+}
+
+fn get_user_agent_settings_from_store(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+) -> Result<UserAgentSettings, String> {
+    match store.get(STORE_KEY_USER_AGENTS) {
+        Some(JsonValue::Null) | None => Ok(UserAgentSettings::default()),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| format!("Failed to deserialize User-Agent settings: {}", e)),
+    }
+}
+
+#[tauri::command]
+async fn get_user_agent_settings(app: tauri::AppHandle) -> Result<UserAgentSettings, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    get_user_agent_settings_from_store(&store)
+}
+
+#[tauri::command]
+async fn set_user_agent_settings(
+    app: tauri::AppHandle,
+    settings: UserAgentSettings,
+) -> Result<(), String> {
+    settings.validate()?;
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store.set(
+        STORE_KEY_USER_AGENTS.to_string(),
+        serde_json::to_value(&settings)
+            .map_err(|e| format!("Failed to serialize User-Agent settings: {}", e))?,
+    );
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+    logging::info(&format!("User-Agent settings updated: {:?}", settings));
+    user_agent::set_user_agents(settings);
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_store_keys(
+    app: tauri::AppHandle,
+    prefix: Option<String>,
+) -> Result<Vec<String>, String> {
+    let _guard = lock_store_writes()?;
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    // Only key names are returned; values (API keys, passwords) never leave the store.
+    let mut keys: Vec<String> = store
+        .keys()
+        .into_iter()
+        .filter(|key| match prefix.as_deref() {
+            Some(prefix) => key.starts_with(prefix),
+            None => true,
+        })
+        .collect();
+    keys.sort_unstable();
+    logging::info(&format!(
+        "Listing {} store key(s) (prefix: {:?}).",
+        keys.len(),
+        prefix
+    ));
+    Ok(keys)
+}
+
+// A second pass aims lower because models tend to overshoot word limits.
+async fn condense_to_word_ceiling(
+    app: &tauri::AppHandle,
+    api_url: &str,
+    api_key: &str,
+    model: &str,
+    article_html: String,
+    max_words: u32,
+) -> Result<String, String> {
+    let mut current = article_html;
+    for (attempt, target_words) in [(1, max_words), (2, max_words * 9 / 10)] {
+        let user_prompt = format!(
+            "Condense the following HTML article to at most {} words (it currently has {}).\n\
+             - Keep the same language, heading structure (every <h2>/<h3>), links, tables, <img> tags and [INSERT_IMAGE_HERE_*] placeholders.\n\
+             - Keep the key points, facts and prices; cut repetition, filler and overly long examples.\n\
+             Output ONLY the condensed HTML.\n\n{}",
+            target_words,
+            count_words(&current),
+            current
+        );
+        let content = call_openai_chat(
+            &shared_client(app),
+            api_url,
+            api_key,
+            model,
+            "You are an editor who shortens HTML articles to a strict word limit without breaking their structure. You output ONLY HTML.",
+            &user_prompt,
+            0.3,
+            Duration::from_secs(LONG_LLM_REQUEST_TIMEOUT_SECS),
+        )
+        .await?;
+        current = strip_code_fences(&content).trim().to_string();
+
+        let word_count = count_words(&current);
+        logging::info(&format!(
+            "Trim pass {} produced {} words (ceiling {}).",
+            attempt, word_count, max_words
+        ));
+        if word_count <= max_words as usize {
+            return Ok(current);
+        }
+    }
+
+    logging::warn(&format!(
+        "Trimmed article still has {} words, above the ceiling of {}.",
+        count_words(&current),
+        max_words
+    ));
+    Ok(current)
+}
+
+// Best effort, like the expansion passes: on failure the untrimmed article is kept.
+async fn enforce_max_word_count(
+    app: &tauri::AppHandle,
+    request: &FullArticleRequest,
+    article_html: String,
+) -> String {
+    let Some(max_words) = request.max_word_count else {
+        return article_html;
+    };
+    let word_count = count_words(&article_html);
+    if word_count <= max_words as usize {
+        return article_html;
+    }
+    if is_gemini_model(&request.model) {
+        logging::info(&format!("Article has {} words, above the ceiling of {}, but trimming is only supported for OpenAI models.", word_count, max_words));
+        return article_html;
+    }
+    logging::info(&format!(
+        "Article has {} words, above the project ceiling of {}. Trimming.",
+        word_count, max_words
+    ));
+    let api_key = match get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string()).await {
+        Ok(Some(key)) if !key.is_empty() => key,
+        _ => {
+            logging::warn("OpenAI API key unavailable, skipping trim to the word ceiling.");
+            return article_html;
+        }
+    };
+    let api_url = match project_chat_completions_url(app, request.project_name.as_deref()).await {
+        Ok(url) => url,
+        Err(e) => {
+            logging::warn(&format!(
+                "Could not resolve the OpenAI endpoint, skipping trim to the word ceiling: {}",
+                e
+            ));
+            return article_html;
+        }
+    };
+    match condense_to_word_ceiling(
+        app,
+        &api_url,
+        &api_key,
+        &request.model,
+        article_html.clone(),
+        max_words,
+    )
+    .await
+    {
+        Ok(trimmed) => trimmed,
+        Err(e) => {
+            logging::warn(&format!("Trim to the word ceiling failed: {}", e));
+            article_html
+        }
+    }
+}
+
+// Without an explicit limit the project's max_word_count is used.
+#[tauri::command]
+async fn trim_article(
+    app: tauri::AppHandle,
+    project_name: Option<String>,
+    article_html: String,
+    max_words: Option<u32>,
+) -> Result<String, String> {
+    let settings = match project_name.clone() {
+        Some(project_name) => get_project_settings(app.clone(), project_name).await?,
+        None => None,
+    };
+    let max_words = max_words
+        .or_else(|| {
+            settings
+                .as_ref()
+                .and_then(|settings| settings.max_word_count)
+        })
+        .ok_or_else(|| {
+            "No maximum word count given and the project has none configured.".to_string()
+        })?;
+    let initial_count = count_words(&article_html);
+    logging::info(&format!(
+        "Trimming article from {} words to at most {}.",
+        initial_count, max_words
+    ));
+    if max_words < MIN_TARGET_WORD_COUNT {
+        return Err(format!(
+            "Maximum word count must be at least {} words.",
+            MIN_TARGET_WORD_COUNT
+        ));
+    }
+    if initial_count <= max_words as usize {
+        logging::info("Article already fits the word ceiling.");
+        return Ok(article_html);
+    }
+
+    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+        .await?
+        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+    let api_url = project_chat_completions_url(&app, project_name.as_deref()).await?;
+    let model = project_text_model(&app, project_name.as_deref()).await?;
+    condense_to_word_ceiling(&app, &api_url, &api_key, &model, article_html, max_words).await
+}
+
+fn article_request_from_settings(
+    project_name: &str,
+    settings: &ProjectSettings,
+) -> FullArticleRequest {
+    FullArticleRequest {
+        tool_name: settings.tool_name.clone(),
+        article_goal_prompt: settings.article_goal_prompt.clone(),
+        example_url: settings.example_url.clone(),
+        sections: settings.sections.clone(),
+        model: settings.text_generation_model.clone(),
+        target_word_count: settings.target_word_count,
+        footer_html: settings.footer_html.clone(),
+        project_name: Some(project_name.to_string()),
+        language: Some(settings.language.clone()),
+        french_typography: Some(settings.french_typography),
+        timeout_secs: None,
+        escalate_on_short: settings.escalate_on_short.clone(),
+        preserve_title: None,
+        disambiguation_notes: settings.disambiguation_notes.clone(),
+        max_retries: None,
+        max_word_count: settings.max_word_count,
+    }
+}
+
+#[tauri::command]
+async fn generate_multilingual(
+    app: tauri::AppHandle,
+    project_name: String,
+    languages: Vec<String>,
+) -> Result<HashMap<String, String>, String> {
+    let mut unique_languages: Vec<String> = Vec::new();
+    for language in languages {
+        let language = language.trim().to_ascii_lowercase();
+        if !language.is_empty() && !unique_languages.contains(&language) {
+            unique_languages.push(language);
+        }
+    }
+    if unique_languages.is_empty() {
+        return Err("At least one language is required.".to_string());
+    }
+
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+    let base_request = article_request_from_settings(&project_name, &settings);
+
+    // Shares the batch concurrency setting since both are bounded by the same rate limit.
+    let concurrency = get_batch_concurrency(app.clone()).await?;
+    logging::info(&format!(
+        "Generating '{}' in {:?} with concurrency {}.",
+        project_name, unique_languages, concurrency
+    ));
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let mut tasks = JoinSet::new();
+    for language in unique_languages {
+        let app = app.clone();
+        let semaphore = semaphore.clone();
+        let mut request = base_request.clone();
+        request.language = Some(language.clone());
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let emit_progress = |status: &str, error: Option<String>| {
+                let progress = MultilingualProgress {
+                    language: language.clone(),
+                    status: status.to_string(),
+                    error,
+                };
+                if let Err(e) = app.emit("multilingual-progress", progress) {
+                    logging::warn(&format!(
+                        "Failed to emit multilingual progress event: {}",
+                        e
+                    ));
+                }
+            };
+            emit_progress("started", None);
+            let result = generate_full_article(request, app.clone()).await;
+            match &result {
+                Ok(_) => emit_progress("completed", None),
+                Err(e) => emit_progress("failed", Some(e.clone())),
+            }
+            (language, result)
+        });
+    }
+
+    let mut articles = HashMap::new();
+    let mut failures = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((language, Ok(article))) => {
+                articles.insert(language, article.article_text);
+            }
+            Ok((language, Err(e))) => {
+                logging::info(&format!("Generation in '{}' failed: {}", language, e));
+                failures.push(format!("{}: {}", language, e));
+            }
+            Err(e) => failures.push(format!("task failed: {}", e)),
+        }
+    }
+
+    if articles.is_empty() {
+        return Err(format!(
+            "Generation failed for every language: {}",
+            failures.join("; ")
+        ));
+    }
+    if !failures.is_empty() {
+        logging::warn(&format!("Some languages failed: {}", failures.join("; ")));
+    }
+    Ok(articles)
+}
+
+const QUALITY_RUBRIC: &str = "Rate the article on each criterion from 1 (very poor) to 10 (excellent):\n\
+- readability: clarity, sentence flow, absence of filler and repetition\n\
+- structure: logical heading hierarchy, sections that match their headings, useful tables or lists\n\
+- depth: concrete details, examples, pricing and comparisons rather than generic claims\n\
+- seo: title and meta description quality, keyword usage in headings, internal coherence\n\
+Give each criterion an integer score and a one or two sentence justification.";
+
+#[tauri::command]
+async fn score_article_quality(
+    app: tauri::AppHandle,
+    project_name: Option<String>,
+    article_html: String,
+) -> Result<QualityScore, String> {
+    if article_html.trim().is_empty() {
+        return Err("Article HTML is empty.".to_string());
+    }
+    if is_safe_mode(&app) {
+        logging::info("Safe mode enabled, returning placeholder quality score.");
+        let placeholder = || QualityCriterion {
+            score: 5,
+            justification: "[Safe mode] Not evaluated.".to_string(),
+        };
+        return Ok(QualityScore {
+            readability: placeholder(),
+            structure: placeholder(),
+            depth: placeholder(),
+            seo: placeholder(),
+            overall: 5.0,
+        });
+    }
+
+    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+        .await?
+        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+    let api_url = project_chat_completions_url(&app, project_name.as_deref()).await?;
+    let model = project_text_model(&app, project_name.as_deref()).await?;
+
+    let seo = extract_seo_metadata(&article_html).unwrap_or(SeoMetadata {
+        title: String::new(),
+        description: String::new(),
+    });
+    let headings: Vec<String> = Regex::new(r"(?is)<(h[1-3])[^>]*>(.*?)</h[1-3]>")
+        .map_err(|e| format!("Regex error: {}", e))?
+        .captures_iter(&article_html)
+        .map(|cap| {
+            format!(
+                "{}: {}",
+                cap[1].to_lowercase(),
+                strip_html_tags(&cap[2]).trim()
+            )
+        })
+        .collect();
+    let article_text = truncate_at_word_boundary(&strip_html_tags(&article_html), 12000);
+    let user_prompt = format!(
+        "{}\n\n\
+         Return ONLY a JSON object of the form {{\"readability\": {{\"score\": 7, \"justification\": \"...\"}}, \"structure\": {{...}}, \"depth\": {{...}}, \"seo\": {{...}}}}.\n\n\
+         Title: {}\nMeta description: {}\nHeadings:\n{}\n\nArticle text:\n{}",
+        QUALITY_RUBRIC,
+        seo.title,
+        seo.description,
+        headings.join("\n"),
+        article_text
+    );
+
+    let content = call_openai_chat(
+        &shared_client(&app),
+        &api_url,
+        &api_key,
+        &model,
+        "You are a strict editorial reviewer who scores articles against a rubric and outputs ONLY a valid JSON object.",
+        &user_prompt,
+        0.1,
+        request_timeout(None),
+    )
+    .await?;
+    let mut score: QualityScore =
+        parse_llm_json(&content).map_err(|e| format!("Failed to parse quality score: {}", e))?;
+
+    for criterion in [
+        &mut score.readability,
+        &mut score.structure,
+        &mut score.depth,
+        &mut score.seo,
+    ] {
+        criterion.score = criterion.score.clamp(1, 10);
+        criterion.justification = criterion.justification.trim().to_string();
+    }
+    score.overall = f64::from(
+        u32::from(score.readability.score)
+            + u32::from(score.structure.score)
+            + u32::from(score.depth.score)
+            + u32::from(score.seo.score),
+    ) / 4.0;
+    logging::info(&format!("Article quality score: {:.2}", score.overall));
+    Ok(score)
+}
+
+fn check_required_elements(
+    html: &str,
+    requirements: &RequirementSet,
+    official_url: Option<&str>,
+) -> Vec<Requirement> {
+    let has_match = |pattern: &str| {
+        Regex::new(pattern)
+            .map(|re| re.is_match(html))
+            .unwrap_or(false)
+    };
+    let mut missing = Vec::new();
+
+    if requirements.title && !has_match(r"(?is)<title[^>]*>\s*[^<\s][^<]*</title>") {
+        missing.push(Requirement::Title);
+    }
+    if requirements.meta_description
+        && !has_match(
+            r#"(?is)<meta\s[^>]*name\s*=\s*["']description["'][^>]*content\s*=\s*["']\s*[^"'\s]"#,
+        )
+        && !has_match(
+            r#"(?is)<meta\s[^>]*content\s*=\s*["']\s*[^"'\s][^>]*name\s*=\s*["']description["']"#,
+        )
+    {
+        missing.push(Requirement::MetaDescription);
+    }
+    if requirements.h1 && !has_match(r"(?is)<h1[\s>]") {
+        missing.push(Requirement::H1);
+    }
+    if requirements.min_h2_count > 0 {
+        let h2_count = Regex::new(r"(?i)<h2[\s>]")
+            .map(|re| re.find_iter(html).count())
+            .unwrap_or(0);
+        if h2_count < requirements.min_h2_count as usize {
+            missing.push(Requirement::H2);
+        }
+    }
+    // A table counts as styled when it carries inline styles/classes or a <style> block targets tables.
+    if requirements.styled_table {
+        let styled_inline = has_match(r#"(?is)<table\s[^>]*(style|class)\s*="#);
+        let styled_block = has_match(r"(?is)<table[\s>]")
+            && has_match(r"(?is)<style[^>]*>[^<]*\b(table|th|td)\b[^<]*</style>");
+        if !styled_inline && !styled_block {
+            missing.push(Requirement::StyledTable);
+        }
+    }
+    // Without a configured official URL there is no site to look for.
+    let official_host = official_url
+        .filter(|_| requirements.official_site_link)
+        .and_then(|url| reqwest::Url::parse(url.trim()).ok())
+        .and_then(|url| normalized_host(&url));
+    if let Some(official_host) = official_host {
+        let links_official_site = Regex::new(r#"(?is)<a\s[^>]*href\s*=\s*["']([^"']*)["']"#)
+            .expect("Invalid anchor href regex")
+            .captures_iter(html)
+            .any(|caps| {
+                reqwest::Url::parse(caps[1].trim())
+                    .ok()
+                    .and_then(|link| normalized_host(&link))
+                    .is_some_and(|host| host == official_host)
+            });
+        if !links_official_site {
+            missing.push(Requirement::OfficialSiteLink);
+        }
+    }
+    missing
+}
+
+#[tauri::command]
+async fn check_article_requirements(
+    app: tauri::AppHandle,
+    project_name: Option<String>,
+    article_html: String,
+) -> Result<Vec<Requirement>, String> {
+    let (requirements, official_url) = match project_name {
+        Some(name) => {
+            let settings = get_project_settings(app.clone(), name.clone())
+                .await?
+                .ok_or_else(|| format!("Settings not found for project '{}'", name))?;
+            (
+                settings.required_elements.unwrap_or_default(),
+                settings.official_url,
+            )
         }
+        None => (RequirementSet::default(), None),
+    };
+    let missing = check_required_elements(&article_html, &requirements, official_url.as_deref());
+    if missing.is_empty() {
+        logging::info("Article contains all required elements.");
     } else {
-        eprintln!(
-            "Rust: OpenAI API request for suggestions failed - Status: {}, Body:\n{}",
-            status, response_body_text
-        );
-        Err(format!(
-            "OpenAI API request failed with status {}: {}",
-            status, response_body_text
-        ))
+        logging::info(&format!(
+            "Article is missing required elements: {:?}",
+            missing
+        ));
     }
+    Ok(missing)
 }
 
 #[tauri::command]
-async fn get_wordpress_categories(
+async fn add_utm_params(
     app: tauri::AppHandle,
     project_name: String,
-) -> Result<Vec<WordPressCategory>, String> {
-    println!("Rust: Fetching WP categories for project: {}", project_name);
-
+    article_html: String,
+) -> Result<String, String> {
     let settings = get_project_settings(app.clone(), project_name.clone())
         .await?
         .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+    match settings.utm_params.as_ref().filter(|p| !p.is_empty()) {
+        Some(utm_params) => {
+            logging::info(&format!(
+                "Adding UTM parameters to outbound links for project '{}'.",
+                project_name
+            ));
+            Ok(apply_utm_params(
+                &article_html,
+                &settings.wordpress_url,
+                utm_params,
+            ))
+        }
+        None => {
+            logging::info(&format!(
+                "No UTM parameters configured for project '{}'.",
+                project_name
+            ));
+            Ok(article_html)
+        }
+    }
+}
 
-    if settings.wordpress_url.trim().is_empty()
-        || settings.wordpress_user.trim().is_empty()
-        || settings.wordpress_pass.trim().is_empty()
-    {
-        return Err(
-            "WordPress URL, User, and Application Password must be configured.".to_string(),
+#[tauri::command]
+async fn shift_article_headings(article_html: String, shift: i32) -> Result<String, String> {
+    logging::info(&format!("Shifting heading levels by {}.", shift));
+    Ok(shift_heading_levels(&article_html, shift))
+}
+
+const META_DESCRIPTION_MIN_CHARS: usize = 150;
+const META_DESCRIPTION_MAX_CHARS: usize = 160;
+
+#[tauri::command]
+async fn update_post_meta_description(
+    app: tauri::AppHandle,
+    project_name: String,
+    post_id: u32,
+) -> Result<String, String> {
+    logging::info(&format!(
+        "Regenerating meta description for WP post {} in project: {}",
+        post_id, project_name
+    ));
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+    let api_url = openai_chat_completions_url(settings.openai_base_url.as_deref());
+    let post = get_wordpress_post(app.clone(), project_name.clone(), post_id).await?;
+
+    let description = if is_safe_mode(&app) {
+        logging::info("Safe mode enabled, using placeholder meta description.");
+        truncate_at_word_boundary(
+            &format!("[Safe mode] {}", post.title),
+            META_DESCRIPTION_MAX_CHARS,
+        )
+    } else {
+        let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+            .await?
+            .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+        let article_text = truncate_at_word_boundary(&strip_html_tags(&post.content_raw), 6000);
+        let user_prompt = format!(
+            "Write an SEO meta description for the article below, in the article's language.\n\
+             It must be between {} and {} characters, summarize the article's main benefit, include its main keyword naturally and end with a complete sentence.\n\
+             Return ONLY the meta description text, without quotes.\n\n\
+             Title: {}\n\nArticle:\n{}",
+            META_DESCRIPTION_MIN_CHARS, META_DESCRIPTION_MAX_CHARS, post.title, article_text
         );
+        let content = call_openai_chat(
+            &shared_client(&app),
+            &api_url,
+            &api_key,
+            &settings.text_generation_model,
+            "You are an SEO specialist who writes concise, compelling meta descriptions.",
+            &user_prompt,
+            0.4,
+            request_timeout(None),
+        )
+        .await?;
+        let generated = content.trim().trim_matches('"').trim().to_string();
+        truncate_at_word_boundary(&generated, META_DESCRIPTION_MAX_CHARS)
+    };
+    if description.is_empty() {
+        return Err("The generated meta description was empty.".to_string());
+    }
+    let length = description.chars().count();
+    if length < META_DESCRIPTION_MIN_CHARS {
+        logging::warn(&format!(
+            "Meta description is only {} characters (target {}-{}).",
+            length, META_DESCRIPTION_MIN_CHARS, META_DESCRIPTION_MAX_CHARS
+        ));
+    }
+    if is_safe_mode(&app) {
+        return Ok(description);
     }
 
-    let categories_api_url = format!(
-        "{}/wp-json/wp/v2/categories?per_page=100",
-        settings.wordpress_url.trim_end_matches('/')
+    // The excerpt is used by most themes; the Yoast key only sticks when the site exposes it over REST.
+    let post_api_url = format!(
+        "{}/wp-json/wp/v2/posts/{}",
+        settings.wordpress_url.trim_end_matches('/'),
+        post_id
     );
-    println!("Rust: Fetching categories from URL: {}", categories_api_url);
-
-    let client = Client::new();
-    let response = client
-        .get(&categories_api_url)
+    let response = shared_client(&app)
+        .post(&post_api_url)
         .basic_auth(&settings.wordpress_user, Some(&settings.wordpress_pass))
-        .send()
+        .json(&serde_json::json!({
+            "excerpt": description,
+            "meta": { "_yoast_wpseo_metadesc": description }
+        }))
+        .timeout(request_timeout(None))
+        .send_recorded()
         .await
-        .map_err(|e| format!("Failed to send request to WordPress Categories API: {}", e))?;
+        .map_err(|e| format!("Failed to send request to WordPress Posts API: {}", e))?;
 
     let status = response.status();
-    println!(
-        "Rust: Received category response from WP (Status: {})",
+    logging::info(&format!(
+        "Received meta description update response from WP (Status: {})",
         status
-    );
-
-    if status.is_success() {
-        let categories = response
-            .json::<Vec<WordPressCategory>>()
-            .await
-            .map_err(|e| format!("Failed to parse WordPress categories JSON: {}", e))?;
-        println!(
-            "Rust: Successfully fetched {} categories.",
-            categories.len()
-        );
-        Ok(categories)
-    } else {
+    ));
+    if !status.is_success() {
         let error_text = response
             .text()
             .await
             .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
-        println!(
-            "Rust: Failed to fetch categories - Status: {}, Body: {}",
-            status, error_text
-        );
-        Err(format!(
-            "Failed to fetch categories (Status {}): {}",
-            status, error_text
-        ))
+        return Err(format!(
+            "Failed to update meta description for post {} (Status {}): {}",
+            post_id, status, error_text
+        ));
     }
+    logging::info(&format!(
+        "Updated meta description for post {} ({} characters).",
+        post_id, length
+    ));
+    Ok(description)
 }
 
-fn extract_body_content(html: &str) -> String {
-    println!(
-        "Rust: Full HTML input to extract_body_content (length {}): [FIRST 200 CHARS]{}[END_SNIPPET]",
-        html.len(),
-        html.chars().take(200).collect::<String>()
-    );
-    let body_regex = Regex::new(r"(?is)<body(?:[^>]*)>(.*?)</body>").expect("Invalid body regex");
-
-    if let Some(caps) = body_regex.captures(html) {
-        if let Some(content_match) = caps.get(1) {
-            println!("Rust: Extracted content from <body> tag.");
-            return content_match.as_str().trim().to_string();
-        } else {
-            println!("Rust: <body> tag matched but no content group found. Returning empty string from body.");
-            return String::new();
-        }
-    } else {
-        println!("Rust: <body> tag not found. Returning empty string as body content is specifically requested.");
-        return String::new();
-    }
+fn unescape_html_attribute(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
 }
 
 #[tauri::command]
-async fn publish_to_wordpress(
+async fn fit_meta_description(
     app: tauri::AppHandle,
-    request: PublishRequest,
+    project_name: Option<String>,
+    article_html: String,
 ) -> Result<String, String> {
-    println!(
-        "Rust: Received request to publish article for project: {}",
-        request.project_name
+    let meta_regex =
+        Regex::new(r#"(?is)(<meta\s+name=["']description["']\s+content=)(?:"([^"]*)"|'([^']*)')"#)
+            .expect("Invalid meta description regex");
+    let caps = meta_regex
+        .captures(&article_html)
+        .ok_or_else(|| "The article has no <meta name=\"description\"> tag.".to_string())?;
+    let current = unescape_html_attribute(
+        caps.get(2)
+            .or_else(|| caps.get(3))
+            .map(|m| m.as_str().trim())
+            .unwrap_or_default(),
     );
-    if let Some(cat_id) = request.category_id {
-        println!("Rust: Requested category ID: {}", cat_id);
+    let before_length = current.chars().count();
+    if (META_DESCRIPTION_MIN_CHARS..=META_DESCRIPTION_MAX_CHARS).contains(&before_length) {
+        logging::info(&format!(
+            "Meta description already fits ({} characters), nothing to do.",
+            before_length
+        ));
+        return Ok(article_html);
     }
-    match request.featured_media_id {
-        Some(fm_id) => println!("Rust: Requested Featured Media ID: {}", fm_id),
-        None => println!("Rust: No Featured Media ID requested (was None)."),
+    logging::info(&format!(
+        "Meta description is {} characters (target {}-{}), rewriting.",
+        before_length, META_DESCRIPTION_MIN_CHARS, META_DESCRIPTION_MAX_CHARS
+    ));
+
+    let fitted = if is_safe_mode(&app) {
+        logging::info("Safe mode enabled, truncating meta description instead of rewriting.");
+        truncate_at_word_boundary(&current, META_DESCRIPTION_MAX_CHARS)
+    } else {
+        let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+            .await?
+            .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+        let api_url = project_chat_completions_url(&app, project_name.as_deref()).await?;
+        let model = project_text_model(&app, project_name.as_deref()).await?;
+        let title = extract_seo_metadata(&article_html)
+            .map(|metadata| metadata.title)
+            .unwrap_or_default();
+        // Ask twice before truncating: cutting the tail would drop the call-to-action.
+        let mut candidate = current.clone();
+        for attempt in 1..=2 {
+            let length = candidate.chars().count();
+            let user_prompt = format!(
+                "Rewrite this meta description so it is between {min} and {max} characters long (it is currently {length}).\n\
+                 Keep the same language, meaning and main keyword, and keep its call-to-action at the end.\n\
+                 Return ONLY the meta description text, without quotes.\n\n\
+                 Article title: {title}\nMeta description: {candidate}",
+                min = META_DESCRIPTION_MIN_CHARS,
+                max = META_DESCRIPTION_MAX_CHARS,
+                length = length,
+                title = title,
+                candidate = candidate
+            );
+            let content = call_openai_chat(
+                &shared_client(&app),
+                &api_url,
+                &api_key,
+                &model,
+                "You are an SEO specialist who writes concise, compelling meta descriptions of a precise length.",
+                &user_prompt,
+                0.3,
+                request_timeout(None),
+            )
+            .await?;
+            let rewritten = content.trim().trim_matches('"').trim().to_string();
+            if rewritten.is_empty() {
+                return Err("The rewritten meta description was empty.".to_string());
+            }
+            candidate = rewritten;
+            let rewritten_length = candidate.chars().count();
+            if rewritten_length <= META_DESCRIPTION_MAX_CHARS {
+                break;
+            }
+            logging::info(&format!(
+                "Rewrite attempt {} gave {} characters (max {}).",
+                attempt, rewritten_length, META_DESCRIPTION_MAX_CHARS
+            ));
+        }
+        truncate_at_word_boundary(&candidate, META_DESCRIPTION_MAX_CHARS)
+    };
+
+    let after_length = fitted.chars().count();
+    logging::info(&format!(
+        "Meta description length: {} -> {} characters.",
+        before_length, after_length
+    ));
+    if after_length < META_DESCRIPTION_MIN_CHARS {
+        logging::warn(&format!(
+            "Meta description is still only {} characters (target {}-{}).",
+            after_length, META_DESCRIPTION_MIN_CHARS, META_DESCRIPTION_MAX_CHARS
+        ));
     }
-    if let Some(slug_val) = &request.slug {
-        println!("Rust: Requested slug: {:?}", slug_val);
+    let full_match = caps.get(0).expect("Regex match has a full capture");
+    let replacement = format!("{}\"{}\"", &caps[1], escape_html_attribute(&fitted));
+    let mut patched = String::with_capacity(article_html.len());
+    patched.push_str(&article_html[..full_match.start()]);
+    patched.push_str(&replacement);
+    patched.push_str(&article_html[full_match.end()..]);
+    Ok(patched)
+}
+
+#[tauri::command]
+async fn set_log_level(level: String) -> Result<(), String> {
+    let level = logging::LogLevel::parse(&level)?;
+    logging::set_level(level);
+    logging::info(&format!("Log level set to '{}'.", level.as_str()));
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_log_level() -> Result<String, String> {
+    Ok(logging::level().as_str().to_string())
+}
+
+#[tauri::command]
+async fn get_log_file_path() -> Result<Option<String>, String> {
+    Ok(logging::log_file().map(|path| path.to_string_lossy().to_string()))
+}
+
+const MAX_CALLOUTS: usize = 10;
+
+#[tauri::command]
+async fn generate_callouts(
+    app: tauri::AppHandle,
+    project_name: Option<String>,
+    article_html: String,
+    count: usize,
+) -> Result<Vec<Callout>, String> {
+    if count == 0 || count > MAX_CALLOUTS {
+        return Err(format!(
+            "Callout count must be between 1 and {}.",
+            MAX_CALLOUTS
+        ));
     }
-    if let Some(ref s_date) = request.schedule_date {
-        println!("Rust: Requested schedule date: {}", s_date);
+    let headings: Vec<String> = Regex::new(r"(?is)<h[2-3][^>]*>(.*?)</h[2-3]>")
+        .map_err(|e| format!("Regex error: {}", e))?
+        .captures_iter(&article_html)
+        .map(|cap| strip_html_tags(&cap[1]).trim().to_string())
+        .filter(|heading| !heading.is_empty())
+        .collect();
+
+    if is_safe_mode(&app) {
+        logging::info("Safe mode enabled, returning placeholder callouts.");
+        return Ok((0..count)
+            .map(|index| Callout {
+                text: format!("[Safe mode] Callout {}", index + 1),
+                near_heading: headings.get(index).cloned(),
+            })
+            .collect());
     }
-    println!(
-        "Rust: Requested publish status: {:?}",
-        request.publish_status
-    );
 
-    let settings = get_project_settings(app.clone(), request.project_name.clone())
+    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
         .await?
-        .ok_or_else(|| format!("Settings not found for project '{}'", request.project_name))?;
+        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+    let api_url = project_chat_completions_url(&app, project_name.as_deref()).await?;
+    let model = project_text_model(&app, project_name.as_deref()).await?;
+    let article_text = truncate_at_word_boundary(&strip_html_tags(&article_html), 12000);
+    let user_prompt = format!(
+        "Pick the {} most impactful sentences from the article below to use as pull-quotes, in the article's language.\n\
+         Copy each sentence verbatim (you may shorten it slightly), keep each under 200 characters and avoid picking two sentences from the same section.\n\
+         For each, give the heading of the section it belongs to, copied exactly from this list:\n{}\n\n\
+         Return ONLY a JSON array of objects with the keys \"text\" and \"near_heading\".\n\n\
+         Article:\n{}",
+        count,
+        headings
+            .iter()
+            .map(|heading| format!("- {}", heading))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        article_text
+    );
+    let content = call_openai_chat(
+        &shared_client(&app),
+        &api_url,
+        &api_key,
+        &model,
+        "You are an editor who selects pull-quotes for magazine layouts and outputs ONLY a valid JSON array.",
+        &user_prompt,
+        0.3,
+        request_timeout(None),
+    )
+    .await?;
+    let generated: Vec<Callout> =
+        parse_llm_json(&content).map_err(|e| format!("Failed to parse callouts: {}", e))?;
+
+    let callouts: Vec<Callout> = generated
+        .into_iter()
+        .filter(|callout| !callout.text.trim().is_empty())
+        .take(count)
+        .map(|callout| Callout {
+            text: callout.text.trim().to_string(),
+            // Headings the article doesn't contain can't be used for placement.
+            near_heading: callout
+                .near_heading
+                .map(|heading| heading.trim().to_string())
+                .filter(|heading| headings.contains(heading)),
+        })
+        .collect();
+    logging::info(&format!("Generated {} callout(s).", callouts.len()));
+    Ok(callouts)
+}
 
-    if settings.wordpress_url.trim().is_empty() {
-        return Err("WordPress URL is not configured in project settings.".to_string());
-    }
-    if settings.wordpress_user.trim().is_empty() {
-        return Err("WordPress User is not configured in project settings.".to_string());
+// Each pull-quote goes after the first paragraph of its section, or after the
+// first paragraph of the article when the heading is unknown.
+fn insert_callouts(html: &str, callouts: &[Callout]) -> String {
+    let heading_regex =
+        Regex::new(r"(?is)<h([2-3])[^>]*>(.*?)</h[2-3]\s*>").expect("Invalid heading regex");
+    let paragraph_end = Regex::new(r"(?i)</p\s*>").expect("Invalid paragraph regex");
+
+    let mut insertions: Vec<(usize, String)> = Vec::new();
+    for callout in callouts {
+        let search_from = callout
+            .near_heading
+            .as_deref()
+            .and_then(|wanted| {
+                heading_regex
+                    .captures_iter(html)
+                    .find(|caps| strip_html_tags(&caps[2]).trim() == wanted)
+            })
+            .map(|caps| caps.get(0).map_or(0, |m| m.end()))
+            .unwrap_or(0);
+        let Some(position) = paragraph_end.find_at(html, search_from).map(|m| m.end()) else {
+            continue;
+        };
+        insertions.push((
+            position,
+            format!(
+                "\n<blockquote class=\"pullquote\"><p>{}</p></blockquote>",
+                escape_html_attribute(&callout.text)
+            ),
+        ));
     }
-    if settings.wordpress_pass.trim().is_empty() {
-        return Err("WordPress Application Password is not configured.".to_string());
+
+    insertions.sort_by_key(|(position, _)| *position);
+    let mut output = String::with_capacity(html.len());
+    let mut last = 0;
+    for (position, block) in insertions {
+        output.push_str(&html[last..position]);
+        output.push_str(&block);
+        last = position;
     }
+    output.push_str(&html[last..]);
+    output
+}
 
-    let default_title = format!("Generated Article for {}", settings.tool_name);
-    let post_title = request
-        .article_title
-        .as_deref()
-        .filter(|s| !s.trim().is_empty())
-        .unwrap_or(&default_title);
+#[tauri::command]
+async fn insert_callouts_into_article(
+    article_html: String,
+    callouts: Vec<Callout>,
+) -> Result<String, String> {
+    logging::info(&format!(
+        "Inserting {} callout(s) into article.",
+        callouts.len()
+    ));
+    Ok(insert_callouts(&article_html, &callouts))
+}
 
-    println!("Rust: Using post title: '{}'", post_title);
+#[tauri::command]
+async fn consolidate_article_styles(article_html: String) -> Result<String, String> {
+    let consolidated = consolidate_styles(&article_html);
+    logging::info(&format!(
+        "Consolidated styles, article length {} -> {}.",
+        article_html.len(),
+        consolidated.len()
+    ));
+    Ok(consolidated)
+}
 
-    let final_content_for_wp = request.article_html.trim();
-    println!(
-        "Rust: Content for WordPress (already body-only). Length: {}",
-        final_content_for_wp.len()
-    );
-    if final_content_for_wp.is_empty() {
-        println!("Rust: Warning - content for WordPress is empty after processing.");
+#[tauri::command]
+async fn preview_backoff_schedule(config: UploadConfig) -> Result<Vec<u64>, String> {
+    check_upload_config(&config)?;
+    let schedule: Vec<u64> = compute_backoff_schedule(&config)
+        .iter()
+        .map(|wait| wait.as_secs())
+        .collect();
+    logging::info(&format!("Upload retry schedule (seconds): {:?}", schedule));
+    Ok(schedule)
+}
+
+#[tauri::command]
+async fn generate_brief(app: tauri::AppHandle, project_name: String) -> Result<String, String> {
+    logging::info(&format!(
+        "Generating article brief for project '{}'",
+        project_name
+    ));
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+    let api_url = openai_chat_completions_url(settings.openai_base_url.as_deref());
+    if settings.tool_name.trim().is_empty() {
+        return Err("The project has no tool name to brief on.".to_string());
     }
 
-    let api_url = format!(
-        "{}/wp-json/wp/v2/posts",
-        settings.wordpress_url.trim_end_matches('/')
+    let section_lines: Vec<String> = settings
+        .sections
+        .iter()
+        .map(|section| section.instructions.trim())
+        .filter(|instructions| !instructions.is_empty())
+        .enumerate()
+        .map(|(index, instructions)| format!("{}. {}", index + 1, instructions))
+        .collect();
+
+    if is_safe_mode(&app) {
+        logging::info("Safe mode enabled, returning placeholder brief.");
+        return Ok(format!(
+            "[Safe mode] Article about {} covering {} section(s) in about {} words.",
+            settings.tool_name,
+            section_lines.len(),
+            settings.target_word_count
+        ));
+    }
+
+    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+        .await?
+        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+    let user_prompt = format!(
+        "Write a one-paragraph brief (at most 120 words) describing the article that will be written from the plan below, so a stakeholder can approve its direction.\n\
+         Say who it is for, what angle it takes and what it will cover. Do not write the article itself.\n\
+         Write the brief in {}.\n\n\
+         Tool: {}\nGoal: {}\nTarget length: {} words\nSections:\n{}",
+        language_display_name(&settings.language),
+        settings.tool_name,
+        settings.article_goal_prompt,
+        settings.target_word_count,
+        section_lines.join("\n")
     );
-    println!("Rust: Posting to WordPress API URL: {}", api_url);
+    let brief = call_openai_chat(
+        &shared_client(&app),
+        &api_url,
+        &api_key,
+        &settings.text_generation_model,
+        "You are an editorial planner who summarizes upcoming articles in a single concise paragraph.",
+        &user_prompt,
+        0.5,
+        request_timeout(None),
+    )
+    .await?;
+    let brief = brief.trim().to_string();
+    if brief.is_empty() {
+        return Err("The model returned an empty brief.".to_string());
+    }
+    logging::info(&format!("Generated brief ({} words).", count_words(&brief)));
+    Ok(brief)
+}
 
-    let mut final_status = request
-        .publish_status
+#[tauri::command]
+async fn link_tool_name(
+    app: tauri::AppHandle,
+    project_name: String,
+    article_html: String,
+) -> Result<String, String> {
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+    let official_url = settings
+        .official_url
         .as_deref()
-        .filter(|s| ["publish", "draft", "pending"].contains(s))
-        .unwrap_or("publish");
-
-    let mut date_to_set: Option<&str> = None;
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .ok_or_else(|| format!("Project '{}' has no official URL configured.", project_name))?;
+    reqwest::Url::parse(official_url)
+        .map_err(|e| format!("Official URL '{}' is invalid: {}", official_url, e))?;
+    logging::info(&format!(
+        "Linking first mention of '{}' to {}.",
+        settings.tool_name, official_url
+    ));
+    Ok(link_first_mention(
+        &article_html,
+        &settings.tool_name,
+        official_url,
+    ))
+}
 
-    if let Some(schedule_str) = request.schedule_date.as_deref() {
-        if !schedule_str.trim().is_empty() {
-            final_status = "future";
-            date_to_set = Some(schedule_str);
-            println!(
-                "Rust: Scheduling post. Status set to 'future', date to '{}'",
-                schedule_str
-            );
+#[tauri::command]
+async fn summarize_version_changes(
+    app: tauri::AppHandle,
+    project_name: String,
+    id_a: u64,
+    id_b: u64,
+) -> Result<String, String> {
+    logging::info(&format!(
+        "Summarizing changes between versions {} and {} of project '{}'",
+        id_a, id_b, project_name
+    ));
+    // Stored versions are identified by their creation timestamp.
+    let history = get_article_history(app.clone(), project_name.clone()).await?;
+    let find_version = |id: u64| {
+        history
+            .iter()
+            .find(|version| version.created_at == id)
+            .ok_or_else(|| format!("Version {} not found for project '{}'.", id, project_name))
+    };
+    let (older, newer) = {
+        let a = find_version(id_a)?;
+        let b = find_version(id_b)?;
+        if a.created_at <= b.created_at {
+            (a, b)
+        } else {
+            (b, a)
         }
-    }
-
-    println!("Rust: Final publish status for WP API: '{}'", final_status);
-
-    let post_payload = WordPressPostPayload {
-        title: post_title,
-        content: final_content_for_wp,
-        status: final_status,
-        categories: request.category_id.map(|id| vec![id]),
-        featured_media: request.featured_media_id,
-        slug: request.slug.as_deref(),
-        date: date_to_set,
     };
 
-    let client = Client::new();
-    println!(
-        "Rust: Authenticating with WP User: {}",
-        settings.wordpress_user
-    );
-    let response = client
-        .post(&api_url)
-        .basic_auth(&settings.wordpress_user, Some(&settings.wordpress_pass))
-        .json(&post_payload)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to send request to WordPress API: {}", e))?;
+    let older_text = strip_html_tags(&older.article_html);
+    let newer_text = strip_html_tags(&newer.article_html);
+    let normalize = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if normalize(&older_text) == normalize(&newer_text) && older.title == newer.title {
+        logging::info("Versions have identical text, skipping OpenAI.");
+        return Ok("- No substantive changes (only formatting differs).".to_string());
+    }
+    if is_safe_mode(&app) {
+        logging::info("Safe mode enabled, returning placeholder change summary.");
+        return Ok(format!(
+            "- [Safe mode] Word count changed from {} to {}.",
+            count_words(&older_text),
+            count_words(&newer_text)
+        ));
+    }
 
-    let status = response.status();
-    println!(
-        "Rust: Received response from WordPress API (Status: {})",
-        status
+    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+        .await?
+        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
+    let api_url = project_chat_completions_url(&app, Some(&project_name)).await?;
+    let model = project_text_model(&app, Some(&project_name)).await?;
+    let user_prompt = format!(
+        "Compare the two versions of the article below and list the substantive changes as short bullet points (\"- ...\"), in the article's language.\n\
+         Cover added or removed sections, changed facts, prices, recommendations and conclusions.\n\
+         Ignore wording tweaks, formatting, punctuation and reordering that doesn't change meaning.\n\
+         Output ONLY the bullet list.\n\n\
+         --- OLDER VERSION (title: {}) ---\n{}\n\n--- NEWER VERSION (title: {}) ---\n{}",
+        older.title.as_deref().unwrap_or("untitled"),
+        truncate_at_word_boundary(&older_text, 15000),
+        newer.title.as_deref().unwrap_or("untitled"),
+        truncate_at_word_boundary(&newer_text, 15000)
     );
+    let summary = call_openai_chat(
+        &shared_client(&app),
+        &api_url,
+        &api_key,
+        &model,
+        "You are an editor who writes concise changelogs describing what changed between two versions of an article.",
+        &user_prompt,
+        0.2,
+        request_timeout(None),
+    )
+    .await?;
+    let summary = summary.trim().to_string();
+    if summary.is_empty() {
+        return Err("The model returned an empty change summary.".to_string());
+    }
+    logging::info(&format!(
+        "Change summary has {} line(s).",
+        summary.lines().count()
+    ));
+    Ok(summary)
+}
 
-    if status.is_success() || status.as_u16() == 201 {
-        let response_text = response.text().await.unwrap_or_default();
-        println!("Rust: WordPress API Success Response: {}", response_text);
-        let mut post_link_msg = "".to_string();
-        if let Ok(json_response) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if let Some(link) = json_response.get("link").and_then(|v| v.as_str()) {
-                post_link_msg = format!(" View post: {}", link);
-            }
-        }
-
-        let mut success_message = format!(
-            "Article successfully processed by WordPress with status '{}'",
-            final_status
-        );
-        if let Some(d) = date_to_set {
-            success_message.push_str(&format!(" and scheduled for {}", d));
-        }
-        if let Some(cat_id) = request.category_id {
-            success_message.push_str(&format!(" in category ID {}", cat_id));
-        }
-        if let Some(fm_id) = request.featured_media_id {
-            success_message.push_str(&format!(" with featured image ID {}", fm_id));
-        }
-        success_message.push_str("!");
-        success_message.push_str(&post_link_msg);
-
-        Ok(success_message)
-    } else {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
-        println!(
-            "Rust: WordPress API request failed - Status: {}, Body: {}",
-            status, error_text
-        );
-        Err(format!(
-            "WordPress API request failed with status {}: {}",
-            status, error_text
-        ))
+async fn verify_wordpress_credentials(
+    client: &Client,
+    wordpress_url: &str,
+    user: &str,
+    pass: &str,
+) -> Result<(), String> {
+    let me_url = format!(
+        "{}/wp-json/wp/v2/users/me?context=edit",
+        wordpress_url.trim_end_matches('/')
+    );
+    let response = client
+        .get(&me_url)
+        .basic_auth(user, Some(pass))
+        .timeout(request_timeout(None))
+        .send_recorded()
+        .await
+        .map_err(|e| format!("Failed to reach {}: {}", wordpress_url, e))?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
     }
+    let error_text = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Could not read WordPress error body".to_string());
+    let reason = match status {
+        StatusCode::UNAUTHORIZED => "the user name or application password is wrong".to_string(),
+        StatusCode::FORBIDDEN => {
+            "the user is not allowed to use the REST API (check its role or a security plugin)"
+                .to_string()
+        }
+        StatusCode::NOT_FOUND => {
+            "the REST API was not found at this URL (check the site URL and permalinks)".to_string()
+        }
+        _ => format!("unexpected status {}", status),
+    };
+    Err(format!(
+        "Credentials for '{}' on {} were rejected: {} (Status {}): {}",
+        user, wordpress_url, reason, status, error_text
+    ))
 }
 
 #[tauri::command]
-async fn upload_images_to_wordpress(
+async fn test_wordpress_connection(
     app: tauri::AppHandle,
-    request: UploadImageRequest,
-) -> Result<UploadImagesResponse, String> {
-    println!(
-        "Rust: Received request to upload {} images for project: {}",
-        request.image_urls.len(),
-        request.project_name
-    );
-
-    let settings = get_project_settings(app.clone(), request.project_name.clone())
+    project_name: String,
+) -> Result<(), String> {
+    logging::info(&format!(
+        "Testing WordPress connection for project: {}",
+        project_name
+    ));
+    let settings = get_project_settings(app.clone(), project_name.clone())
         .await?
-        .ok_or_else(|| format!("Settings not found for project '{}'", request.project_name))?;
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
 
     if settings.wordpress_url.trim().is_empty()
         || settings.wordpress_user.trim().is_empty()
@@ -1131,400 +11362,428 @@ async fn upload_images_to_wordpress(
         );
     }
 
-    let media_api_url = format!(
-        "{}/wp-json/wp/v2/media",
-        settings.wordpress_url.trim_end_matches('/')
-    );
-    println!("Rust: Uploading media to URL: {}", media_api_url);
-
-    let client = Client::new();
-    let mut upload_results: Vec<ImageUploadResult> = Vec::new();
-
-    for (index, image_url) in request.image_urls.iter().enumerate() {
-        println!("Rust: Processing image URL {}: {}", index + 1, image_url);
-        let result = process_single_image_upload(
-            &client,
-            &media_api_url,
-            &settings.wordpress_user,
-            &settings.wordpress_pass,
-            image_url,
-        )
-        .await;
-        upload_results.push(result);
-    }
-
-    println!("Rust: Finished processing all image uploads.");
-    Ok(UploadImagesResponse {
-        results: upload_results,
-    })
+    verify_wordpress_credentials(
+        &shared_client(&app),
+        &settings.wordpress_url,
+        &settings.wordpress_user,
+        &settings.wordpress_pass,
+    )
+    .await?;
+    logging::info(&format!(
+        "WordPress credentials for project '{}' are valid.",
+        project_name
+    ));
+    Ok(())
 }
 
-async fn process_single_image_upload(
-    client: &Client,
-    media_api_url: &str,
-    wp_user: &str,
-    wp_pass: &str,
-    image_url: &str,
-) -> ImageUploadResult {
-    const MAX_RETRIES: u32 = 4;
-    const INITIAL_BACKOFF_SECS: u64 = 10;
-
-    let download_response = match client.get(image_url).send().await {
-        Ok(resp) => resp,
-        Err(e) => {
-            let err_msg = format!("Failed to start download for {}: {}", image_url, e);
-            println!("Rust: Error - {}", err_msg);
-            return ImageUploadResult {
-                original_url: image_url.to_string(),
-                success: false,
-                error: Some(err_msg),
-                wordpress_media_id: None,
-                wordpress_media_url: None,
-            };
-        }
-    };
-
-    if !download_response.status().is_success() {
-        let err_msg = format!(
-            "Failed to download image from {}: Status {}",
-            image_url,
-            download_response.status()
-        );
-        println!("Rust: Error - {}", err_msg);
-        return ImageUploadResult {
-            original_url: image_url.to_string(),
-            success: false,
-            error: Some(err_msg),
-            wordpress_media_id: None,
-            wordpress_media_url: None,
-        };
+#[tauri::command]
+async fn rotate_wordpress_password(
+    app: tauri::AppHandle,
+    old_pass: String,
+    new_pass: String,
+    verify: Option<bool>,
+) -> Result<Vec<String>, String> {
+    if new_pass.trim().is_empty() {
+        return Err("The new password cannot be empty.".to_string());
+    }
+    if old_pass == new_pass {
+        return Err("The new password is the same as the old one.".to_string());
     }
 
-    let image_bytes = match download_response.bytes().await {
-        Ok(bytes) => bytes,
-        Err(e) => {
-            let err_msg = format!("Failed to read image bytes from {}: {}", image_url, e);
-            println!("Rust: Error - {}", err_msg);
-            return ImageUploadResult {
-                original_url: image_url.to_string(),
-                success: false,
-                error: Some(err_msg),
-                wordpress_media_id: None,
-                wordpress_media_url: None,
-            };
-        }
+    let store = app
+        .store(PathBuf::from(STORE_FILE))
+        .map_err(|e| format!("Failed to access store: {}", e))?;
+    let projects = {
+        let _guard = lock_store_writes()?;
+        store
+            .reload()
+            .map_err(|e| format!("Failed to load store: {}", e))?;
+        load_projects(&app, &store)?
     };
-    println!(
-        "Rust: Successfully downloaded {} bytes from {}",
-        image_bytes.len(),
-        image_url
-    );
 
-    let url_path = image_url.split('?').next().unwrap_or(image_url);
-    let url_path = url_path.split('#').next().unwrap_or(url_path);
+    let mut updated: Vec<String> = projects
+        .iter()
+        .filter(|(_, settings)| settings.wordpress_pass == old_pass)
+        .map(|(name, _)| name.clone())
+        .collect();
+    updated.sort_unstable();
+    if updated.is_empty() {
+        logging::info("No project uses the old WordPress password.");
+        return Ok(updated);
+    }
 
-    let filename = Path::new(url_path)
-        .file_name()
-        .and_then(|name| name.to_str())
-        .map(|name| name.to_string())
-        .filter(|name| !name.is_empty())
-        .unwrap_or_else(|| {
-            println!(
-                "Rust: Warning - Could not extract filename from '{}', using fallback.",
-                url_path
+    // Verify each distinct site/user pair before touching any project.
+    if verify.unwrap_or(false) {
+        let client = shared_client(&app);
+        let mut checked: HashSet<(String, String)> = HashSet::new();
+        for name in &updated {
+            let settings = &projects[name];
+            let key = (
+                settings.wordpress_url.trim_end_matches('/').to_string(),
+                settings.wordpress_user.clone(),
             );
-            format!(
-                "upload_{}.png",
-                std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs()
-            )
-        });
-
-    let mime_type = mime_guess::from_path(&filename)
-        .first_or_octet_stream()
-        .to_string();
-
-    println!(
-        "Rust: Using cleaned filename '{}' and guessed MIME type '{}' for upload.",
-        filename, mime_type
-    );
-
-    let content_disposition_value = format!("attachment; filename=\"{}\"", filename);
-
-    println!("Rust: Sending raw image data to WordPress...");
-    let mut attempts = 0;
-    loop {
-        attempts += 1;
-        println!("Rust: Upload attempt {} for {}", attempts, image_url);
-
-        let current_image_bytes = image_bytes.clone();
-        let upload_response = match client
-            .post(media_api_url)
-            .basic_auth(wp_user, Some(wp_pass))
-            .header(CONTENT_TYPE, &mime_type)
-            .header(CONTENT_DISPOSITION, &content_disposition_value)
-            .body(current_image_bytes)
-            .send()
-            .await
-        {
-            Ok(resp) => resp,
-            Err(e) => {
-                let err_msg = format!(
-                    "Failed to send upload request (Attempt {}): {}",
-                    attempts, e
-                );
-                println!("Rust: Error - {}", err_msg);
-                return ImageUploadResult {
-                    original_url: image_url.to_string(),
-                    success: false,
-                    error: Some(err_msg),
-                    wordpress_media_id: None,
-                    wordpress_media_url: None,
-                };
-            }
-        };
-
-        let status = upload_response.status();
-        println!(
-            "Rust: Received upload response (Attempt {}) - Status: {}",
-            attempts, status
-        );
-
-        match status {
-            StatusCode::OK | StatusCode::CREATED => {
-                match upload_response.json::<WordPressMediaResponse>().await {
-                    Ok(wp_media) => {
-                        println!(
-                            "Rust: Success (Attempt {}) - WP Media ID: {}, URL: {}",
-                            attempts, wp_media.id, wp_media.source_url
-                        );
-                        return ImageUploadResult {
-                            original_url: image_url.to_string(),
-                            success: true,
-                            error: None,
-                            wordpress_media_id: Some(wp_media.id),
-                            wordpress_media_url: Some(wp_media.source_url),
-                        };
-                    }
-                    Err(e) => {
-                        let err_msg = format!(
-                            "Failed to parse successful WP media response (Attempt {}): {}",
-                            attempts, e
-                        );
-                        println!("Rust: Error - {}", err_msg);
-                        return ImageUploadResult {
-                            original_url: image_url.to_string(),
-                            success: false,
-                            error: Some(err_msg),
-                            wordpress_media_id: None,
-                            wordpress_media_url: None,
-                        };
-                    }
-                }
+            if !checked.insert(key) {
+                continue;
             }
-            StatusCode::TOO_MANY_REQUESTS => {
-                if attempts >= MAX_RETRIES {
-                    let err_msg = format!(
-                        "Upload failed after {} attempts due to rate limiting (429).",
-                        attempts
-                    );
-                    println!("Rust: Error - {}", err_msg);
-                    let body_text = upload_response
-                        .text()
-                        .await
-                        .unwrap_or_else(|_| "Could not read 429 error body".to_string());
-                    println!("Rust: Last 429 Body: {}", body_text);
-                    return ImageUploadResult {
-                        original_url: image_url.to_string(),
-                        success: false,
-                        error: Some(err_msg),
-                        wordpress_media_id: None,
-                        wordpress_media_url: None,
-                    };
-                }
-
-                let wait_duration = match upload_response.headers().get(RETRY_AFTER) {
-                    Some(retry_header) => {
-                        if let Ok(seconds_str) = retry_header.to_str() {
-                            if let Ok(seconds) = seconds_str.parse::<u64>() {
-                                println!(
-                                    "Rust: Rate limited (429). Obeying Retry-After: {} seconds.",
-                                    seconds
-                                );
-                                Duration::from_secs(seconds.max(1))
-                            } else {
-                                let backoff_secs = INITIAL_BACKOFF_SECS * 2u64.pow(attempts - 1);
-                                println!("Rust: Rate limited (429). Couldn't parse Retry-After header '{}'. Using exponential backoff: {} seconds.", seconds_str, backoff_secs);
-                                Duration::from_secs(backoff_secs)
-                            }
-                        } else {
-                            let backoff_secs = INITIAL_BACKOFF_SECS * 2u64.pow(attempts - 1);
-                            println!("Rust: Rate limited (429). Invalid Retry-After header value. Using exponential backoff: {} seconds.", backoff_secs);
-                            Duration::from_secs(backoff_secs)
-                        }
-                    }
-                    None => {
-                        let backoff_secs = INITIAL_BACKOFF_SECS * 2u64.pow(attempts - 1);
-                        println!("Rust: Rate limited (429). No Retry-After header. Using exponential backoff: {} seconds.", backoff_secs);
-                        Duration::from_secs(backoff_secs)
-                    }
-                };
+            logging::info(&format!(
+                "Verifying new password for '{}' on {}",
+                settings.wordpress_user, settings.wordpress_url
+            ));
+            verify_wordpress_credentials(
+                &client,
+                &settings.wordpress_url,
+                &settings.wordpress_user,
+                &new_pass,
+            )
+            .await?;
+        }
+    }
 
-                println!("Rust: Waiting for {:?} before retry...", wait_duration);
-                sleep(wait_duration).await;
-            }
-            _ => {
-                let error_text = upload_response
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| "Could not read error body".to_string());
-                let err_msg = format!(
-                    "WordPress media upload failed (Attempt {}) with status {}: {}",
-                    attempts, status, error_text
-                );
-                println!("Rust: Error - {}", err_msg);
-                return ImageUploadResult {
-                    original_url: image_url.to_string(),
-                    success: false,
-                    error: Some(err_msg),
-                    wordpress_media_id: None,
-                    wordpress_media_url: None,
-                };
-            }
+    // Verification awaited, so re-read under the lock before writing; a project
+    // whose password changed meanwhile is left alone.
+    let _guard = lock_store_writes()?;
+    store
+        .reload()
+        .map_err(|e| format!("Failed to load store: {}", e))?;
+    let mut projects = load_projects(&app, &store)?;
+    updated.retain(|name| {
+        projects
+            .get(name)
+            .is_some_and(|settings| settings.wordpress_pass == old_pass)
+    });
+    for name in &updated {
+        if let Some(settings) = projects.get_mut(name) {
+            settings.wordpress_pass = new_pass.clone();
         }
     }
+    save_projects(&app, &store, &projects)?;
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+    logging::info(&format!(
+        "Rotated WordPress password for {} project(s): {:?}",
+        updated.len(),
+        updated
+    ));
+    Ok(updated)
 }
 
 #[tauri::command]
-async fn get_article_with_image_placeholders_llm(
-    app: tauri::AppHandle,
-    request: InsertPlaceholdersLLMRequest,
-) -> Result<InsertPlaceholdersLLMResponse, String> {
-    println!(
-        "Rust: Received request to get article with {} image placeholders via LLM.",
-        request.images.len()
-    );
-
-    if request.images.is_empty() {
-        println!("Rust: No images provided, returning original HTML.");
-        return Ok(InsertPlaceholdersLLMResponse {
-            article_with_placeholders: request.article_html,
-        });
+async fn check_keyword_in_title(
+    article_html: String,
+    primary_keyword: String,
+) -> Result<KeywordTitleCheck, String> {
+    if primary_keyword.trim().is_empty() {
+        return Err("Primary keyword cannot be empty.".to_string());
     }
+    let seo = extract_seo_metadata(&article_html)?;
+    let check = check_keyword_title_alignment(&seo.title, &primary_keyword);
+    logging::info(&format!(
+        "Keyword '{}' in title '{}': present={}, position={:?}, length={}",
+        primary_keyword.trim(),
+        check.title,
+        check.contains_keyword,
+        check.keyword_position,
+        check.title_length
+    ));
+    Ok(check)
+}
 
-    let api_key = get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+#[tauri::command]
+async fn check_article_heading_keywords(
+    app: tauri::AppHandle,
+    project_name: String,
+    article_html: String,
+) -> Result<Vec<HeadingKeywordResult>, String> {
+    let settings = get_project_settings(app.clone(), project_name.clone())
         .await?
-        .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?;
-
-    let image_list_string = request
-        .images
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+    let required: Vec<&str> = match &settings.heading_keywords {
+        Some(keywords) => keywords.iter().map(String::as_str).collect(),
+        None => DEFAULT_HEADING_KEYWORDS.to_vec(),
+    };
+    let results = check_heading_keywords(&article_html, &required);
+    let missing: Vec<&str> = results
         .iter()
-        .map(|img| {
-            format!(
-                "Image Placeholder: [INSERT_IMAGE_HERE_{}]\n   Context/Alt Text: {}\n   (URL: {}, WP ID: {})",
-                img.placeholder_index,
-                img.alt_text,
-                img.wordpress_media_url,
-                img.wordpress_media_id
-            )
-        })
-        .collect::<Vec<_>>()
-        .join("\n");
-
-    let system_prompt = "You are an AI assistant that modifies HTML articles. Your task is to insert unique image placeholders (like [INSERT_IMAGE_HERE_1], [INSERT_IMAGE_HERE_2], etc.) into the provided HTML article at semantically relevant locations based on the image's context/alt text and the surrounding article content. Ensure the final output is ONLY the complete, valid HTML code for the modified article including the placeholders, starting with <!DOCTYPE html> or <html> and ending with </html>. Do not include any explanations or preamble.";
-
-    let user_prompt = format!(
-        r#"Please modify the following HTML article by inserting the unique placeholders provided for each image.
-
-Placeholders and Context:
----
-{}
----
-
-HTML Article to Modify:
----
-{}
----
-
-Instructions:
-1. Analyze the article content and the context/alt text for each image placeholder.
-2. For each image placeholder (e.g., `[INSERT_IMAGE_HERE_1]`), insert it exactly as provided into the most semantically relevant location within the article body.
-3. Place placeholders where they enhance the content, ideally near paragraphs discussing related topics. Avoid breaking HTML structure. Do not place placeholders inside header tags (h1, h2, etc.) or within other HTML tags. Place them between paragraphs or block elements where an image would naturally fit.
-4. Ensure ALL provided placeholders are inserted exactly once.
-5. Return ONLY the complete, modified HTML article content including the inserted placeholders. Do not add any introductory text, explanations, or code fences.
-
-Modified HTML Article with Placeholders:"#,
-        image_list_string, request.article_html
-    );
-
-    println!("Rust: Sending request to LLM for image placeholder insertion.");
-    let model = "gpt-4o";
-
-    let client = reqwest::Client::new();
-    let api_url = "https://api.openai.com/v1/chat/completions";
-
-    let request_body = serde_json::json!({
-        "model": model,
-        "messages": [
-            { "role": "system", "content": system_prompt },
-            { "role": "user", "content": user_prompt }
-        ],
-        "temperature": 0.5
-    });
+        .filter(|result| !result.found)
+        .map(|result| result.keyword.as_str())
+        .collect();
+    if missing.is_empty() {
+        logging::info(&format!(
+            "All {} heading keyword(s) present.",
+            results.len()
+        ));
+    } else {
+        logging::info(&format!("Heading keywords missing: {:?}", missing));
+    }
+    Ok(results)
+}
 
-    let response = client
-        .post(api_url)
-        .bearer_auth(&api_key)
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to send request to OpenAI: {}", e))?;
+const LONG_ARTICLE_STYLE: &str =
+    "table { width: 100%; border-collapse: collapse; margin: 1.5em 0; }\n\
+th, td { border: 1px solid #ddd; padding: 8px 12px; text-align: left; }\n\
+th { background-color: #f5f5f5; }\n\
+tr:nth-child(even) td { background-color: #fafafa; }";
+// Characters of the previous section passed along for continuity.
+const LONG_ARTICLE_CONTEXT_CHARS: usize = 3000;
 
-    let status = response.status();
-    let response_body_text = response
-        .text()
-        .await
-        .map_err(|e| format!("Failed to read OpenAI response body: {}", e))?;
+#[tauri::command]
+async fn generate_long_article(
+    app: tauri::AppHandle,
+    project_name: String,
+) -> Result<String, String> {
+    let settings = get_project_settings(app.clone(), project_name.clone())
+        .await?
+        .ok_or_else(|| format!("Settings not found for project '{}'", project_name))?;
+    let api_url = openai_chat_completions_url(settings.openai_base_url.as_deref());
+    let sections: Vec<&str> = settings
+        .sections
+        .iter()
+        .map(|section| section.instructions.trim())
+        .filter(|instructions| !instructions.is_empty())
+        .collect();
+    if sections.is_empty() {
+        return Err(format!(
+            "Project '{}' has no sections to generate.",
+            project_name
+        ));
+    }
+    let total_sections = sections.len();
+    let words_per_section = (settings.target_word_count as usize / total_sections).max(150);
+    let language_name = language_display_name(&settings.language);
+    logging::info(&format!(
+        "Generating long article for '{}' in {} sections (~{} words each).",
+        project_name, total_sections, words_per_section
+    ));
+
+    let emit_progress = |section_index: usize, status: &str, word_count: usize| {
+        let progress = LongArticleProgress {
+            section_index,
+            total_sections,
+            status: status.to_string(),
+            word_count,
+        };
+        if let Err(e) = app.emit("long-article-progress", progress) {
+            logging::warn(&format!(
+                "Failed to emit long article progress event: {}",
+                e
+            ));
+        }
+    };
 
-    println!(
-        "Rust: Received LLM placeholder insertion response (Status: {})",
-        status
+    let safe_mode = is_safe_mode(&app);
+    let api_key = if safe_mode {
+        String::new()
+    } else {
+        get_api_key(app.clone(), STORE_KEY_TEXT_API.to_string())
+            .await?
+            .ok_or_else(|| "OpenAI API Key (textApiKey) not found in store.".to_string())?
+    };
+    let system_prompt = format!(
+        "You are a helpful assistant writing one section at a time of a detailed AI tool review article in {} HTML. You output ONLY an HTML fragment.",
+        language_name
     );
 
-    if status.is_success() {
-        match serde_json::from_str::<OpenAiApiResponse>(&response_body_text) {
-            Ok(parsed_response) => {
-                if let Some(choice) = parsed_response.choices.get(0) {
-                    println!("Rust: Successfully extracted HTML with placeholders from LLM.");
-                    Ok(InsertPlaceholdersLLMResponse {
-                        article_with_placeholders: choice.message.content.trim().to_string(),
-                    })
-                } else {
-                    Err("OpenAI response successful but 'choices' array was empty.".to_string())
-                }
-            }
-            Err(e) => {
-                eprintln!(
-                    "Rust: Error parsing LLM response JSON: {}. Using raw response body as fallback.",
-                    e
-                );
-                Ok(InsertPlaceholdersLLMResponse {
-                    article_with_placeholders: response_body_text.trim().to_string(),
+    let mut section_htmls: Vec<String> = Vec::new();
+    for (index, instructions) in sections.iter().enumerate() {
+        emit_progress(index, "started", 0);
+        let section_html = if safe_mode {
+            format!(
+                "<h2>Section {}</h2>\n<p>[Safe mode] {}</p>",
+                index + 1,
+                instructions
+            )
+        } else {
+            let previous_headings: Vec<String> = section_htmls
+                .iter()
+                .flat_map(|html| {
+                    Regex::new(r"(?is)<h2[^>]*>(.*?)</h2>")
+                        .expect("Invalid H2 regex")
+                        .captures_iter(html)
+                        .map(|caps| strip_html_tags(&caps[1]).trim().to_string())
+                        .collect::<Vec<_>>()
                 })
-            }
+                .collect();
+            let previous_text = section_htmls
+                .last()
+                .map(|html| {
+                    let text = strip_html_tags(html);
+                    let skip = text
+                        .chars()
+                        .count()
+                        .saturating_sub(LONG_ARTICLE_CONTEXT_CHARS);
+                    text.chars().skip(skip).collect::<String>()
+                })
+                .unwrap_or_default();
+            let position = if index == 0 {
+                "This is the first section: open with a short introduction that presents the tool and links to its official site."
+            } else if index + 1 == total_sections {
+                "This is the last section: end the article with a conclusion."
+            } else {
+                "This is a middle section: do not re-introduce the tool or conclude the article."
+            };
+            let user_prompt = format!(
+                "Article about: {tool}\nArticle goal: {goal}\nReference example: {example}\n\n\
+                 Write section {number} of {total}. {position}\n\
+                 Section instructions: {instructions}\n\n\
+                 Sections already written (headings): {headings}\n\
+                 End of the previous section, for continuity (do not repeat it):\n{previous}\n\n\
+                 Requirements:\n\
+                 - Write in {language}, at least {words} words.\n\
+                 - Start with an <h2> heading you choose from the instructions; use <h3>, <p>, <ul> and <table> inside.\n\
+                 - Output ONLY the section HTML: no <html>, <head>, <body>, <style> or <h1> tags.",
+                tool = settings.tool_name,
+                goal = settings.article_goal_prompt,
+                example = settings.example_url,
+                number = index + 1,
+                total = total_sections,
+                position = position,
+                instructions = instructions,
+                headings = if previous_headings.is_empty() {
+                    "none".to_string()
+                } else {
+                    previous_headings.join(" | ")
+                },
+                previous = if previous_text.is_empty() {
+                    "(none)".to_string()
+                } else {
+                    previous_text
+                },
+                language = language_name,
+                words = words_per_section
+            );
+            let content = call_openai_chat(
+                &shared_client(&app),
+                &api_url,
+                &api_key,
+                &settings.text_generation_model,
+                &system_prompt,
+                &user_prompt,
+                0.7,
+                Duration::from_secs(LONG_LLM_REQUEST_TIMEOUT_SECS),
+            )
+            .await
+            .map_err(|e| {
+                emit_progress(index, "failed", 0);
+                format!("Section {} failed: {}", index + 1, e)
+            })?;
+            let fragment = strip_code_fences(&content).to_string();
+            // Sections sometimes come back as full documents despite the instructions.
+            let fragment = if Regex::new(r"(?i)<body\b")
+                .expect("Invalid body regex")
+                .is_match(&fragment)
+            {
+                extract_body_content(&fragment)
+            } else {
+                fragment
+            };
+            Regex::new(r"(?is)<h1(?:[^>]*)>.*?</h1>")
+                .expect("Invalid H1 regex")
+                .replace_all(&fragment, "")
+                .trim()
+                .to_string()
+        };
+        let word_count = count_words(&strip_html_tags(&section_html));
+        logging::info(&format!(
+            "Section {}/{} generated ({} words).",
+            index + 1,
+            total_sections,
+            word_count
+        ));
+        emit_progress(index, "completed", word_count);
+        section_htmls.push(section_html);
+    }
+
+    let mut body = section_htmls.join("\n");
+    if settings.french_typography && is_french(&settings.language) {
+        body = apply_french_typography(&body);
+    }
+    if let Some(footer) = settings.footer_html.as_deref() {
+        body = append_footer_html(&body, footer);
+    }
+
+    let head = if safe_mode {
+        LongArticleHead {
+            title: format!("[Safe mode] {}", settings.tool_name),
+            meta_description: format!("[Safe mode] {}", settings.tool_name),
         }
     } else {
-        eprintln!(
-            "Rust: LLM placeholder insertion request failed - Status: {}, Body:\n{}",
-            status, response_body_text
+        let headings: Vec<String> = Regex::new(r"(?is)<h2[^>]*>(.*?)</h2>")
+            .expect("Invalid H2 regex")
+            .captures_iter(&body)
+            .map(|caps| strip_html_tags(&caps[1]).trim().to_string())
+            .collect();
+        let user_prompt = format!(
+            "Write the SEO <title> ({}-{} characters) and meta description ({}-{} characters, with a call-to-action) for a {} article about {} with these sections: {}.\n\
+             Return ONLY a JSON object with the keys \"title\" and \"meta_description\".",
+            TITLE_MIN_CHARS,
+            TITLE_MAX_CHARS,
+            META_DESCRIPTION_MIN_CHARS,
+            META_DESCRIPTION_MAX_CHARS,
+            language_name,
+            settings.tool_name,
+            headings.join(" | ")
         );
-        Err(format!(
-            "OpenAI API request failed with status {}: {}",
-            status, response_body_text
-        ))
+        let content = call_openai_chat(
+            &shared_client(&app),
+            &api_url,
+            &api_key,
+            &settings.text_generation_model,
+            "You are an SEO copywriter who outputs ONLY a valid JSON object.",
+            &user_prompt,
+            0.5,
+            request_timeout(None),
+        )
+        .await?;
+        parse_llm_json(&content).map_err(|e| format!("Failed to parse article head: {}", e))?
+    };
+
+    let document = format!(
+        "<!DOCTYPE html>\n<html lang=\"{lang}\">\n<head>\n<meta charset=\"UTF-8\">\n<title>{title}</title>\n<meta name=\"description\" content=\"{description}\">\n<style>\n{style}\n</style>\n</head>\n<body>\n<h1>{title}</h1>\n{body}\n</body>\n</html>",
+        lang = escape_html_attribute(&settings.language),
+        title = escape_html_attribute(head.title.trim()),
+        description = escape_html_attribute(head.meta_description.trim()),
+        style = LONG_ARTICLE_STYLE,
+        body = body
+    );
+    let document = consolidate_styles(&document);
+    let total_words = count_words(&strip_html_tags(&body));
+    logging::info(&format!(
+        "Long article assembled: {} words (target {}).",
+        total_words, settings.target_word_count
+    ));
+
+    // Safe-mode placeholders are not real drafts; keep them out of the history.
+    if !safe_mode {
+        if let Err(e) = record_article_version(
+            &app,
+            &project_name,
+            Some(head.title.trim().to_string()),
+            document.clone(),
+        ) {
+            logging::warn(&format!("Failed to record article history: {}", e));
+        }
     }
+    Ok(document)
+}
+
+#[tauri::command]
+async fn convert_html_to_markdown(article_html: String) -> Result<String, String> {
+    let has_body = Regex::new(r"(?i)<body\b")
+        .expect("Invalid body regex")
+        .is_match(&article_html);
+    let body = if has_body {
+        extract_body_content(&article_html)
+    } else {
+        article_html
+    };
+    let markdown = markdown::html_to_markdown(&body);
+    logging::info(&format!(
+        "Converted article to Markdown ({} -> {} characters).",
+        body.len(),
+        markdown.len()
+    ));
+    Ok(markdown)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -1532,18 +11791,23 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_store::Builder::default().build())
         .setup(|app| {
+            app.manage(build_http_client());
             let handle = app.handle().clone();
             let app_data_dir = handle
                 .path()
                 .app_data_dir()
                 .expect("Failed to get app data dir");
             let store_path = app_data_dir.join(STORE_FILE);
-            println!("Store path: {:?}", store_path);
+            logging::info(&format!("Store path: {:?}", store_path));
+            logging::set_log_file(Some(app_data_dir.join("logs").join("gen-article.log")));
 
             match app.store(store_path.clone()) {
                 Ok(store) => {
                     if !store_path.exists() {
-                        println!("Store file not found at {:?}, initializing...", store_path);
+                        logging::info(&format!(
+                            "Store file not found at {:?}, initializing...",
+                            store_path
+                        ));
                         store.set(STORE_KEY_TEXT_API.to_string(), JsonValue::Null);
                         store.set(STORE_KEY_IMAGE_API.to_string(), JsonValue::Null);
                         store.set(
@@ -1551,18 +11815,73 @@ pub fn run() {
                             serde_json::to_value(ProjectsMap::new()).unwrap_or(JsonValue::Null),
                         );
                         store.save().expect("Failed to save initialized store");
-                        println!("Store initialized and saved.");
+                        logging::info("Store initialized and saved.");
                     } else {
                         store.reload().unwrap_or_else(|e| {
-                            eprintln!("Error reloading existing store during setup: {}", e)
+                            logging::warn(&format!(
+                                "Error reloading existing store during setup: {}",
+                                e
+                            ))
                         });
-                        println!("Existing store found at {:?}.", store_path);
+                        logging::info(&format!("Existing store found at {:?}.", store_path));
+                    }
+
+                    if matches!(
+                        store.get(STORE_KEY_RECORD_HTTP),
+                        Some(JsonValue::Bool(true))
+                    ) {
+                        match http_recording_dir(&handle) {
+                            Ok(dir) => {
+                                logging::info(&format!(
+                                    "HTTP recording enabled, writing to {:?}",
+                                    dir
+                                ));
+                                http_recording::set_recording_dir(Some(dir));
+                            }
+                            Err(e) => {
+                                logging::warn(&format!("Failed to enable HTTP recording: {}", e))
+                            }
+                        }
+                    }
+
+                    let leftover_projects = store
+                        .get(STORE_KEY_PROJECTS)
+                        .is_some_and(|value| value.as_object().is_some_and(|map| !map.is_empty()));
+                    if uses_per_project_files(&store) && leftover_projects {
+                        logging::info("Finishing migration of projects to per-project files...");
+                        let _guard = lock_store_writes()?;
+                        if let Err(e) = migrate_project_storage(&handle, &store, true) {
+                            logging::warn(&format!(
+                                "Failed to migrate projects to per-project files: {}",
+                                e
+                            ));
+                        }
+                    }
+
+                    match requeue_interrupted_batch_items(&store) {
+                        Ok(0) => {}
+                        Ok(count) => {
+                            logging::info(&format!("Requeued {} interrupted batch item(s).", count))
+                        }
+                        Err(e) => logging::warn(&format!(
+                            "Failed to requeue interrupted batch items: {}",
+                            e
+                        )),
+                    }
+
+                    match get_user_agent_settings_from_store(&store) {
+                        Ok(settings) => user_agent::set_user_agents(settings),
+                        Err(e) => {
+                            logging::warn(&format!("Failed to load User-Agent settings: {}", e))
+                        }
                     }
                 }
                 Err(e) => {
                     panic!("Failed to access or build store during setup: {}", e);
                 }
             }
+
+            start_store_backup_task(handle);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -1573,13 +11892,102 @@ pub fn run() {
             get_project_settings,
             save_project_settings,
             delete_project,
+            rename_project,
+            duplicate_project,
             generate_ideogram_image,
             generate_full_article,
             suggest_image_prompts,
             publish_to_wordpress,
             get_wordpress_categories,
             upload_images_to_wordpress,
-            get_article_with_image_placeholders_llm
+            get_article_with_image_placeholders_llm,
+            validate_html,
+            get_wordpress_authors,
+            save_preferred_seed,
+            export_article_bundle,
+            probe_wordpress,
+            generate_social_meta,
+            get_pricing_table,
+            update_pricing_table,
+            estimate_generation_cost,
+            list_wordpress_posts,
+            suggest_internal_links,
+            get_safe_mode,
+            set_safe_mode,
+            get_article_history,
+            export_all_articles,
+            get_http_recording,
+            set_http_recording,
+            fix_french_typography,
+            get_wordpress_post,
+            generate_article_batch,
+            audit_projects,
+            generate_and_upload_featured_image,
+            validate_sections,
+            get_media_sizes,
+            get_store_backup_settings,
+            set_store_backup_settings,
+            list_store_backups,
+            restore_store_backup,
+            generate_title_variants,
+            diagnose_network,
+            extract_article_images,
+            get_batch_concurrency,
+            set_batch_concurrency,
+            normalize_sections,
+            get_image_pricing_table,
+            update_image_pricing_table,
+            estimate_image_cost,
+            get_wordpress_terms,
+            get_rate_limit_status,
+            preview_chat_messages,
+            merge_articles,
+            get_default_project,
+            set_default_project,
+            generate_social_posts,
+            check_similarity,
+            get_user_agent_settings,
+            set_user_agent_settings,
+            regenerate_article,
+            export_usage_log_csv,
+            list_store_keys,
+            resume_batch,
+            list_batches,
+            set_media_alt_text,
+            generate_images_for_prompts,
+            get_post_featured_image,
+            trim_article,
+            validate_image_prompt,
+            generate_multilingual,
+            score_article_quality,
+            unpublish_post,
+            check_article_requirements,
+            add_utm_params,
+            shift_article_headings,
+            find_existing_media,
+            update_post_meta_description,
+            set_log_level,
+            get_log_level,
+            get_log_file_path,
+            get_project_storage_mode,
+            set_project_storage_mode,
+            generate_callouts,
+            insert_callouts_into_article,
+            consolidate_article_styles,
+            preview_backoff_schedule,
+            generate_brief,
+            link_tool_name,
+            get_daily_upload_usage,
+            summarize_version_changes,
+            rotate_wordpress_password,
+            check_keyword_in_title,
+            check_article_heading_keywords,
+            generate_long_article,
+            fit_meta_description,
+            get_wordpress_tags,
+            replace_image_placeholders,
+            convert_html_to_markdown,
+            test_wordpress_connection
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1597,3 +12005,690 @@ fn default_text_model() -> String {
 fn default_word_count() -> u32 {
     1000
 }
+fn default_image_download_timeout_secs() -> u64 {
+    30
+}
+fn default_image_download_concurrency() -> usize {
+    4
+}
+fn default_language() -> String {
+    DEFAULT_LANGUAGE.to_string()
+}
+
+fn is_french(language: &str) -> bool {
+    language.trim().eq_ignore_ascii_case("fr")
+}
+
+impl FullArticleRequest {
+    fn language(&self) -> &str {
+        self.language.as_deref().unwrap_or(DEFAULT_LANGUAGE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct TitleAndMeta {
+        title: String,
+        meta_description: String,
+    }
+
+    #[test]
+    fn parse_llm_json_reads_fenced_json() {
+        let content = "```json\n{\"title\": \"T\", \"meta_description\": \"M\"}\n```";
+        assert_eq!(
+            parse_llm_json::<TitleAndMeta>(content).unwrap(),
+            TitleAndMeta {
+                title: "T".to_string(),
+                meta_description: "M".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_llm_json_ignores_prose_around_the_object() {
+        let content = "Here is the JSON you asked for:\n```json\n{\"title\": \"T\", \"meta_description\": \"M\"}\n```\nLet me know if you need changes.";
+        assert_eq!(parse_llm_json::<TitleAndMeta>(content).unwrap().title, "T");
+    }
+
+    #[test]
+    fn parse_llm_json_reads_arrays() {
+        let content = "Sure! [\"First title\", \"Second title\"] Hope this helps.";
+        assert_eq!(
+            parse_llm_json::<Vec<String>>(content).unwrap(),
+            vec!["First title".to_string(), "Second title".to_string()]
+        );
+    }
+
+    #[test]
+    fn parse_llm_json_handles_braces_inside_strings() {
+        let content =
+            "{\"title\": \"Use {braces} and ] here\", \"meta_description\": \"A } b { c\"}";
+        let parsed = parse_llm_json::<TitleAndMeta>(content).unwrap();
+        assert_eq!(parsed.title, "Use {braces} and ] here");
+        assert_eq!(parsed.meta_description, "A } b { c");
+    }
+
+    #[test]
+    fn parse_llm_json_handles_nested_objects() {
+        let content = "{\"outer\": {\"inner\": [1, 2]}}";
+        let parsed = parse_llm_json::<HashMap<String, HashMap<String, Vec<u32>>>>(content).unwrap();
+        assert_eq!(parsed["outer"]["inner"], vec![1, 2]);
+    }
+
+    #[test]
+    fn parse_llm_json_error_includes_a_snippet() {
+        let error = parse_llm_json::<TitleAndMeta>("I cannot help with that.").unwrap_err();
+        assert!(error.contains("Content was: I cannot help with that."));
+        assert!(!error.ends_with('…'));
+
+        let long = format!("{{\"title\": \"{}\"", "x".repeat(500));
+        let error = parse_llm_json::<TitleAndMeta>(&long).unwrap_err();
+        let snippet = error.split("Content was: ").nth(1).unwrap();
+        assert_eq!(snippet.chars().count(), LLM_JSON_SNIPPET_CHARS + 1);
+        assert!(snippet.ends_with('…'));
+    }
+
+    #[test]
+    fn tidy_html_removes_empty_paragraphs() {
+        let html =
+            "<h2>Intro</h2>\n<p></p>\n<p> &nbsp; </p>\n<p class=\"lead\"><br></p>\n<p>Text</p>";
+        assert_eq!(tidy_html(html), "<h2>Intro</h2>\n<p>Text</p>");
+    }
+
+    #[test]
+    fn tidy_html_removes_containers_left_empty() {
+        let html = "<section><p></p></section><ul>\n</ul><p>Kept</p>";
+        assert_eq!(tidy_html(html), "<p>Kept</p>");
+    }
+
+    #[test]
+    fn tidy_html_keeps_empty_containers_with_attributes() {
+        let html = "<div class=\"spacer\"></div><p>Text</p>";
+        assert_eq!(tidy_html(html), html);
+    }
+
+    #[test]
+    fn tidy_html_collapses_repeated_line_breaks() {
+        assert_eq!(
+            tidy_html("<p>One<br><br>Two<br/>\n<br />\n<BR>Three</p>"),
+            "<p>One<br>\nTwo<br>\nThree</p>"
+        );
+        assert_eq!(tidy_html("<p>One<br>Two</p>"), "<p>One<br>Two</p>");
+    }
+
+    #[test]
+    fn tidy_html_collapses_whitespace_gaps_between_tags() {
+        let html = "<h2>Title</h2>   \n\n\n   <p>First</p>\n\t\n<p>Second</p>";
+        assert_eq!(
+            tidy_html(html),
+            "<h2>Title</h2>\n<p>First</p>\n<p>Second</p>"
+        );
+    }
+
+    #[test]
+    fn tidy_html_keeps_spaces_between_inline_elements() {
+        let html = "<p><strong>Bold</strong> <em>italic</em></p>";
+        assert_eq!(tidy_html(html), html);
+    }
+
+    #[test]
+    fn tidy_html_keeps_style_block_untouched() {
+        let style = "<style>\n  .note p {}\n\n\n  p:empty { display: none; }\n</style>";
+        let html = format!("{}\n\n<p></p>\n<p>Body</p>", style);
+        assert_eq!(tidy_html(&html), format!("{}\n<p>Body</p>", style));
+    }
+
+    #[test]
+    fn extract_article_head_fields_unescapes_entities() {
+        let html = "<head><title>Tips &amp; Tricks</title>\
+                    <meta name=\"description\" content=\"Salt &amp; pepper\"></head>\
+                    <body><h1>Q&amp;A: <em>&quot;Why&quot;</em></h1></body>";
+        let fields = extract_article_head_fields(html);
+        assert_eq!(fields.seo_title.as_deref(), Some("Tips & Tricks"));
+        assert_eq!(fields.meta_description.as_deref(), Some("Salt & pepper"));
+        assert_eq!(fields.h1.as_deref(), Some("Q&A: \"Why\""));
+    }
+
+    #[test]
+    fn check_required_elements_wants_a_link_to_the_official_site() {
+        let requirements = RequirementSet {
+            title: false,
+            meta_description: false,
+            h1: false,
+            min_h2_count: 0,
+            styled_table: false,
+            official_site_link: true,
+        };
+        let official = Some("https://www.example.com/pricing");
+        let other_site = r#"<p><a href="https://wikipedia.org/wiki/Example">Wiki</a></p>"#;
+        assert_eq!(
+            check_required_elements(other_site, &requirements, official),
+            vec![Requirement::OfficialSiteLink]
+        );
+        let official_site = r#"<p><a href="https://example.com/">Example</a></p>"#;
+        assert!(check_required_elements(official_site, &requirements, official).is_empty());
+        assert!(check_required_elements(other_site, &requirements, None).is_empty());
+    }
+
+    #[test]
+    fn merge_project_settings_keeps_fields_missing_from_a_partial_save() {
+        let existing: ProjectSettings = serde_json::from_value(serde_json::json!({
+            "tool_name": "Notion",
+            "target_word_count": 1200,
+            "footer_html": "<p>Footer</p>",
+            "max_word_count": 1500,
+            "utm_params": { "utm_source": "blog" },
+            "french_typography": true,
+        }))
+        .unwrap();
+        let patch = serde_json::json!({
+            "wordpress_url": "https://example.com",
+            "tool_name": "Notion AI",
+            "target_word_count": 1000,
+        });
+        let merged = merge_project_settings(&existing, patch).unwrap();
+        assert_eq!(merged.wordpress_url, "https://example.com");
+        assert_eq!(merged.tool_name, "Notion AI");
+        assert_eq!(merged.target_word_count, 1000);
+        assert_eq!(merged.footer_html.as_deref(), Some("<p>Footer</p>"));
+        assert_eq!(merged.max_word_count, Some(1500));
+        assert!(merged.french_typography);
+        assert_eq!(
+            merged
+                .utm_params
+                .as_ref()
+                .and_then(|params| params.get("utm_source"))
+                .map(String::as_str),
+            Some("blog")
+        );
+    }
+
+    #[test]
+    fn merge_project_settings_rejects_non_objects() {
+        let existing: ProjectSettings = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(merge_project_settings(&existing, JsonValue::Null).is_err());
+    }
+
+    #[test]
+    fn apply_french_typography_spaces_high_punctuation_in_text() {
+        assert_eq!(
+            apply_french_typography("<p>Pourquoi ? Voici : la liste ; enfin !</p>"),
+            "<p>Pourquoi\u{202F}? Voici\u{202F}: la liste\u{202F}; enfin\u{202F}!</p>"
+        );
+        assert_eq!(
+            apply_french_typography("<p>Vraiment?!</p>"),
+            "<p>Vraiment\u{202F}?!</p>"
+        );
+    }
+
+    #[test]
+    fn apply_french_typography_leaves_attributes_and_code_alone() {
+        let html = r#"<p><a href="https://example.com/?q=1" title="Quoi ?">Lien</a></p>"#;
+        assert_eq!(apply_french_typography(html), html);
+        let code = "<p>Exemple <code>a ? b : c;</code> fin</p>";
+        assert_eq!(apply_french_typography(code), code);
+    }
+
+    #[test]
+    fn apply_french_typography_leaves_urls_and_entities_alone() {
+        let html = "<p>Voir https://example.com/a?b=1 ou Tom &amp; Jerry.</p>";
+        assert_eq!(apply_french_typography(html), html);
+    }
+
+    #[test]
+    fn is_french_ignores_case_and_whitespace() {
+        assert!(is_french("fr"));
+        assert!(is_french(" FR "));
+        assert!(!is_french("en"));
+    }
+
+    #[test]
+    fn replace_image_url_handles_escaped_query_strings() {
+        let html = concat!(
+            r#"<img src="https://cdn.example.com/a.png?sig=1&amp;exp=2">"#,
+            r#"<img src="https://cdn.example.com/b.png">"#
+        );
+        let urls = extract_image_urls(html);
+        assert_eq!(urls[0], "https://cdn.example.com/a.png?sig=1&exp=2");
+        let rewritten = replace_image_url(html, &urls[0], "images/image_1.png");
+        let rewritten = replace_image_url(&rewritten, &urls[1], "images/image_2.png");
+        assert_eq!(
+            rewritten,
+            r#"<img src="images/image_1.png"><img src="images/image_2.png">"#
+        );
+    }
+
+    fn sections(instructions: &[&str]) -> Vec<SectionDefinitionData> {
+        instructions
+            .iter()
+            .map(|text| SectionDefinitionData {
+                instructions: text.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn check_sections_groups_near_duplicate_instructions() {
+        let validation = check_sections(&sections(&[
+            "Describe the pricing plans and the free tier of the tool.",
+            "Explain how the API works.",
+            "describe the pricing plans and the free tier of this tool",
+        ]));
+        assert!(validation.is_valid);
+        assert_eq!(validation.duplicate_groups, vec![vec![0, 2]]);
+        assert_eq!(
+            section_duplicate_warnings(&validation),
+            vec!["Sections 1, 3 have near-duplicate instructions.".to_string()]
+        );
+    }
+
+    #[test]
+    fn check_sections_keeps_related_but_distinct_instructions_apart() {
+        let validation = check_sections(&sections(&[
+            "Describe the pricing plans of the tool.",
+            "Describe the main features of the tool.",
+        ]));
+        assert!(validation.duplicate_groups.is_empty());
+    }
+
+    #[test]
+    fn check_sections_reports_blank_sections() {
+        let validation = check_sections(&sections(&["Intro", "   ", "Conclusion"]));
+        assert!(!validation.is_valid);
+        assert_eq!(validation.blank_indices, vec![1]);
+        assert!(validation.duplicate_groups.is_empty());
+    }
+
+    #[test]
+    fn format_iso8601_utc_formats_unix_timestamps() {
+        assert_eq!(format_iso8601_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_iso8601_utc(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_iso8601_utc(1_700_000_000), "2023-11-14T22:13:20Z");
+    }
+
+    #[test]
+    fn build_article_jsonld_describes_the_post() {
+        let meta = SeoMetadata {
+            title: "Review".to_string(),
+            description: "A short review.".to_string(),
+        };
+        let script = build_article_jsonld(
+            &meta,
+            "Jane",
+            "2024-05-01T08:00:00Z",
+            Some(" https://example.com/a.png "),
+        );
+        let json = script
+            .strip_prefix("<script type=\"application/ld+json\">\n")
+            .and_then(|rest| rest.strip_suffix("\n</script>"))
+            .expect("JSON-LD should be wrapped in a script element");
+        let schema: JsonValue = serde_json::from_str(json).unwrap();
+        assert_eq!(schema["@type"], "BlogPosting");
+        assert_eq!(schema["headline"], "Review");
+        assert_eq!(schema["author"]["name"], "Jane");
+        assert_eq!(schema["datePublished"], "2024-05-01T08:00:00Z");
+        assert_eq!(schema["dateModified"], "2024-05-01T08:00:00Z");
+        assert_eq!(schema["image"], "https://example.com/a.png");
+    }
+
+    #[test]
+    fn build_article_jsonld_skips_blank_images_and_escapes_script_end() {
+        let meta = SeoMetadata {
+            title: "Tags like </script> in titles".to_string(),
+            description: String::new(),
+        };
+        let script = build_article_jsonld(&meta, "Jane", "2024-05-01T08:00:00Z", Some("  "));
+        assert!(!script.contains("\"image\""));
+        assert_eq!(script.matches("</script>").count(), 1);
+        assert!(script.contains("<\\/script>"));
+    }
+
+    fn utm(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn apply_utm_params_appends_to_existing_query_before_fragment() {
+        let html =
+            r#"<a href="https://tool.example.com/pricing?plan=pro&amp;ref=1#faq">Pricing</a>"#;
+        let result = apply_utm_params(
+            html,
+            "https://blog.example.org",
+            &utm(&[("utm_source", "blog"), ("utm_medium", "article")]),
+        );
+        assert_eq!(
+            result,
+            r#"<a href="https://tool.example.com/pricing?plan=pro&amp;ref=1&amp;utm_medium=article&amp;utm_source=blog#faq">Pricing</a>"#
+        );
+    }
+
+    #[test]
+    fn apply_utm_params_keeps_utm_keys_already_on_the_link() {
+        let html = r#"<a href='https://tool.example.com/?utm_source=newsletter'>Tool</a>"#;
+        let result = apply_utm_params(
+            html,
+            "https://blog.example.org",
+            &utm(&[("utm_source", "blog"), ("utm_medium", "article")]),
+        );
+        assert_eq!(
+            result,
+            r#"<a href='https://tool.example.com/?utm_source=newsletter&amp;utm_medium=article'>Tool</a>"#
+        );
+    }
+
+    #[test]
+    fn apply_utm_params_leaves_internal_and_relative_links_alone() {
+        let html = concat!(
+            r#"<a href="https://www.blog.example.org/other-post">Internal</a>"#,
+            r#"<a href="/about">Relative</a>"#,
+            r#"<a href="mailto:hello@example.org">Mail</a>"#
+        );
+        let result = apply_utm_params(
+            html,
+            "https://blog.example.org",
+            &utm(&[("utm_source", "blog")]),
+        );
+        assert_eq!(result, html);
+    }
+
+    #[test]
+    fn apply_outbound_link_rel_merges_with_an_existing_rel() {
+        let html = r#"<a href="https://tool.example.com" rel="NoFollow">Tool</a>"#;
+        let result = apply_outbound_link_rel(html, "https://blog.example.org", "nofollow noopener");
+        assert_eq!(
+            result,
+            r#"<a href="https://tool.example.com" rel="nofollow noopener">Tool</a>"#
+        );
+    }
+
+    #[test]
+    fn apply_outbound_link_rel_matches_mixed_case_markup() {
+        let html = r#"<A HREF='https://Tool.Example.com/page' REL='sponsored'>Tool</A>"#;
+        let result = apply_outbound_link_rel(html, "https://blog.example.org", "noopener");
+        assert_eq!(
+            result,
+            r#"<a HREF='https://Tool.Example.com/page' rel="sponsored noopener">Tool</A>"#
+        );
+    }
+
+    #[test]
+    fn apply_outbound_link_rel_adds_rel_to_external_links_only() {
+        let html = concat!(
+            r#"<a href="https://tool.example.com">Tool</a>"#,
+            r#"<a href="https://www.BLOG.example.org/post">Internal</a>"#,
+            r##"<a href="#intro">Anchor</a>"##
+        );
+        let result = apply_outbound_link_rel(html, "https://blog.example.org", "noopener");
+        assert_eq!(
+            result,
+            concat!(
+                r#"<a href="https://tool.example.com" rel="noopener">Tool</a>"#,
+                r#"<a href="https://www.BLOG.example.org/post">Internal</a>"#,
+                r##"<a href="#intro">Anchor</a>"##
+            )
+        );
+    }
+
+    #[test]
+    fn link_first_mention_skips_links_headings_and_attributes() {
+        let html = concat!(
+            "<h2>Notion review</h2>",
+            r#"<p><a href="https://reviews.example.com">Notion alternatives</a></p>"#,
+            r#"<p><img src="notion.png" alt="Notion logo"> Notion is fast. Notion is cheap.</p>"#
+        );
+        let result = link_first_mention(html, "notion", "https://www.notion.so");
+        assert_eq!(
+            result,
+            concat!(
+                "<h2>Notion review</h2>",
+                r#"<p><a href="https://reviews.example.com">Notion alternatives</a></p>"#,
+                r#"<p><img src="notion.png" alt="Notion logo"> <a href="https://www.notion.so">Notion</a> is fast. Notion is cheap.</p>"#
+            )
+        );
+    }
+
+    #[test]
+    fn link_first_mention_matches_whole_words_only() {
+        let html = "<p>Notionally, Notion works.</p>";
+        assert_eq!(
+            link_first_mention(html, "Notion", "https://notion.so"),
+            r#"<p>Notionally, <a href="https://notion.so">Notion</a> works.</p>"#
+        );
+    }
+
+    #[test]
+    fn link_first_mention_does_nothing_when_the_site_is_already_linked() {
+        let html = r#"<p>Notion is great. <a href="https://notion.so/pricing">Pricing</a></p>"#;
+        assert_eq!(
+            link_first_mention(html, "Notion", "https://www.notion.so"),
+            html
+        );
+    }
+
+    #[test]
+    fn split_html_into_chunks_splits_only_between_top_level_blocks() {
+        let html = concat!(
+            "<h2>Intro</h2><p>First paragraph of text.</p>",
+            "<div><p>Nested one.</p><p>Nested two.</p></div>",
+            "<p>Last paragraph.</p>"
+        );
+        let chunks = split_html_into_chunks(html, 50);
+        assert_eq!(
+            chunks,
+            vec![
+                "<h2>Intro</h2><p>First paragraph of text.</p>",
+                "<div><p>Nested one.</p><p>Nested two.</p></div>",
+                "<p>Last paragraph.</p>",
+            ]
+        );
+        assert_eq!(chunks.concat(), html);
+        assert_eq!(split_html_into_chunks(html, 10_000), vec![html]);
+    }
+
+    #[test]
+    fn split_html_into_chunks_preserves_placeholders() {
+        let html: String = (1..=6)
+            .map(|index| {
+                format!(
+                    "<p>Paragraph {} talks about the tool.</p>\n<p>[INSERT_IMAGE_HERE_{}]</p>\n",
+                    index, index
+                )
+            })
+            .collect();
+        let chunks = split_html_into_chunks(&html, 120);
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.concat(), html);
+        let placeholders = |text: &str| text.matches("[INSERT_IMAGE_HERE_").count();
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|chunk| placeholders(chunk))
+                .sum::<usize>(),
+            6
+        );
+    }
+
+    #[test]
+    fn assign_images_to_chunks_prefers_matching_text_then_spreads_ties() {
+        let chunks = vec![
+            "<p>Pricing plans and billing options.</p>".to_string(),
+            "<p>The editor supports collaboration features.</p>".to_string(),
+        ];
+        let image = |alt: &str, index: usize| ImageDetailsForLLM {
+            wordpress_media_url: format!("https://example.com/{}.png", index),
+            wordpress_media_id: index as u32,
+            alt_text: alt.to_string(),
+            placeholder_index: index,
+        };
+        let images = [
+            image("Screenshot of the collaboration editor", 1),
+            image("Pricing table", 2),
+            image("Logo", 3),
+            image("Mascot", 4),
+        ];
+        let refs: Vec<&ImageDetailsForLLM> = images.iter().collect();
+        assert_eq!(assign_images_to_chunks(&chunks, &refs), vec![1, 0, 0, 1]);
+    }
+
+    fn issue_kinds(validation: &HtmlValidation) -> Vec<(&str, Option<&str>)> {
+        validation
+            .issues
+            .iter()
+            .map(|issue| (issue.kind.as_str(), issue.tag.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn check_html_accepts_well_formed_fragments_with_void_tags() {
+        let validation =
+            check_html(r#"<div><p>Line<br>break<br/></p><img src="a.png"><!-- <p> --></div>"#);
+        assert!(validation.is_valid);
+        assert!(validation.has_single_root);
+        assert!(validation.issues.is_empty());
+    }
+
+    #[test]
+    fn check_html_reports_unclosed_tags() {
+        let validation = check_html("<div><p>Text</div><section>");
+        assert!(!validation.is_valid);
+        assert_eq!(
+            issue_kinds(&validation),
+            vec![
+                ("mismatched_nesting", Some("p")),
+                ("unclosed_tag", Some("section")),
+            ]
+        );
+        assert_eq!(validation.root_element_count, 2);
+    }
+
+    #[test]
+    fn check_html_reports_mis_nested_and_stray_closing_tags() {
+        let validation = check_html("<p><strong>Bold <em>both</strong></em></p></span>");
+        assert_eq!(
+            issue_kinds(&validation),
+            vec![
+                ("mismatched_nesting", Some("em")),
+                ("unexpected_closing_tag", Some("em")),
+                ("unexpected_closing_tag", Some("span")),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_html_rejects_closing_tags_on_void_elements() {
+        let validation = check_html("<p>One<br></br>Two</p><img src=\"a.png\"></img>");
+        assert_eq!(
+            issue_kinds(&validation),
+            vec![
+                ("void_closing_tag", Some("br")),
+                ("void_closing_tag", Some("img")),
+            ]
+        );
+    }
+
+    fn upload_config(initial: u64, multiplier: f64, max: u64, retries: u32) -> UploadConfig {
+        UploadConfig {
+            initial_backoff_secs: initial,
+            backoff_multiplier: multiplier,
+            max_backoff_secs: max,
+            max_retries: retries,
+        }
+    }
+
+    #[test]
+    fn compute_backoff_schedule_grows_and_caps() {
+        let schedule = compute_backoff_schedule(&upload_config(10, 2.0, 60, 5));
+        assert_eq!(
+            schedule,
+            [10, 20, 40, 60, 60]
+                .iter()
+                .map(|secs| Duration::from_secs(*secs))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn compute_backoff_schedule_has_one_wait_per_retry() {
+        assert!(compute_backoff_schedule(&upload_config(10, 2.0, 60, 0)).is_empty());
+        assert_eq!(
+            compute_backoff_schedule(&upload_config(5, 1.0, 5, 3)),
+            vec![Duration::from_secs(5); 3]
+        );
+        // Huge exponents still stay at the cap instead of overflowing.
+        let schedule = compute_backoff_schedule(&upload_config(1, 10.0, 30, MAX_UPLOAD_RETRIES));
+        assert_eq!(schedule.last(), Some(&Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn check_upload_config_accepts_defaults_and_bounds() {
+        assert!(check_upload_config(&upload_config(10, 2.0, 60, 3)).is_ok());
+        assert!(check_upload_config(&upload_config(1, 1.0, 1, MAX_UPLOAD_RETRIES)).is_ok());
+    }
+
+    #[test]
+    fn check_upload_config_rejects_invalid_values() {
+        assert!(check_upload_config(&upload_config(0, 2.0, 60, 3)).is_err());
+        assert!(check_upload_config(&upload_config(10, 0.5, 60, 3)).is_err());
+        assert!(check_upload_config(&upload_config(10, f64::NAN, 60, 3)).is_err());
+        assert!(check_upload_config(&upload_config(10, f64::INFINITY, 60, 3)).is_err());
+        assert!(check_upload_config(&upload_config(30, 2.0, 10, 3)).is_err());
+        assert!(check_upload_config(&upload_config(10, 2.0, 60, MAX_UPLOAD_RETRIES + 1)).is_err());
+    }
+
+    #[test]
+    fn make_tables_responsive_wraps_each_table_once() {
+        let html = "<p>Intro</p><table><tr><td>1</td></tr></table>";
+        let wrapped = make_tables_responsive(html);
+        assert_eq!(
+            wrapped,
+            r#"<p>Intro</p><div class="table-responsive" style="overflow-x:auto"><table><tr><td>1</td></tr></table></div>"#
+        );
+        assert_eq!(make_tables_responsive(&wrapped), wrapped);
+    }
+
+    #[test]
+    fn add_table_data_labels_uses_the_first_header_row_without_thead() {
+        let html = concat!(
+            "<table><tr><th>Plan</th><th><b>Price</b></th></tr>",
+            "<tr><td>Free</td><td>0 &euro;</td></tr>",
+            r#"<tr><td data-label="Custom">Pro</td><td>9 &euro;</td></tr></table>"#
+        );
+        assert_eq!(
+            add_table_data_labels(html),
+            concat!(
+                "<table><tr><th>Plan</th><th><b>Price</b></th></tr>",
+                r#"<tr><td data-label="Plan">Free</td><td data-label="Price">0 &euro;</td></tr>"#,
+                r#"<tr><td data-label="Custom">Pro</td><td data-label="Price">9 &euro;</td></tr></table>"#
+            )
+        );
+    }
+
+    #[test]
+    fn add_table_data_labels_follows_colspan() {
+        let html = concat!(
+            r#"<table><thead><tr><th>Feature</th><th colspan="2">Plans</th><th>Notes</th></tr></thead>"#,
+            r#"<tbody><tr><td>Export</td><td>Free</td><td>Pro</td><td>-</td></tr>"#,
+            r#"<tr><td colspan="3">All plans</td><td>Soon</td></tr></tbody></table>"#
+        );
+        assert_eq!(
+            add_table_data_labels(html),
+            concat!(
+                r#"<table><thead><tr><th>Feature</th><th colspan="2">Plans</th><th>Notes</th></tr></thead>"#,
+                r#"<tbody><tr><td data-label="Feature">Export</td><td data-label="Plans">Free</td><td data-label="Plans">Pro</td><td data-label="Notes">-</td></tr>"#,
+                r#"<tr><td colspan="3" data-label="Feature">All plans</td><td data-label="Notes">Soon</td></tr></tbody></table>"#
+            )
+        );
+    }
+
+    #[test]
+    fn add_table_data_labels_leaves_tables_without_headers_alone() {
+        let html = "<table><tr><td>1</td><td>2</td></tr></table>";
+        assert_eq!(add_table_data_labels(html), html);
+    }
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+static LOG_FILE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LogLevel {
+    Error = 0,
+    Warn = 1,
+    Info = 2,
+    Debug = 3,
+}
+
+impl LogLevel {
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "error" => Ok(LogLevel::Error),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" | "verbose" => Ok(LogLevel::Debug),
+            other => Err(format!(
+                "Unknown log level '{}'. Expected one of: error, warn, info, debug.",
+                other
+            )),
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => LogLevel::Error,
+            1 => LogLevel::Warn,
+            3 => LogLevel::Debug,
+            _ => LogLevel::Info,
+        }
+    }
+}
+
+pub(crate) fn set_level(level: LogLevel) {
+    LOG_LEVEL.store(level as u8, Ordering::SeqCst);
+}
+
+pub(crate) fn level() -> LogLevel {
+    LogLevel::from_u8(LOG_LEVEL.load(Ordering::SeqCst))
+}
+
+pub(crate) fn is_debug_enabled() -> bool {
+    level() >= LogLevel::Debug
+}
+
+pub(crate) fn set_log_file(path: Option<PathBuf>) {
+    if let Ok(mut current) = LOG_FILE.lock() {
+        *current = path;
+    }
+}
+
+pub(crate) fn log_file() -> Option<PathBuf> {
+    LOG_FILE.lock().ok().and_then(|path| path.clone())
+}
+
+// Keeps a single previous generation ("<name>.1") so the file never grows unbounded.
+fn rotate_if_needed(path: &PathBuf) {
+    let too_large = std::fs::metadata(path)
+        .map(|meta| meta.len() >= MAX_LOG_FILE_BYTES)
+        .unwrap_or(false);
+    if too_large {
+        let mut rotated = path.clone().into_os_string();
+        rotated.push(".1");
+        if let Err(e) = std::fs::rename(path, PathBuf::from(rotated)) {
+            eprintln!("Rust: Failed to rotate log file {:?}: {}", path, e);
+        }
+    }
+}
+
+fn append_to_log_file(line: &str) {
+    let Ok(guard) = LOG_FILE.lock() else {
+        return;
+    };
+    let Some(path) = guard.as_ref() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    rotate_if_needed(path);
+    let result = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", line));
+    if let Err(e) = result {
+        eprintln!("Rust: Failed to write log file {:?}: {}", path, e);
+    }
+}
+
+fn log(level: LogLevel, message: &str) {
+    if level > self::level() {
+        return;
+    }
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let line = format!(
+        "[{}] {} {}",
+        timestamp,
+        level.as_str().to_ascii_uppercase(),
+        message
+    );
+    if level <= LogLevel::Warn {
+        eprintln!("Rust: {}", line);
+    } else {
+        println!("Rust: {}", line);
+    }
+    append_to_log_file(&line);
+}
+
+pub(crate) fn error(message: &str) {
+    log(LogLevel::Error, message);
+}
+
+pub(crate) fn warn(message: &str) {
+    log(LogLevel::Warn, message);
+}
+
+pub(crate) fn info(message: &str) {
+    log(LogLevel::Info, message);
+}
+
+pub(crate) fn debug(message: &str) {
+    log(LogLevel::Debug, message);
+}
@@ -0,0 +1,513 @@
+use regex::Regex;
+
+// Their content never belongs in the exported article.
+const SKIPPED_ELEMENTS: &[&str] = &["head", "style", "script", "title", "noscript", "template"];
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "header",
+    "footer",
+    "aside",
+    "nav",
+    "figure",
+    "figcaption",
+    "pre",
+    "dl",
+    "dt",
+    "dd",
+];
+const LIST_INDENT: &str = "    ";
+
+struct ListState {
+    ordered: bool,
+    next_number: usize,
+}
+
+struct PendingLink {
+    start: usize,
+    href: String,
+}
+
+#[derive(Default)]
+struct TableState {
+    rows: Vec<Vec<String>>,
+    in_cell: bool,
+}
+
+#[derive(Default)]
+struct MarkdownWriter {
+    output: String,
+    inline: String,
+    lists: Vec<ListState>,
+    pending_marker: Option<String>,
+    links: Vec<PendingLink>,
+    heading_level: Option<usize>,
+    blockquote_depth: usize,
+    table: Option<TableState>,
+    in_code: bool,
+}
+
+impl MarkdownWriter {
+    fn quote_prefix(&self) -> String {
+        "> ".repeat(self.blockquote_depth)
+    }
+
+    fn start_block(&mut self) {
+        if self.output.is_empty() {
+            return;
+        }
+        while !self.output.ends_with("\n\n") {
+            self.output.push('\n');
+        }
+    }
+
+    fn start_line(&mut self) {
+        if !self.output.is_empty() && !self.output.ends_with('\n') {
+            self.output.push('\n');
+        }
+    }
+
+    fn take_inline(&mut self) -> String {
+        // Links left open across a block boundary can no longer be wrapped.
+        self.links.clear();
+        let text = collapse_whitespace(&self.inline);
+        self.inline.clear();
+        text
+    }
+
+    fn flush(&mut self) {
+        if self.table.as_ref().is_some_and(|table| table.in_cell) {
+            return;
+        }
+        let text = escape_line_start(&self.take_inline());
+        if text.is_empty() {
+            return;
+        }
+        let quote = self.quote_prefix();
+        if let Some(level) = self.heading_level {
+            self.start_block();
+            self.output
+                .push_str(&format!("{}{} {}\n", quote, "#".repeat(level), text));
+        } else if !self.lists.is_empty() {
+            let indent = LIST_INDENT.repeat(self.lists.len() - 1);
+            self.start_line();
+            match self.pending_marker.take() {
+                Some(marker) => self
+                    .output
+                    .push_str(&format!("{}{}{}{}\n", quote, indent, marker, text)),
+                None => self
+                    .output
+                    .push_str(&format!("{}{}{}{}\n", quote, indent, LIST_INDENT, text)),
+            }
+        } else {
+            self.start_block();
+            self.output.push_str(&format!("{}{}\n", quote, text));
+        }
+    }
+
+    fn open_list(&mut self, ordered: bool) {
+        self.flush();
+        if let Some(marker) = self.pending_marker.take() {
+            // An item whose only content is a nested list still needs its own marker.
+            let indent = LIST_INDENT.repeat(self.lists.len().saturating_sub(1));
+            let quote = self.quote_prefix();
+            self.start_line();
+            self.output
+                .push_str(&format!("{}{}{}\n", quote, indent, marker.trim_end()));
+        }
+        if self.lists.is_empty() {
+            self.start_block();
+        }
+        self.lists.push(ListState {
+            ordered,
+            next_number: 1,
+        });
+    }
+
+    fn close_list(&mut self) {
+        self.flush();
+        self.pending_marker = None;
+        self.lists.pop();
+        if self.lists.is_empty() {
+            self.start_block();
+        }
+    }
+
+    fn open_list_item(&mut self) {
+        self.flush();
+        let marker = match self.lists.last_mut() {
+            Some(list) if list.ordered => {
+                let marker = format!("{}. ", list.next_number);
+                list.next_number += 1;
+                marker
+            }
+            _ => "- ".to_string(),
+        };
+        self.pending_marker = Some(marker);
+    }
+
+    fn push_emphasis(&mut self, marker: &str, closing: bool) {
+        if closing {
+            // "**bold **" is not valid emphasis, so trailing spaces go after the marker.
+            let trimmed_len = self.inline.trim_end().len();
+            let trailing = self.inline.split_off(trimmed_len);
+            self.inline.push_str(marker);
+            self.inline.push_str(&trailing);
+        } else {
+            self.inline.push_str(marker);
+        }
+    }
+
+    fn close_link(&mut self) {
+        let Some(link) = self.links.pop() else {
+            return;
+        };
+        if link.start > self.inline.len() {
+            return;
+        }
+        let text = collapse_whitespace(&self.inline[link.start..]);
+        self.inline.truncate(link.start);
+        if link.href.is_empty() {
+            self.inline.push_str(&text);
+        } else {
+            self.inline.push_str(&format!("[{}]({})", text, link.href));
+        }
+    }
+
+    fn open_cell(&mut self) {
+        self.flush();
+        if let Some(table) = self.table.as_mut() {
+            if table.rows.is_empty() {
+                table.rows.push(Vec::new());
+            }
+            table.in_cell = true;
+        }
+        self.inline.clear();
+    }
+
+    fn close_cell(&mut self) {
+        let Some(table) = self.table.as_mut() else {
+            return;
+        };
+        if !table.in_cell {
+            return;
+        }
+        table.in_cell = false;
+        self.links.clear();
+        let text = collapse_whitespace(&self.inline).replace('|', "\\|");
+        self.inline.clear();
+        if let Some(table) = self.table.as_mut() {
+            if let Some(row) = table.rows.last_mut() {
+                row.push(text);
+            }
+        }
+    }
+
+    fn close_table(&mut self) {
+        self.close_cell();
+        let Some(table) = self.table.take() else {
+            return;
+        };
+        let rows: Vec<Vec<String>> = table
+            .rows
+            .into_iter()
+            .filter(|row| !row.is_empty())
+            .collect();
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if columns == 0 {
+            return;
+        }
+        let quote = self.quote_prefix();
+        self.start_block();
+        for (index, row) in rows.iter().enumerate() {
+            let mut cells = row.clone();
+            cells.resize(columns, String::new());
+            self.output
+                .push_str(&format!("{}| {} |\n", quote, cells.join(" | ")));
+            if index == 0 {
+                self.output
+                    .push_str(&format!("{}|{}\n", quote, " --- |".repeat(columns)));
+            }
+        }
+        self.start_block();
+    }
+
+    fn finish(mut self) -> String {
+        self.close_table();
+        self.flush();
+        let collapsed = Regex::new(r"\n{3,}")
+            .expect("Invalid newline regex")
+            .replace_all(self.output.trim(), "\n\n")
+            .to_string();
+        if collapsed.is_empty() {
+            collapsed
+        } else {
+            format!("{}\n", collapsed)
+        }
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Text nodes are escaped as they are read; markers the writer adds itself are not.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '[' | ']' | '`' | '<') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// Only the start of a block can turn plain text into a heading, quote or list.
+fn escape_line_start(text: &str) -> String {
+    if text.starts_with('#')
+        || text.starts_with('>')
+        || text.starts_with("- ")
+        || text.starts_with("+ ")
+    {
+        return format!("\\{}", text);
+    }
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    let rest = &text[digits..];
+    if digits > 0 && (rest.starts_with(". ") || rest.starts_with(") ")) {
+        return format!("{}\\{}", &text[..digits], rest);
+    }
+    text.to_string()
+}
+
+fn decode_entities(text: &str) -> String {
+    Regex::new(r"&(#[xX][0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);")
+        .expect("Invalid entity regex")
+        .replace_all(text, |caps: &regex::Captures| {
+            let entity = &caps[1];
+            let decoded = if let Some(hex) = entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+            {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(decimal) = entity.strip_prefix('#') {
+                decimal.parse::<u32>().ok().and_then(char::from_u32)
+            } else {
+                match entity {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    _ => None,
+                }
+            };
+            decoded
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .to_string()
+}
+
+fn attribute(attributes: &str, name: &str) -> Option<String> {
+    let pattern = format!(
+        r#"(?is)(?:^|\s){}\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#,
+        regex::escape(name)
+    );
+    Regex::new(&pattern)
+        .expect("Invalid attribute regex")
+        .captures(attributes)
+        .and_then(|caps| caps.get(1).or_else(|| caps.get(2)).or_else(|| caps.get(3)))
+        .map(|m| decode_entities(m.as_str().trim()))
+}
+
+pub(crate) fn html_to_markdown(html: &str) -> String {
+    let token_regex =
+        Regex::new(r"(?s)<!--.*?-->|<!DOCTYPE[^>]*>|<(/?)([a-zA-Z][a-zA-Z0-9]*)([^>]*)>|[^<]+|<")
+            .expect("Invalid HTML token regex");
+    let mut writer = MarkdownWriter::default();
+    let mut skipping: Option<(String, usize)> = None;
+
+    for caps in token_regex.captures_iter(html) {
+        let token = caps
+            .get(0)
+            .expect("Regex match has a full capture")
+            .as_str();
+        let Some(name) = caps.get(2).map(|m| m.as_str().to_ascii_lowercase()) else {
+            if skipping.is_none() && !token.starts_with("<!") {
+                let text = decode_entities(token);
+                if writer.in_code {
+                    writer.inline.push_str(&text);
+                } else {
+                    writer.inline.push_str(&escape_markdown(&text));
+                }
+            }
+            continue;
+        };
+        let closing = !caps[1].is_empty();
+        let attributes = caps.get(3).map_or("", |m| m.as_str());
+
+        if let Some((skipped_name, depth)) = skipping.as_mut() {
+            if *skipped_name == name {
+                if closing {
+                    *depth -= 1;
+                } else if !attributes.trim_end().ends_with('/') {
+                    *depth += 1;
+                }
+                if *depth == 0 {
+                    skipping = None;
+                }
+            }
+            continue;
+        }
+        if SKIPPED_ELEMENTS.contains(&name.as_str()) {
+            if !closing && !attributes.trim_end().ends_with('/') {
+                skipping = Some((name, 1));
+            }
+            continue;
+        }
+
+        match (name.as_str(), closing) {
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                writer.flush();
+                writer.heading_level = name[1..].parse().ok();
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => {
+                writer.flush();
+                writer.heading_level = None;
+            }
+            ("ul", false) => writer.open_list(false),
+            ("ol", false) => writer.open_list(true),
+            ("ul" | "ol", true) => writer.close_list(),
+            ("li", false) => writer.open_list_item(),
+            ("li", true) => writer.flush(),
+            ("blockquote", false) => {
+                writer.flush();
+                writer.start_block();
+                writer.blockquote_depth += 1;
+            }
+            ("blockquote", true) => {
+                writer.flush();
+                writer.blockquote_depth = writer.blockquote_depth.saturating_sub(1);
+                writer.start_block();
+            }
+            ("strong" | "b", _) => writer.push_emphasis("**", closing),
+            ("em" | "i", _) => writer.push_emphasis("*", closing),
+            ("code", _) => {
+                writer.inline.push('`');
+                writer.in_code = !closing;
+            }
+            ("a", false) => {
+                let start = writer.inline.len();
+                writer.links.push(PendingLink {
+                    start,
+                    href: attribute(attributes, "href").unwrap_or_default(),
+                });
+            }
+            ("a", true) => writer.close_link(),
+            ("img", false) => {
+                let src = attribute(attributes, "src").unwrap_or_default();
+                if !src.is_empty() {
+                    let alt = attribute(attributes, "alt").unwrap_or_default();
+                    writer.inline.push_str(&format!(
+                        " ![{}]({}) ",
+                        escape_markdown(&collapse_whitespace(&alt)),
+                        src
+                    ));
+                }
+            }
+            ("br", false) => writer.flush(),
+            ("hr", false) => {
+                writer.flush();
+                writer.start_block();
+                writer.output.push_str("---\n");
+            }
+            ("table", false) => {
+                writer.flush();
+                writer.table = Some(TableState::default());
+            }
+            ("table", true) => writer.close_table(),
+            ("tr", false) => {
+                writer.close_cell();
+                if let Some(table) = writer.table.as_mut() {
+                    table.rows.push(Vec::new());
+                }
+            }
+            ("td" | "th", false) => {
+                writer.close_cell();
+                writer.open_cell();
+            }
+            ("td" | "th" | "tr", true) => writer.close_cell(),
+            (block, _) if BLOCK_ELEMENTS.contains(&block) => writer.flush(),
+            _ => {}
+        }
+    }
+
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_nested_lists() {
+        let html = "<ul><li>One</li><li>Two<ol><li>Nested A</li><li>Nested B<ul><li>Deep</li></ul></li></ol></li><li>Three</li></ul>";
+        assert_eq!(
+            html_to_markdown(html),
+            "- One\n- Two\n    1. Nested A\n    2. Nested B\n        - Deep\n- Three\n"
+        );
+    }
+
+    #[test]
+    fn keeps_links_inside_headings() {
+        let html = "<h2><a href=\"https://example.com/tool\">Tool review</a></h2><p>Body</p>";
+        assert_eq!(
+            html_to_markdown(html),
+            "## [Tool review](https://example.com/tool)\n\nBody\n"
+        );
+    }
+
+    #[test]
+    fn moves_trailing_spaces_outside_emphasis() {
+        let html = "<p><strong>Bold </strong>text and <em>italic </em>text</p>";
+        assert_eq!(html_to_markdown(html), "**Bold** text and *italic* text\n");
+    }
+
+    #[test]
+    fn converts_images_with_alt_text() {
+        let html = "<p>Before <img src=\"https://example.com/a.png\" alt=\"A [chart]\"> after</p>";
+        assert_eq!(
+            html_to_markdown(html),
+            "Before ![A \\[chart\\]](https://example.com/a.png) after\n"
+        );
+    }
+
+    #[test]
+    fn strips_head_and_style() {
+        let html = "<html><head><title>T</title><style>p { color: red; }</style></head><body><style>.x {}</style><h1>Title</h1><p>Text</p></body></html>";
+        assert_eq!(html_to_markdown(html), "# Title\n\nText\n");
+    }
+
+    #[test]
+    fn escapes_markdown_in_text() {
+        let html =
+            "<p>5 * 3 = 15 and snake_case</p><p>#1 tool</p><p>1. not a list</p><p>[draft]</p>";
+        assert_eq!(
+            html_to_markdown(html),
+            "5 \\* 3 = 15 and snake\\_case\n\n\\#1 tool\n\n1\\. not a list\n\n\\[draft\\]\n"
+        );
+    }
+
+    #[test]
+    fn leaves_code_unescaped() {
+        assert_eq!(
+            html_to_markdown("<p>Use <code>a*b_c</code> here</p>"),
+            "Use `a*b_c` here\n"
+        );
+    }
+}
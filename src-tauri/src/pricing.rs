@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Prices are in USD per million tokens.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ModelPricing {
+    pub(crate) input_per_million: f64,
+    pub(crate) output_per_million: f64,
+}
+
+pub(crate) type PricingTable = HashMap<String, ModelPricing>;
+
+const DEFAULT_PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4.1", 2.00, 8.00),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1-nano", 0.10, 0.40),
+    ("gpt-4-turbo", 10.00, 30.00),
+    ("gpt-4", 30.00, 60.00),
+    ("gpt-3.5-turbo", 0.50, 1.50),
+];
+
+pub(crate) fn default_pricing_table() -> PricingTable {
+    DEFAULT_PRICES
+        .iter()
+        .map(|(model, input, output)| {
+            (
+                model.to_string(),
+                ModelPricing {
+                    input_per_million: *input,
+                    output_per_million: *output,
+                },
+            )
+        })
+        .collect()
+}
+
+pub(crate) fn merge_pricing_tables(base: &PricingTable, overrides: &PricingTable) -> PricingTable {
+    let mut merged = base.clone();
+    for (model, pricing) in overrides {
+        merged.insert(model.clone(), *pricing);
+    }
+    merged
+}
+
+pub(crate) fn lookup_pricing<'a>(table: &'a PricingTable, model: &str) -> Option<&'a ModelPricing> {
+    if let Some(pricing) = table.get(model) {
+        return Some(pricing);
+    }
+    // Dated snapshots (e.g. gpt-4o-2024-08-06) resolve to their base model.
+    table
+        .iter()
+        .filter(|(known, _)| {
+            model.starts_with(known.as_str()) && model[known.len()..].starts_with('-')
+        })
+        .max_by_key(|(known, _)| known.len())
+        .map(|(_, pricing)| pricing)
+}
+
+pub(crate) fn estimate_cost(
+    table: &PricingTable,
+    model: &str,
+    input_tokens: u64,
+    output_tokens: u64,
+) -> Option<f64> {
+    lookup_pricing(table, model).map(|pricing| {
+        (input_tokens as f64 * pricing.input_per_million
+            + output_tokens as f64 * pricing.output_per_million)
+            / 1_000_000.0
+    })
+}
+
+// Ideogram bills a flat price per image; durations are rough wall-clock averages.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ImagePricing {
+    pub(crate) cost_per_image: f64,
+    pub(crate) seconds_per_image: f64,
+}
+
+pub(crate) type ImagePricingTable = HashMap<String, ImagePricing>;
+
+const DEFAULT_IMAGE_PRICES: &[(&str, f64, f64)] = &[
+    ("TURBO", 0.03, 5.0),
+    ("DEFAULT", 0.06, 10.0),
+    ("QUALITY", 0.09, 20.0),
+];
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct ImageCostEstimate {
+    pub(crate) rendering_speed: String,
+    pub(crate) num_images: u32,
+    pub(crate) cost_per_image: f64,
+    pub(crate) estimated_cost_usd: f64,
+    pub(crate) estimated_seconds: f64,
+}
+
+pub(crate) fn default_image_pricing_table() -> ImagePricingTable {
+    DEFAULT_IMAGE_PRICES
+        .iter()
+        .map(|(speed, cost, seconds)| {
+            (
+                speed.to_string(),
+                ImagePricing {
+                    cost_per_image: *cost,
+                    seconds_per_image: *seconds,
+                },
+            )
+        })
+        .collect()
+}
+
+pub(crate) fn merge_image_pricing_tables(
+    base: &ImagePricingTable,
+    overrides: &ImagePricingTable,
+) -> ImagePricingTable {
+    let mut merged = base.clone();
+    for (speed, pricing) in overrides {
+        merged.insert(speed.to_ascii_uppercase(), *pricing);
+    }
+    merged
+}
+
+pub(crate) fn estimate_image_cost(
+    table: &ImagePricingTable,
+    num_images: u32,
+    rendering_speed: &str,
+) -> Option<ImageCostEstimate> {
+    let rendering_speed = rendering_speed.trim().to_ascii_uppercase();
+    table
+        .get(&rendering_speed)
+        .map(|pricing| ImageCostEstimate {
+            num_images,
+            cost_per_image: pricing.cost_per_image,
+            estimated_cost_usd: pricing.cost_per_image * num_images as f64,
+            estimated_seconds: pricing.seconds_per_image * num_images as f64,
+            rendering_speed,
+        })
+}
@@ -0,0 +1,99 @@
+use reqwest::header::{HeaderValue, USER_AGENT};
+use reqwest::{Request, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+const LLM_API_HOSTS: &[&str] = &[
+    "api.openai.com",
+    "api.ideogram.ai",
+    "generativelanguage.googleapis.com",
+];
+
+static USER_AGENTS: Mutex<Option<UserAgentSettings>> = Mutex::new(None);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct UserAgentSettings {
+    #[serde(default)]
+    pub(crate) default: Option<String>,
+    #[serde(default)]
+    pub(crate) wordpress: Option<String>,
+    #[serde(default)]
+    pub(crate) image_downloads: Option<String>,
+    #[serde(default)]
+    pub(crate) llm_apis: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum UserAgentTarget {
+    WordPress,
+    ImageDownload,
+    LlmApi,
+}
+
+impl UserAgentSettings {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("default", &self.default),
+            ("wordpress", &self.wordpress),
+            ("image_downloads", &self.image_downloads),
+            ("llm_apis", &self.llm_apis),
+        ] {
+            if let Some(value) = value {
+                HeaderValue::from_str(value.trim())
+                    .map_err(|e| format!("Invalid '{}' User-Agent {:?}: {}", name, value, e))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn for_target(&self, target: UserAgentTarget) -> Option<&str> {
+        let specific = match target {
+            UserAgentTarget::WordPress => &self.wordpress,
+            UserAgentTarget::ImageDownload => &self.image_downloads,
+            UserAgentTarget::LlmApi => &self.llm_apis,
+        };
+        specific
+            .as_deref()
+            .or(self.default.as_deref())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+pub(crate) fn set_user_agents(settings: UserAgentSettings) {
+    if let Ok(mut current) = USER_AGENTS.lock() {
+        *current = Some(settings);
+    }
+}
+
+fn configured_user_agent(target: UserAgentTarget) -> Option<HeaderValue> {
+    let current = USER_AGENTS.lock().ok()?;
+    let value = current.as_ref()?.for_target(target)?;
+    HeaderValue::from_str(value).ok()
+}
+
+// Requests going through send_recorded are either LLM/image APIs or WordPress.
+pub(crate) fn apply_to_request(request: &mut Request) {
+    if request.headers().contains_key(USER_AGENT) {
+        return;
+    }
+    let is_llm_api = request
+        .url()
+        .host_str()
+        .is_some_and(|host| LLM_API_HOSTS.contains(&host));
+    let target = if is_llm_api {
+        UserAgentTarget::LlmApi
+    } else {
+        UserAgentTarget::WordPress
+    };
+    if let Some(value) = configured_user_agent(target) {
+        request.headers_mut().insert(USER_AGENT, value);
+    }
+}
+
+pub(crate) fn apply_to_builder(builder: RequestBuilder, target: UserAgentTarget) -> RequestBuilder {
+    match configured_user_agent(target) {
+        Some(value) => builder.header(USER_AGENT, value),
+        None => builder,
+    }
+}
@@ -56,6 +56,7 @@ interface FullArticleRequest {
     sections: SectionDefinitionData[];
     model: string;
     target_word_count: number;
+    project_name: string;
 }
 
 // --- NEW Response Interface for Suggestions ---
@@ -66,6 +67,7 @@ interface SuggestImagePromptsResponse {
 // --- NEW Request Interface for Suggestions ---
 interface SuggestImagePromptsRequest {
     article_text: string;
+    project_name: string;
 }
 
 // --- NEW Type for storing image generation results per prompt ---
@@ -107,6 +109,7 @@ interface ImageUploadResult {
     error?: string;
     wordpress_media_id?: number;
     wordpress_media_url?: string;
+    dry_run?: boolean;
 }
 
 // --- NEW Interface matching Rust's UploadImagesResponse ---
@@ -124,6 +127,7 @@ interface ImageDetailsForLLM {
 interface InsertPlaceholdersLLMRequest {
     article_html: string;
     images: ImageDetailsForLLM[];
+    project_name: string;
 }
 interface InsertPlaceholdersLLMResponse {
     article_with_placeholders: string;
@@ -383,8 +387,12 @@ function ProjectPage({ projectName, displayFeedback, onBack, onDelete }: Project
 
      try {
          // Send the correct structure expected by Rust backend
-         await invoke("save_project_settings", { name: projectName, settings: settingsToSave });
-         displayFeedback(`Base settings for '${projectName}' saved!`, "success");
+         const warnings = await invoke<string[]>("save_project_settings", { name: projectName, settings: settingsToSave });
+         if (warnings.length > 0) {
+             displayFeedback(`Base settings for '${projectName}' saved with warnings: ${warnings.join(" ")}`, "warning");
+         } else {
+             displayFeedback(`Base settings for '${projectName}' saved!`, "success");
+         }
          // Update the main settings state AFTER successful save
          setCurrentSettings(settingsToSave);
      } catch (err) {
@@ -430,8 +438,12 @@ function ProjectPage({ projectName, displayFeedback, onBack, onDelete }: Project
 
        try {
             // Send the correct structure expected by Rust backend
-           await invoke("save_project_settings", { name: projectName, settings: settingsToSave });
-           displayFeedback(`Article configuration for '${projectName}' saved!`, "success");
+           const warnings = await invoke<string[]>("save_project_settings", { name: projectName, settings: settingsToSave });
+           if (warnings.length > 0) {
+               displayFeedback(`Article configuration for '${projectName}' saved with warnings: ${warnings.join(" ")}`, "warning");
+           } else {
+               displayFeedback(`Article configuration for '${projectName}' saved!`, "success");
+           }
            // Update local state to reflect ALL saved settings
            setCurrentSettings(settingsToSave);
        } catch (err) {
@@ -534,6 +546,7 @@ function ProjectPage({ projectName, displayFeedback, onBack, onDelete }: Project
           sections: sectionDefinitions.map(({ id, ...rest }) => rest),
           model: textModelInput, // Send selected model
           target_word_count: wordCountNum, // Send parsed word count
+          project_name: projectName, // Lets the backend apply the project's settings
       };
 
       try {
@@ -577,7 +590,7 @@ function ProjectPage({ projectName, displayFeedback, onBack, onDelete }: Project
     displayFeedback("Suggesting image prompts...", "warning");
 
     try {
-        const request: SuggestImagePromptsRequest = { article_text: generatedArticle };
+        const request: SuggestImagePromptsRequest = { article_text: generatedArticle, project_name: projectName };
         const response = await invoke<SuggestImagePromptsResponse>("suggest_image_prompts", { request });
         setSuggestedPrompts(response.prompts);
 
@@ -765,7 +778,11 @@ function ProjectPage({ projectName, displayFeedback, onBack, onDelete }: Project
            displayFeedback(successMessage, "success");
        } catch (err) {
            console.error("Failed to publish to WordPress:", err);
-           const errorMsg = err instanceof Error ? err.message : String(err);
+           const errorMsg = err instanceof Error
+               ? err.message
+               : typeof err === "object" && err !== null && "message" in err
+                   ? `${(err as { message: string }).message}${"code" in err ? ` (${(err as { code: string }).code})` : ""}`
+                   : String(err);
            displayFeedback(`Error publishing to WordPress: ${errorMsg}`, "error");
        } finally {
            setIsPublishing(false);
@@ -926,7 +943,8 @@ function ProjectPage({ projectName, displayFeedback, onBack, onDelete }: Project
 
                 const placeholderRequest: InsertPlaceholdersLLMRequest = {
                     article_html: generatedArticle, // Send current article content
-                    images: imagesForLLM
+                    images: imagesForLLM,
+                    project_name: projectName
                 };
 
                 console.log("Sending request for article with placeholders:", placeholderRequest);